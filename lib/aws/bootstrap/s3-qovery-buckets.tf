@@ -33,3 +33,24 @@ resource "aws_kms_key" "s3_kubeconfig_kms_encryption" {
     }
   )
 }
+
+// S3 bucket to store self-hosted databases backups
+resource "aws_s3_bucket" "database_backups_bucket" {
+  bucket = var.s3_bucket_database_backups
+  acl    = "private"
+  force_destroy = true
+
+  tags = merge(
+    local.tags_eks,
+    {
+      "Name" = "Databases backups"
+    }
+  )
+  server_side_encryption_configuration {
+    rule {
+      apply_server_side_encryption_by_default {
+        sse_algorithm = "AES256"
+      }
+    }
+  }
+}
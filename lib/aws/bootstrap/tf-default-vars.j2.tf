@@ -117,6 +117,12 @@ variable "s3_bucket_kubeconfig" {
   type = string
 }
 
+variable "s3_bucket_database_backups" {
+  description = "S3 bucket containing self-hosted databases backups"
+  default = "{{ s3_database_backups_bucket }}"
+  type = string
+}
+
 # EC2 SSH default SSH key
 
 variable "ec2_ssh_default_key" {
@@ -74,6 +74,7 @@ resource "aws_docdb_cluster_instance" "documentdb_cluster_instances" {
     q_environment_id = var.q_environment_id
     q_project_id = var.q_project_id
     database_identifier = var.documentdb_identifier
+    q_database_name = "{{ sanitized_name }}"
   }
 }
 
@@ -87,6 +88,7 @@ resource "aws_docdb_cluster" "documentdb_cluster" {
     q_environment_id = var.q_environment_id
     q_project_id = var.q_project_id
     database_identifier = var.documentdb_identifier
+    q_database_name = "{{ sanitized_name }}"
     {% if resource_expiration_in_seconds is defined %}ttl = var.resource_expiration_in_seconds{% endif %}
     {% if snapshot is defined and snapshot["snapshot_id"] %}meta_last_restored_from = var.snapshot_identifier{% endif %}
   }
@@ -118,4 +120,9 @@ resource "aws_docdb_cluster" "documentdb_cluster" {
   backup_retention_period = var.backup_retention_period
   preferred_backup_window = var.preferred_backup_window
   skip_final_snapshot = var.skip_final_snapshot
+
+  # restores are done on purpose by forcing the re-creation of the cluster
+  lifecycle {
+    ignore_changes = [snapshot_identifier]
+  }
 }
@@ -7,6 +7,7 @@ locals {
     q_environment_id = var.q_environment_id
     q_project_id = var.q_project_id
     database_identifier = var.mysql_identifier
+    q_database_name = "{{ sanitized_name }}"
     {% if resource_expiration_in_seconds is defined %}ttl = var.resource_expiration_in_seconds{% endif %}
     {% if snapshot is defined and snapshot["snapshot_id"] %}meta_last_restored_from = var.snapshot_identifier{% endif %}
   }
@@ -42,6 +43,7 @@ data "aws_security_group" "selected" {
   }
 }
 
+
 data "aws_iam_role" "rds_enhanced_monitoring" {
   name = "qovery-rds-enhanced-monitoring-${var.kubernetes_cluster_id}"
 }
@@ -65,6 +67,10 @@ resource "helm_release" "mysql_instance_external_name" {
     name = "app_id"
     value = "{{database_id}}"
   }
+  set {
+    name = "engine_version"
+    value = aws_db_instance.mysql_instance.engine_version
+  }
 
   depends_on = [
     aws_db_instance.mysql_instance
@@ -72,7 +78,12 @@ resource "helm_release" "mysql_instance_external_name" {
 }
 
 resource "aws_db_parameter_group" "mysql_parameter_group" {
-  name   = "qovery-${var.mysql_identifier}"
+  {%- if allow_major_version_upgrade is defined %}
+  # the family changes with the major version, the new group must exist before the instance is upgraded
+  name = "qovery-${var.mysql_identifier}-${replace(var.parameter_group_family, ".", "")}"
+  {%- else %}
+  name = "qovery-${var.mysql_identifier}"
+  {%- endif %}
   family = var.parameter_group_family
 
   tags = local.tags_mysql
@@ -82,6 +93,12 @@ resource "aws_db_parameter_group" "mysql_parameter_group" {
     name  = "log_bin_trust_function_creators"
     value = "1"
   }
+
+  lifecycle {
+    create_before_destroy = true
+    # only a family change replaces the group, keep the name it was created with
+    ignore_changes = [name]
+  }
 }
 
 # Non snapshoted version
@@ -104,6 +121,13 @@ resource "aws_db_instance" "mysql_instance" {
   {%- if snapshot is defined and snapshot["snapshot_id"] %}
   # Snapshot
   snapshot_identifier = var.snapshot_identifier
+  {%- elif restore_time is defined %}
+  # Point in time, from the retained automated backups of the instance being replaced,
+  # its resource id is resolved before the instance is deleted
+  restore_to_point_in_time {
+    source_dbi_resource_id = var.restore_source_resource_id
+    restore_time = var.restore_time
+  }
   {%- else %}
   allocated_storage = var.disk_size
   storage_type = var.storage_type
@@ -120,7 +144,13 @@ resource "aws_db_instance" "mysql_instance" {
   multi_az = var.multi_az
 
   # Maintenance and upgrades
+  {%- if allow_major_version_upgrade is defined %}
+  # major version upgrades are requested on purpose and applied right away
+  allow_major_version_upgrade = true
+  apply_immediately = true
+  {%- else %}
   apply_immediately = var.apply_changes_now
+  {%- endif %}
   auto_minor_version_upgrade = var.upgrade_minor
   maintenance_window = var.maintenance_window
 
@@ -134,4 +164,8 @@ resource "aws_db_instance" "mysql_instance" {
   skip_final_snapshot = var.skip_final_snapshot
   delete_automated_backups = var.delete_automated_backups
 
+  # restores are done on purpose by forcing the re-creation of the instance
+  lifecycle {
+    ignore_changes = [snapshot_identifier, restore_to_point_in_time]
+  }
 }
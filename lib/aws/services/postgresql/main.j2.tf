@@ -28,6 +28,7 @@ data "aws_security_group" "selected" {
   }
 }
 
+
 data "aws_iam_role" "rds_enhanced_monitoring" {
   name = "qovery-rds-enhanced-monitoring-${var.kubernetes_cluster_id}"
 }
@@ -51,6 +52,10 @@ resource "helm_release" "postgres_instance_external_name" {
     name = "app_id"
     value = "{{database_id}}"
   }
+  set {
+    name = "engine_version"
+    value = aws_db_instance.postgresql_instance.engine_version
+  }
 
   depends_on = [
     aws_db_instance.postgresql_instance
@@ -69,8 +74,9 @@ resource "aws_db_instance" "postgresql_instance" {
     q_environment_id = var.q_environment_id
     q_project_id = var.q_project_id
     database_identifier = var.postgresql_identifier
+    q_database_name = "{{ sanitized_name }}"
     {% if resource_expiration_in_seconds is defined %}ttl = var.resource_expiration_in_seconds{% endif %}
-    {% if snapshot and snapshot["snapshot_id"] %}meta_last_restored_from = var.snapshot_identifier{% endif %}
+    {% if snapshot is defined and snapshot["snapshot_id"] %}meta_last_restored_from = var.snapshot_identifier{% endif %}
   }
 
   # Postgres instance basics
@@ -82,9 +88,16 @@ resource "aws_db_instance" "postgresql_instance" {
     delete = "60m"
   }
   password = var.password
-  {%- if snapshot and snapshot["snapshot_id"] %}
+  {%- if snapshot is defined and snapshot["snapshot_id"] %}
   # Snapshot
   snapshot_identifier = var.snapshot_identifier
+  {%- elif restore_time is defined %}
+  # Point in time, from the retained automated backups of the instance being replaced,
+  # its resource id is resolved before the instance is deleted
+  restore_to_point_in_time {
+    source_dbi_resource_id = var.restore_source_resource_id
+    restore_time = var.restore_time
+  }
   {%- else %}
   allocated_storage = var.disk_size
   name = var.database_name
@@ -102,7 +115,13 @@ resource "aws_db_instance" "postgresql_instance" {
   multi_az = var.multi_az
 
   # Maintenance and upgrades
+  {%- if allow_major_version_upgrade is defined %}
+  # major version upgrades are requested on purpose and applied right away
+  allow_major_version_upgrade = true
+  apply_immediately = true
+  {%- else %}
   apply_immediately = var.apply_changes_now
+  {%- endif %}
   auto_minor_version_upgrade = var.upgrade_minor
   maintenance_window = var.maintenance_window
 
@@ -118,4 +137,8 @@ resource "aws_db_instance" "postgresql_instance" {
   skip_final_snapshot = var.skip_final_snapshot
   delete_automated_backups = var.delete_automated_backups
 
+  # restores are done on purpose by forcing the re-creation of the instance
+  lifecycle {
+    ignore_changes = [snapshot_identifier, restore_to_point_in_time]
+  }
 }
@@ -172,12 +172,29 @@ variable "skip_final_snapshot" {
   type = bool
 }
 
+{%- if snapshot is defined %}
 # Snapshots
-#variable "snapshot_identifier" {
-#  description = "Snapshot ID to restore"
-#  default = "{ service_info['snapshot']['snapshot_id'] }"
-#  type = string
-#}
+variable "snapshot_identifier" {
+  description = "Snapshot ID to restore"
+  default = "{{ snapshot['snapshot_id']}}"
+  type = string
+}
+{% endif %}
+
+{%- if restore_time is defined %}
+# Point in time restore
+variable "restore_time" {
+  description = "Date and time in UTC format to restore the instance from"
+  default = "{{ restore_time }}"
+  type = string
+}
+
+variable "restore_source_resource_id" {
+  description = "Resource ID of the instance to restore from"
+  default = "{{ restore_source_resource_id }}"
+  type = string
+}
+{% endif %}
 
 {%- if resource_expiration_in_seconds is defined %}
 # Pleco ttl
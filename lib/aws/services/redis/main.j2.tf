@@ -65,6 +65,7 @@ resource "aws_elasticache_cluster" "elasticache_cluster" {
     q_environment_id = var.q_environment_id
     q_project_id = var.q_project_id
     database_identifier = var.elasticache_identifier
+    q_database_name = "{{ sanitized_name }}"
     {% if resource_expiration_in_seconds is defined %}ttl = var.resource_expiration_in_seconds{% endif %}
   }
 
@@ -75,7 +76,7 @@ resource "aws_elasticache_cluster" "elasticache_cluster" {
   # need to add this dirty trick while Hashicorp fix this issue
   # https://github.com/hashicorp/terraform-provider-aws/issues/15625
   lifecycle {
-    ignore_changes = [engine_version]
+    ignore_changes = [engine_version, snapshot_name]
   }
 
   {%- if replication_group_id is defined %}
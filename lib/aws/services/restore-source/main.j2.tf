@@ -0,0 +1,54 @@
+# Read only checks of the source of a managed database restore, run before the database is replaced:
+# the plan fails when the snapshot or the instance to restore from doesn't exist.
+
+{%- if restore_database_kind == "postgresql" or restore_database_kind == "mysql" %}
+{%- if restore_snapshot_id is defined %}
+data "aws_db_snapshot" "restore_source" {
+  db_snapshot_identifier = "{{ restore_snapshot_id }}"
+}
+
+output "restore_source" {
+  value = data.aws_db_snapshot.restore_source.db_snapshot_identifier
+}
+{%- else %}
+# the retained automated backups of the instance are identified by its resource id, which is kept once the instance is deleted
+data "aws_db_instance" "restore_source" {
+  db_instance_identifier = "{{ fqdn_id }}"
+}
+
+output "restore_source" {
+  value = data.aws_db_instance.restore_source.resource_id
+}
+{%- endif %}
+{%- elif restore_database_kind == "mongodb" %}
+data "aws_db_cluster_snapshot" "restore_source" {
+  db_cluster_snapshot_identifier = "{{ restore_snapshot_id }}"
+}
+
+output "restore_source" {
+  value = data.aws_db_cluster_snapshot.restore_source.db_cluster_snapshot_identifier
+}
+{%- elif restore_database_kind == "redis" %}
+# there is no Terraform data source for ElastiCache snapshots, the aws cli is required
+resource "null_resource" "restore_source" {
+  triggers = {
+    snapshot_name = "{{ restore_snapshot_id }}"
+  }
+
+  provisioner "local-exec" {
+    command = <<EOT
+status=$(aws elasticache describe-snapshots --snapshot-name {{ restore_snapshot_id }} --query 'Snapshots[0].SnapshotStatus' --output text)
+[ "$status" = "available" ] || { echo "snapshot {{ restore_snapshot_id }} is not available: $status"; exit 1; }
+EOT
+    environment = {
+      AWS_ACCESS_KEY_ID = "{{ aws_access_key }}"
+      AWS_SECRET_ACCESS_KEY = "{{ aws_secret_key }}"
+      AWS_DEFAULT_REGION = "{{ region }}"
+    }
+  }
+}
+
+output "restore_source" {
+  value = null_resource.restore_source.triggers.snapshot_name
+}
+{%- endif %}
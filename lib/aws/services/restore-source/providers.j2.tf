@@ -0,0 +1,20 @@
+terraform {
+  required_providers {
+    aws = {
+      source = "hashicorp/aws"
+      version    = "~> 3.36.0"
+    }
+    null = {
+      source = "hashicorp/null"
+      version = "~> 3.1.0"
+    }
+  }
+  required_version = ">= 0.13"
+}
+
+provider "aws" {
+  profile    = "default"
+  region     = "{{ region }}"
+  access_key = "{{ aws_access_key }}"
+  secret_key = "{{ aws_secret_key }}"
+}
@@ -0,0 +1,91 @@
+# Manual snapshots are kept when the database is deleted, the state of this workspace is not kept on purpose:
+# a snapshot must never be destroyed by a later Terraform run.
+
+{%- if snapshot_source_environment_id is defined %}
+# the database to snapshot is the one with the same name in the environment being cloned, if any
+data "aws_resourcegroupstaggingapi_resources" "snapshot_source" {
+  resource_type_filters = ["{{ snapshot_source_resource_type }}"]
+
+  tag_filter {
+    key = "q_environment_id"
+    values = ["{{ snapshot_source_environment_id }}"]
+  }
+
+  tag_filter {
+    key = "q_database_name"
+    values = ["{{ sanitized_name }}"]
+  }
+}
+
+locals {
+  snapshot_source_arns = data.aws_resourcegroupstaggingapi_resources.snapshot_source.resource_tag_mapping_list[*].resource_arn
+  # arn:aws:<service>:<region>:<account>:<resource type>:<identifier>
+  database_identifier = length(local.snapshot_source_arns) > 0 ? element(split(":", local.snapshot_source_arns[0]), 6) : ""
+}
+{%- else %}
+locals {
+  database_identifier = "{{ fqdn_id }}"
+}
+{%- endif %}
+
+{%- if snapshot_database_kind == "postgresql" or snapshot_database_kind == "mysql" %}
+resource "aws_db_snapshot" "database_snapshot" {
+  count = local.database_identifier == "" ? 0 : 1
+
+  db_instance_identifier = local.database_identifier
+  db_snapshot_identifier = "{{ snapshot_name }}"
+
+  tags = {
+    cluster_name = "{{ cluster_name }}"
+    region = "{{ region }}"
+    q_client_id = "{{ owner_id }}"
+    q_environment_id = "{{ environment_id }}"
+    q_project_id = "{{ project_id }}"
+    q_execution_id = "{{ execution_id }}"
+    database_identifier = local.database_identifier
+  }
+
+  timeouts {
+    read = "120m"
+  }
+}
+{%- elif snapshot_database_kind == "mongodb" %}
+resource "aws_docdb_cluster_snapshot" "database_snapshot" {
+  count = local.database_identifier == "" ? 0 : 1
+
+  db_cluster_identifier = local.database_identifier
+  db_cluster_snapshot_identifier = "{{ snapshot_name }}"
+
+  timeouts {
+    create = "120m"
+  }
+}
+{%- elif snapshot_database_kind == "redis" %}
+# there is no Terraform resource for ElastiCache manual snapshots, the aws cli is required
+resource "null_resource" "database_snapshot" {
+  count = local.database_identifier == "" ? 0 : 1
+
+  provisioner "local-exec" {
+    command = <<EOT
+aws elasticache create-snapshot --cache-cluster-id ${local.database_identifier} --snapshot-name {{ snapshot_name }} \
+  --tags Key=q_execution_id,Value={{ execution_id }} Key=q_environment_id,Value={{ environment_id }}
+while true; do
+  status=$(aws elasticache describe-snapshots --snapshot-name {{ snapshot_name }} --query 'Snapshots[0].SnapshotStatus' --output text)
+  [ "$status" = "available" ] && break
+  [ "$status" = "failed" ] && exit 1
+  sleep 10
+done
+EOT
+    environment = {
+      AWS_ACCESS_KEY_ID = "{{ aws_access_key }}"
+      AWS_SECRET_ACCESS_KEY = "{{ aws_secret_key }}"
+      AWS_DEFAULT_REGION = "{{ region }}"
+    }
+  }
+}
+{%- endif %}
+
+# empty when there was nothing to snapshot
+output "snapshot_name" {
+  value = local.database_identifier == "" ? "" : "{{ snapshot_name }}"
+}
@@ -12,9 +12,15 @@ resource "digitalocean_kubernetes_cluster" "kubernetes_cluster" {
     tags = [digitalocean_tag.cluster_tag.id]
     name = "qovery-${var.kubernetes_cluster_id}"
     size = "{{ doks_worker_nodes[0].instance_type }}"
+    {%- if doks_paused %}
+    # pause: the default node pool can't be empty, keep a single node alongside the control plane
+    auto_scale = false
+    node_count = 1
+    {%- else %}
     # use Digital Ocean built-in cluster autoscaler
     auto_scale = true
     min_nodes  = "{{ doks_worker_nodes[0].min_size }}"
     max_nodes  = "{{ doks_worker_nodes[0].max_size }}"
+    {%- endif %}
   }
 }
\ No newline at end of file
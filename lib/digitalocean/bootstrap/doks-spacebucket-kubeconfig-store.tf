@@ -2,4 +2,9 @@ resource "digitalocean_spaces_bucket" "space_bucket_kubeconfig" {
   name   = var.space_bucket_kubeconfig
   region = var.region
   force_destroy = true
-}
\ No newline at end of file
+}
+resource "digitalocean_spaces_bucket" "space_bucket_database_backups" {
+  name   = var.space_bucket_database_backups
+  region = var.region
+  force_destroy = true
+}
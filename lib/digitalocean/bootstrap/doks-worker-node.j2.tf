@@ -1,6 +1,7 @@
 # This resource block is useful to add another worker to the cluster
 # The first worker node is create into digitalocean_kubernetes_cluster terraform resource
-{%- if doks_worker_nodes|length > 1 %}
+# On pause, the additional node pools are removed and created again on the next deployment
+{%- if doks_worker_nodes|length > 1 and not doks_paused %}
 {% for doks_worker_node in doks_worker_nodes %}
 {%- if loop.index > 1 %}
 resource "digitalocean_kubernetes_node_pool" "app_node_pool_{{ loop.index }}" {
@@ -97,6 +97,12 @@ variable "space_bucket_kubeconfig" {
   type = string
 }
 
+variable "space_bucket_database_backups" {
+  description = "Space bucket containing self-hosted databases backups"
+  default = "{{ space_bucket_database_backups }}"
+  type = string
+}
+
 variable "space_access_id" {
   description = "credentials space access key"
   default = "{{ spaces_access_id }}"
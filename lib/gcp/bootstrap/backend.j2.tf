@@ -0,0 +1,7 @@
+terraform {
+  backend "gcs" {
+    credentials = "{{ gcp_credentials_file }}"
+    bucket = "{{ gcp_terraform_backend_bucket }}"
+    prefix = "{{ gke_cluster_id }}"
+  }
+}
\ No newline at end of file
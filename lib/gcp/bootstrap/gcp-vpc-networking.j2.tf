@@ -0,0 +1,11 @@
+resource "google_compute_network" "qovery_vpc" {
+  name                    = var.vpc_name
+  auto_create_subnetworks = false
+}
+
+resource "google_compute_subnetwork" "qovery_subnet" {
+  name          = "qovery-${var.kubernetes_cluster_id}"
+  network       = google_compute_network.qovery_vpc.id
+  region        = var.region
+  ip_cidr_range = var.cidr_block
+}
\ No newline at end of file
@@ -0,0 +1,13 @@
+# the GKE master reaches the admission webhooks (cert-manager, prometheus operator) and metrics server on the nodes
+resource "google_compute_firewall" "qovery_firewall" {
+  name    = "qovery-${var.kubernetes_cluster_id}-additional-fw-rules"
+  network = google_compute_network.qovery_vpc.name
+
+  allow {
+    protocol = "tcp"
+    ports    = ["443", "8443", "9100", "10250"]
+  }
+
+  source_ranges = [google_container_cluster.kubernetes_cluster.cluster_ipv4_cidr, var.cidr_block]
+  target_tags   = ["qovery-${var.kubernetes_cluster_id}"]
+}
\ No newline at end of file
@@ -0,0 +1,11 @@
+resource "google_storage_bucket" "gcs_bucket_kubeconfig" {
+  name     = var.gcs_bucket_kubeconfig
+  location = var.region
+  force_destroy = true
+}
+
+resource "google_storage_bucket" "gcs_bucket_database_backups" {
+  name     = var.gcs_bucket_database_backups
+  location = var.region
+  force_destroy = true
+}
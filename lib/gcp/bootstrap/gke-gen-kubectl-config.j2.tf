@@ -0,0 +1,67 @@
+# GKE kubeconfigs rely on gcloud to get a token, the engine uses a dedicated service account token instead
+resource "kubernetes_service_account" "qovery_admin" {
+  metadata {
+    name      = "qovery-admin"
+    namespace = "kube-system"
+  }
+
+  depends_on = [google_container_node_pool.app_node_pool_1]
+}
+
+resource "kubernetes_cluster_role_binding" "qovery_admin" {
+  metadata {
+    name = "qovery-admin"
+  }
+  role_ref {
+    api_group = "rbac.authorization.k8s.io"
+    kind      = "ClusterRole"
+    name      = "cluster-admin"
+  }
+  subject {
+    kind      = "ServiceAccount"
+    name      = kubernetes_service_account.qovery_admin.metadata.0.name
+    namespace = "kube-system"
+  }
+}
+
+data "kubernetes_secret" "qovery_admin_token" {
+  metadata {
+    name      = kubernetes_service_account.qovery_admin.default_secret_name
+    namespace = "kube-system"
+  }
+}
+
+locals {
+  kubeconfig = <<KUBECONFIG
+apiVersion: v1
+kind: Config
+clusters:
+- cluster:
+    certificate-authority-data: ${google_container_cluster.kubernetes_cluster.master_auth.0.cluster_ca_certificate}
+    server: https://${google_container_cluster.kubernetes_cluster.endpoint}
+  name: ${google_container_cluster.kubernetes_cluster.name}
+contexts:
+- context:
+    cluster: ${google_container_cluster.kubernetes_cluster.name}
+    user: qovery-admin
+  name: ${google_container_cluster.kubernetes_cluster.name}
+current-context: ${google_container_cluster.kubernetes_cluster.name}
+users:
+- name: qovery-admin
+  user:
+    token: ${data.kubernetes_secret.qovery_admin_token.data.token}
+KUBECONFIG
+}
+
+resource "local_file" "kubeconfig" {
+  filename = "${var.gcs_bucket_kubeconfig}/${var.kubernetes_cluster_id}.yaml"
+  content = local.kubeconfig
+  file_permission = "0644"
+}
+
+resource "google_storage_bucket_object" "upload_kubeconfig" {
+  bucket     = google_storage_bucket.gcs_bucket_kubeconfig.name
+  name       = "${var.kubernetes_cluster_id}.yaml"
+  source     = local_file.kubeconfig.filename
+  depends_on = [local_file.kubeconfig, google_storage_bucket.gcs_bucket_kubeconfig]
+}
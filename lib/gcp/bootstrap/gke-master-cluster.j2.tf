@@ -0,0 +1,31 @@
+resource "google_container_cluster" "kubernetes_cluster" {
+  name = "qovery-${var.kubernetes_cluster_id}"
+  location = var.region
+  min_master_version = var.gke_version
+
+  network    = google_compute_network.qovery_vpc.id
+  subnetwork = google_compute_subnetwork.qovery_subnet.id
+
+  # pods and services ranges are allocated by GKE (VPC native cluster)
+  ip_allocation_policy {}
+
+  # worker nodes are managed in separated node pools, to be paused and upgraded independently
+  remove_default_node_pool = true
+  initial_node_count       = 1
+
+  release_channel {
+    channel = "UNSPECIFIED"
+  }
+
+  addons_config {
+    gce_persistent_disk_csi_driver_config {
+      enabled = true
+    }
+  }
+
+  master_auth {
+    client_certificate_config {
+      issue_client_certificate = false
+    }
+  }
+}
\ No newline at end of file
@@ -0,0 +1,18 @@
+# service account of the worker nodes, allowed to pull images from Artifact Registry
+resource "google_service_account" "nodes" {
+  account_id   = "qovery-${var.kubernetes_cluster_id}"
+  display_name = "Qovery cluster ${var.kubernetes_cluster_id} nodes"
+}
+
+resource "google_project_iam_member" "nodes_roles" {
+  for_each = toset([
+    "roles/logging.logWriter",
+    "roles/monitoring.metricWriter",
+    "roles/monitoring.viewer",
+    "roles/artifactregistry.reader",
+  ])
+
+  project = var.project_id
+  role    = each.value
+  member  = "serviceAccount:${google_service_account.nodes.email}"
+}
\ No newline at end of file
@@ -0,0 +1,41 @@
+{% for gke_worker_node in gke_worker_nodes %}
+resource "google_container_node_pool" "app_node_pool_{{ loop.index }}" {
+  name     = "qovery-{{ gke_cluster_id }}-{{ loop.index }}"
+  location = var.region
+  cluster  = google_container_cluster.kubernetes_cluster.name
+  version  = var.gke_version
+
+  {%- if gke_paused %}
+  # pause: remove all worker nodes but keep the control plane
+  node_count = 0
+  {%- else %}
+  # the node count is per zone of the region
+  initial_node_count = 1
+  autoscaling {
+    min_node_count = 1
+    max_node_count = "{{ gke_worker_node.max_size }}"
+  }
+  {%- endif %}
+
+  management {
+    auto_repair  = true
+    auto_upgrade = false
+  }
+
+  upgrade_settings {
+    max_surge       = 1
+    max_unavailable = 0
+  }
+
+  node_config {
+    machine_type    = "{{ gke_worker_node.instance_type }}"
+    service_account = google_service_account.nodes.email
+    oauth_scopes    = ["https://www.googleapis.com/auth/cloud-platform"]
+    tags            = ["qovery-${var.kubernetes_cluster_id}"]
+  }
+
+  lifecycle {
+    ignore_changes = [initial_node_count]
+  }
+}
+{% endfor %}
@@ -0,0 +1,49 @@
+//resource "helm_release" "alertmanager_discord" {
+//  name = "alertmanager-discord"
+//  chart = "common/charts/alertmanager-discord"
+//  namespace = "prometheus"
+//  create_namespace = true
+//  atomic = true
+//  max_history = 50
+//
+//  set {
+//    name = "replicaCount"
+//    value = "1"
+//  }
+//
+//  # Interrupt channel
+//  set {
+//    name = "application.environmentVariables.DISCORD_WEBHOOK"
+//    value = var.discord_api_key
+//  }
+//
+//  set {
+//    name = "resources.limits.cpu"
+//    value = "50m"
+//  }
+//
+//  set {
+//    name = "resources.requests.cpu"
+//    value = "50m"
+//  }
+//
+//  set {
+//    name = "resources.limits.memory"
+//    value = "50Mi"
+//  }
+//
+//  set {
+//    name = "resources.requests.memory"
+//    value = "50Mi"
+//  }
+//
+//  set {
+//    name = "forced_upgrade"
+//    value = var.forced_upgrade
+//  }
+//
+//  depends_on = [
+//    google_container_node_pool.app_node_pool_1,
+//    helm_release.prometheus_operator,
+//  ]
+//}
\ No newline at end of file
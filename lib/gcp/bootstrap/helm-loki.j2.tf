@@ -0,0 +1,75 @@
+resource "google_storage_bucket" "loki_bucket" {
+  name     = "qovery-logs-${var.kubernetes_cluster_id}"
+  location = var.region
+  force_destroy = true
+}
+
+{% if log_history_enabled %}
+resource "helm_release" "loki" {
+  name = "loki"
+  chart = "common/charts/loki"
+  namespace = "logging"
+  create_namespace = true
+  atomic = true
+  max_history = 50
+
+  values = [file("chart_values/loki.yaml")]
+
+  set {
+    name = "config.storage_config.aws.endpoint"
+    value = "storage.googleapis.com"
+  }
+
+  set {
+    name = "config.storage_config.aws.s3"
+    value = "s3://storage.googleapis.com/qovery-logs-${var.kubernetes_cluster_id}"
+  }
+
+  set {
+    name = "config.storage_config.aws.region"
+    value = var.region
+  }
+
+  set {
+    name = "config.storage_config.aws.access_key_id"
+    value = var.gcs_access_key_id
+  }
+
+  set {
+    name = "config.storage_config.aws.secret_access_key"
+    value = var.gcs_secret_access_key
+  }
+
+  # Limits
+  set {
+    name = "resources.limits.cpu"
+    value = "100m"
+  }
+
+  set {
+    name = "resources.requests.cpu"
+    value = "100m"
+  }
+
+  set {
+    name = "resources.limits.memory"
+    value = "2Gi"
+  }
+
+  set {
+    name = "resources.requests.memory"
+    value = "1Gi"
+  }
+
+  set {
+    name = "forced_upgrade"
+    value = var.forced_upgrade
+  }
+
+  depends_on = [
+    google_storage_bucket.loki_bucket,
+    google_container_node_pool.app_node_pool_1,
+    helm_release.q_storageclass,
+  ]
+}
+{% endif %}
\ No newline at end of file
@@ -0,0 +1,47 @@
+resource "helm_release" "metrics_server" {
+  name = "metrics-server"
+  chart = "common/charts/metrics-server"
+  namespace = "kube-system"
+  atomic = true
+  max_history = 50
+
+  set {
+    name = "extraArgs.kubelet-preferred-address-types"
+    value = "InternalIP"
+  }
+
+  set {
+    name = "apiService.create"
+    value = "true"
+  }
+
+  set {
+    name = "resources.limits.cpu"
+    value = "250m"
+  }
+
+  set {
+    name = "resources.requests.cpu"
+    value = "250m"
+  }
+
+  set {
+    name = "resources.limits.memory"
+    value = "256Mi"
+  }
+
+  set {
+    name = "resources.requests.memory"
+    value = "256Mi"
+  }
+
+  set {
+    name = "forced_upgrade"
+    value = var.forced_upgrade
+  }
+
+  depends_on = [
+    google_container_node_pool.app_node_pool_1,
+    helm_release.q_storageclass,
+  ]
+}
\ No newline at end of file
@@ -0,0 +1,28 @@
+resource "helm_release" "pleco" {
+  count = var.test_cluster == "false" ? 0 : 1
+
+  name = "pleco"
+  chart = "common/charts/pleco"
+  namespace = "kube-system"
+  atomic = true
+  max_history = 50
+
+  set {
+    name = "enabledFeatures.disableDryRun"
+    value = "true"
+  }
+
+  set {
+    name = "environmentVariables.LOG_LEVEL"
+    value = "debug"
+  }
+
+  set {
+    name = "forced_upgrade"
+    value = var.forced_upgrade
+  }
+
+  depends_on = [
+    google_container_node_pool.app_node_pool_1
+  ]
+}
\ No newline at end of file
@@ -0,0 +1,61 @@
+{% if metrics_history_enabled %}
+resource "helm_release" "prometheus-adapter" {
+  name = "prometheus-adapter"
+  chart = "common/charts/prometheus-adapter"
+  namespace = helm_release.prometheus_operator.namespace
+  atomic = true
+  max_history = 50
+
+  set {
+    name = "metricsRelistInterval"
+    value = "30s"
+  }
+
+  set {
+    name = "prometheus.url"
+    value = "http://prometheus-operated.${helm_release.prometheus_operator.namespace}.svc"
+  }
+
+  # PDB
+  set {
+    name = "podDisruptionBudget.enabled"
+    value = "true"
+  }
+
+  set {
+    name = "podDisruptionBudget.maxUnavailable"
+    value = "1"
+  }
+
+  # Limits
+  set {
+    name = "resources.limits.cpu"
+    value = "100m"
+  }
+
+  set {
+    name = "resources.requests.cpu"
+    value = "100m"
+  }
+
+  set {
+    name = "resources.limits.memory"
+    value = "128Mi"
+  }
+
+  set {
+    name = "resources.requests.memory"
+    value = "128Mi"
+  }
+
+  set {
+    name = "forced_upgrade"
+    value = var.forced_upgrade
+  }
+
+  depends_on = [
+    google_container_node_pool.app_node_pool_1,
+    helm_release.prometheus_operator,
+  ]
+}
+{% endif %}
\ No newline at end of file
@@ -0,0 +1,16 @@
+resource "helm_release" "q_storageclass" {
+  name = "q-storageclass"
+  chart = "charts/q-storageclass"
+  namespace = "kube-system"
+  atomic = true
+  max_history = 50
+
+  set {
+    name = "forced_upgrade"
+    value = var.forced_upgrade
+  }
+
+  depends_on = [
+    google_container_node_pool.app_node_pool_1,
+  ]
+}
\ No newline at end of file
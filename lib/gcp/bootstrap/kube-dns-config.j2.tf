@@ -0,0 +1,26 @@
+# GKE runs kube-dns instead of CoreDNS, managed domains are forwarded to their resolvers with stub domains
+locals {
+  kube_dns_stub_domains = jsonencode({
+    for domain in {{ managed_dns_domains_terraform_format }} : domain => {{ managed_dns_resolvers_terraform_format }}
+  })
+}
+
+resource "null_resource" "kube_dns_stub_domains" {
+  triggers = {
+    stub_domains = local.kube_dns_stub_domains
+  }
+
+  # the kube-dns config map is created by GKE, it can only be patched
+  provisioner "local-exec" {
+    command = <<EOT
+kubectl -n kube-system patch configmap kube-dns --type merge -p '{"data":{"stubDomains":${jsonencode(local.kube_dns_stub_domains)}}}'
+EOT
+    environment = {
+      KUBECONFIG = local_file.kubeconfig.filename
+    }
+  }
+
+  depends_on = [
+    google_container_node_pool.app_node_pool_1,
+  ]
+}
\ No newline at end of file
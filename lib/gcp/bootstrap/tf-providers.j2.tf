@@ -0,0 +1,58 @@
+provider "google" {
+  credentials = file("{{ gcp_credentials_file }}")
+  project = "{{ gcp_project_id }}"
+  region = "{{ gcp_region }}"
+}
+
+data "google_client_config" "default" {}
+
+provider "helm" {
+  debug           = true
+  kubernetes {
+    host = "https://${google_container_cluster.kubernetes_cluster.endpoint}"
+    cluster_ca_certificate = base64decode(google_container_cluster.kubernetes_cluster.master_auth.0.cluster_ca_certificate)
+    load_config_file       = false
+    token                  = data.google_client_config.default.access_token
+  }
+}
+
+provider "kubernetes" {
+  host = "https://${google_container_cluster.kubernetes_cluster.endpoint}"
+  cluster_ca_certificate = base64decode(google_container_cluster.kubernetes_cluster.master_auth.0.cluster_ca_certificate)
+  load_config_file       = false
+  token                  = data.google_client_config.default.access_token
+}
+
+terraform {
+  required_providers {
+    google = {
+      source = "hashicorp/google"
+      version = "~> 3.51.0"
+    }
+    external = {
+      source = "hashicorp/external"
+      version = "~> 1.2"
+    }
+    helm = {
+      source = "hashicorp/helm"
+      version = "~> 1.3.2"
+    }
+    kubernetes = {
+      source = "hashicorp/kubernetes"
+      version = "~> 1.13.3"
+    }
+    local = {
+      source = "hashicorp/local"
+      version = "~> 1.4"
+    }
+    null = {
+      source = "hashicorp/null"
+      version = "~> 2.1"
+    }
+    random = {
+      source = "hashicorp/random"
+      version = "~> 2.3"
+    }
+  }
+  required_version = ">= 0.13"
+}
\ No newline at end of file
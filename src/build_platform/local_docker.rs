@@ -1,4 +1,6 @@
-use crate::build_platform::{Build, BuildPlatform, BuildResult, Image, Kind};
+use crate::build_platform::{
+    is_valid_build_variable_key, Build, BuildPlatform, BuildResult, EnvironmentVariable, Image, Kind,
+};
 use crate::error::{EngineError, EngineErrorCause, SimpleError, SimpleErrorKind};
 use crate::fs::workspace_directory;
 use crate::git::checkout_submodules;
@@ -8,11 +10,19 @@ use crate::models::{
 use crate::{cmd, git};
 use chrono::Duration;
 use std::env;
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
 use std::path::Path;
 use sysinfo::{Disk, DiskExt, SystemExt};
 
 const BUILD_DURATION_TIMEOUT_MIN: i64 = 30;
 
+const GIT_MIRROR_LOCK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30 * 60);
+const GIT_MIRROR_MAX_UNUSED: std::time::Duration = std::time::Duration::from_secs(7 * 24 * 60 * 60);
+
+/// the docker driver of buildx can't export the build cache to a registry, this builder runs BuildKit in a container
+const BUILDX_BUILDER_NAME: &str = "qovery-engine";
+
 /// https://buildpacks.io/
 const BUILDPACKS_BUILDERS: [&str; 1] = [
     "heroku/buildpacks:20",
@@ -57,19 +67,138 @@ impl LocalDocker {
         }
     }
 
+    fn get_or_create_buildx_builder(&self) -> Result<(), SimpleError> {
+        let envs = self.get_docker_host_envs();
+
+        if cmd::utilities::exec("docker", vec!["buildx", "inspect", BUILDX_BUILDER_NAME], &envs).is_ok() {
+            return Ok(());
+        }
+
+        cmd::utilities::exec(
+            "docker",
+            vec![
+                "buildx",
+                "create",
+                "--name",
+                BUILDX_BUILDER_NAME,
+                "--driver",
+                "docker-container",
+            ],
+            &envs,
+        )
+    }
+
     fn build_image_with_docker(
         &self,
         build: Build,
         dockerfile_complete_path: &str,
         into_dir_docker_style: &str,
         env_var_args: Vec<String>,
+        secret_args: Vec<String>,
         use_build_cache: bool,
         lh: &ListenersHelper,
+        is_task_canceled: &dyn Fn() -> bool,
     ) -> Result<BuildResult, EngineError> {
-        let mut docker_args = if !use_build_cache {
-            vec!["build", "--no-cache"]
-        } else {
-            vec!["build"]
+        let cache_reference = match build.options.cache_reference.as_ref() {
+            Some(cache_reference) if use_build_cache => match self.get_or_create_buildx_builder() {
+                Ok(_) => Some(cache_reference.clone()),
+                Err(err) => {
+                    warn!(
+                        "can't use the registry build cache, buildx builder is not available: {:?}",
+                        err
+                    );
+                    None
+                }
+            },
+            _ => None,
+        };
+
+        let result = self.exec_docker_build(
+            &build,
+            dockerfile_complete_path,
+            into_dir_docker_style,
+            &env_var_args,
+            &secret_args,
+            cache_reference.as_deref(),
+            use_build_cache,
+            lh,
+            is_task_canceled,
+        );
+
+        // importing or exporting the cache fails the whole build, E.g: when the registry refuses the cache manifest
+        let result = match result {
+            Err(err) if cache_reference.is_some() && !is_task_canceled() => {
+                let message = format!(
+                    "build of container image {} with the registry build cache has failed, retrying without it",
+                    self.name_with_id()
+                );
+                warn!("{}: {:?}", message, err.message);
+
+                lh.deployment_in_progress(ProgressInfo::new(
+                    ProgressScope::Application {
+                        id: build.image.application_id.clone(),
+                    },
+                    ProgressLevel::Warn,
+                    Some(message),
+                    self.context.execution_id(),
+                ));
+
+                self.exec_docker_build(
+                    &build,
+                    dockerfile_complete_path,
+                    into_dir_docker_style,
+                    &env_var_args,
+                    &secret_args,
+                    None,
+                    use_build_cache,
+                    lh,
+                    is_task_canceled,
+                )
+            }
+            result => result,
+        };
+
+        result.map(|_| BuildResult { build })
+    }
+
+    fn exec_docker_build(
+        &self,
+        build: &Build,
+        dockerfile_complete_path: &str,
+        into_dir_docker_style: &str,
+        env_var_args: &[String],
+        secret_args: &[String],
+        cache_reference: Option<&str>,
+        use_build_cache: bool,
+        lh: &ListenersHelper,
+        is_task_canceled: &dyn Fn() -> bool,
+    ) -> Result<(), EngineError> {
+        let cache_from_arg;
+        let cache_to_arg;
+        let mut docker_args = match cache_reference {
+            Some(cache_reference) => {
+                // the image is loaded into the local docker daemon afterwards, to be tagged and pushed from there
+                cache_from_arg = format!("type=registry,ref={}", cache_reference);
+                // an OCI image manifest is accepted by more registries than the default manifest list, E.g: ECR
+                cache_to_arg = format!(
+                    "type=registry,ref={},mode=max,image-manifest=true,oci-mediatypes=true",
+                    cache_reference
+                );
+
+                vec![
+                    "buildx",
+                    "build",
+                    "--builder",
+                    BUILDX_BUILDER_NAME,
+                    "--load",
+                    "--cache-from",
+                    cache_from_arg.as_str(),
+                    "--cache-to",
+                    cache_to_arg.as_str(),
+                ]
+            }
+            None if !use_build_cache => vec!["build", "--no-cache"],
+            None => vec!["build"],
         };
 
         let args = self.context.docker_build_options();
@@ -96,13 +225,25 @@ impl LocalDocker {
             docker_args
         };
 
+        let mut docker_envs = self.get_docker_host_envs();
+        if cache_reference.is_some() || !secret_args.is_empty() {
+            docker_envs.push(("DOCKER_BUILDKIT", "1"));
+            docker_args.push("--progress=plain");
+        }
+
+        // secrets are only available to the `RUN --mount=type=secret,id=<key>` instructions, under /run/secrets/<key>
+        secret_args.iter().for_each(|x| {
+            docker_args.push("--secret");
+            docker_args.push(x.as_str());
+        });
+
         docker_args.push(into_dir_docker_style);
 
         // docker build
-        let exit_status = cmd::utilities::exec_with_envs_and_output(
+        let exit_status = cmd::utilities::exec_with_envs_and_output_and_cancel(
             "docker",
             docker_args,
-            self.get_docker_host_envs(),
+            docker_envs,
             |line| {
                 let line_string = line.unwrap();
                 info!("{}", line_string.as_str());
@@ -130,10 +271,15 @@ impl LocalDocker {
                 ));
             },
             Duration::minutes(BUILD_DURATION_TIMEOUT_MIN),
+            is_task_canceled,
         );
 
         match exit_status {
-            Ok(_) => Ok(BuildResult { build }),
+            Ok(_) => Ok(()),
+            Err(_) if is_task_canceled() => Err(self.engine_error(
+                EngineErrorCause::Internal,
+                format!("build of container image {} has been canceled", self.name_with_id()),
+            )),
             Err(err) => Err(self.engine_error(
                 EngineErrorCause::User(
                     "It looks like there is something wrong in your Dockerfile. Try run locally using `qovery run` or build with `docker build --no-cache`",
@@ -154,6 +300,7 @@ impl LocalDocker {
         env_var_args: Vec<String>,
         use_build_cache: bool,
         lh: &ListenersHelper,
+        is_task_canceled: &dyn Fn() -> bool,
     ) -> Result<BuildResult, EngineError> {
         let name_with_tag = build.image.name_with_tag();
 
@@ -195,7 +342,7 @@ impl LocalDocker {
             buildpacks_args.push(builder_name);
 
             // buildpacks build
-            exit_status = cmd::utilities::exec_with_envs_and_output(
+            exit_status = cmd::utilities::exec_with_envs_and_output_and_cancel(
                 "pack",
                 buildpacks_args,
                 self.get_docker_host_envs(),
@@ -226,9 +373,10 @@ impl LocalDocker {
                     ));
                 },
                 Duration::minutes(BUILD_DURATION_TIMEOUT_MIN),
+                is_task_canceled,
             );
 
-            if exit_status.is_ok() {
+            if exit_status.is_ok() || is_task_canceled() {
                 // quit now if the builder successfully build the app
                 break;
             }
@@ -236,6 +384,10 @@ impl LocalDocker {
 
         match exit_status {
             Ok(_) => Ok(BuildResult { build }),
+            Err(_) if is_task_canceled() => Err(self.engine_error(
+                EngineErrorCause::Internal,
+                format!("build of container image {} has been canceled", self.name_with_id()),
+            )),
             Err(err) => {
                 warn!("{:?}", err);
 
@@ -255,6 +407,57 @@ impl LocalDocker {
     }
 }
 
+/// split the variables given to the build into the `--build-arg` values, kept in the image history, and the secrets
+fn split_build_environment_variables(
+    environment_variables: &[EnvironmentVariable],
+) -> (Vec<String>, Vec<(String, String)>) {
+    let mut env_var_args: Vec<String> = Vec::with_capacity(environment_variables.len());
+    let mut secret_env_vars: Vec<(String, String)> = vec![];
+
+    for ev in environment_variables {
+        if ev.is_secret {
+            secret_env_vars.push((ev.key.clone(), ev.value.clone()));
+        } else {
+            env_var_args.push(format!("{}={}", ev.key, ev.value));
+        }
+    }
+
+    (env_var_args, secret_env_vars)
+}
+
+/// write a file per secret, readable by the owner only, and return the matching `docker build --secret` values
+fn write_build_secrets(secrets_path: &str, secrets: &[(String, String)]) -> Result<Vec<String>, std::io::Error> {
+    if secrets.is_empty() {
+        return Ok(vec![]);
+    }
+
+    if let Some((key, _)) = secrets.iter().find(|(key, _)| !is_valid_build_variable_key(key)) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("invalid build secret name {:?}", key),
+        ));
+    }
+
+    let _ = std::fs::create_dir_all(secrets_path)?;
+
+    secrets
+        .iter()
+        .map(|(key, value)| {
+            let secret_path = format!("{}/{}", secrets_path, key);
+
+            let mut file = std::fs::OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(true)
+                .mode(0o600)
+                .open(&secret_path)?;
+            file.write_all(value.as_bytes())?;
+
+            Ok(format!("id={},src={}", key, secret_path))
+        })
+        .collect()
+}
+
 impl BuildPlatform for LocalDocker {
     fn context(&self) -> &Context {
         &self.context
@@ -284,7 +487,39 @@ impl BuildPlatform for LocalDocker {
         Ok(())
     }
 
-    fn build(&self, build: Build, force_build: bool) -> Result<BuildResult, EngineError> {
+    fn prepare_builds(&self) {
+        // ensure docker_path is a mounted volume, otherwise ignore because it's not what Qovery does in production
+        // ex: this cause regular cleanup on CI, leading to random tests errors
+        match env::var_os("CI") {
+            Some(_) => info!("CI environment variable found, no docker prune will be made"),
+            None => {
+                // ensure there is enough disk space left before building a new image
+                let docker_path_string = "/var/lib/docker";
+                let docker_path = Path::new(docker_path_string);
+
+                // get system info
+                let mut system = sysinfo::System::new_all();
+                system.refresh_all();
+
+                for disk in system.get_disks() {
+                    if disk.get_mount_point() == docker_path {
+                        match check_docker_space_usage_and_clean(disk, self.get_docker_host_envs()) {
+                            Ok(msg) => info!("{:?}", msg),
+                            Err(e) => error!("{:?}", e.message),
+                        }
+                        break;
+                    };
+                }
+            }
+        }
+    }
+
+    fn build(
+        &self,
+        build: Build,
+        force_build: bool,
+        is_task_canceled: &dyn Fn() -> bool,
+    ) -> Result<BuildResult, EngineError> {
         info!("LocalDocker.build() called for {}", self.name());
 
         let listeners_helper = ListenersHelper::new(&self.listeners);
@@ -298,46 +533,85 @@ impl BuildPlatform for LocalDocker {
             return Ok(BuildResult { build });
         }
 
-        // git clone
+        // git fetch into the mirror of the repository, shared by the builds
         let repository_root_path = workspace_directory(
             self.context.workspace_root_dir(),
             self.context.execution_id(),
             format!("build/{}", build.image.name.as_str()),
         );
 
-        info!(
-            "cloning repository: {} to {}",
-            build.git_repository.url, repository_root_path
+        let mirrors_path = format!("{}/.qovery-workspace/git-mirrors", self.context.workspace_root_dir());
+        if let Err(err) = git::evict_unused_mirrors(&mirrors_path, GIT_MIRROR_MAX_UNUSED) {
+            warn!(
+                "error while evicting the unused git mirrors of {}: {:?}",
+                mirrors_path, err
+            );
+        }
+
+        let mirror_path = format!(
+            "{}/{}",
+            mirrors_path,
+            crate::crypto::to_sha1(build.git_repository.url.as_str())
         );
-        let git_clone = git::clone(
+
+        // another build may fetch the same repository, the lock is held until the checkout is done
+        let mirror_lock = match git::lock_mirror(&mirror_path, GIT_MIRROR_LOCK_TIMEOUT) {
+            Ok(lock) => lock,
+            Err(err) => {
+                let message = format!(
+                    "Error while waiting for the mirror of repository {}. Error: {:?}",
+                    &build.git_repository.url, err
+                );
+                error!("{}", message);
+                return Err(self.engine_error(EngineErrorCause::Internal, message));
+            }
+        };
+
+        info!("fetching repository: {} into {}", build.git_repository.url, mirror_path);
+        let commit_id = &build.git_repository.commit_id;
+        let mirror = match git::fetch_into_mirror(
             build.git_repository.url.as_str(),
-            &repository_root_path,
+            commit_id,
+            build.git_repository.branch.as_str(),
+            &mirror_path,
             &build.git_repository.credentials,
-        );
-
-        if let Err(err) = git_clone {
-            let message = format!(
-                "Error while cloning repository {}. Error: {:?}",
-                &build.git_repository.url, err
-            );
-            error!("{}", message);
-            return Err(self.engine_error(EngineErrorCause::Internal, message));
-        }
+        ) {
+            Ok(mirror) => mirror,
+            Err(err) => {
+                let message = format!(
+                    "Error while fetching repository {}. Error: {:?}",
+                    &build.git_repository.url, err
+                );
+                error!("{}", message);
+                return Err(self.engine_error(EngineErrorCause::Internal, message));
+            }
+        };
 
         // git checkout to given commit
-        let repo = git_clone.unwrap();
-        let commit_id = &build.git_repository.commit_id;
-        if let Err(err) = git::checkout(&repo, commit_id, build.git_repository.url.as_str()) {
-            let message = format!(
-                "Error while git checkout repository {} with commit id {}. Error: {:?}",
-                &build.git_repository.url, commit_id, err
-            );
-            error!("{}", message);
-            return Err(self.engine_error(EngineErrorCause::Internal, message));
-        }
+        info!(
+            "checking out commit {} of repository {} to {}",
+            commit_id, build.git_repository.url, repository_root_path
+        );
+        let repo = match git::checkout_from_mirror(
+            &mirror,
+            build.git_repository.url.as_str(),
+            commit_id,
+            &repository_root_path,
+            build.git_repository.root_path.as_str(),
+        ) {
+            Ok(repo) => repo,
+            Err(err) => {
+                let message = format!(
+                    "Error while git checkout repository {} with commit id {}. Error: {:?}",
+                    &build.git_repository.url, commit_id, err
+                );
+                error!("{}", message);
+                return Err(self.engine_error(EngineErrorCause::Internal, message));
+            }
+        };
 
         // git checkout submodules
-        if let Err(err) = checkout_submodules(&repo) {
+        if let Err(err) = checkout_submodules(&repo, &build.git_repository.credentials) {
             let message = format!(
                 "Error while checkout submodules from repository {}. Error: {:?}",
                 &build.git_repository.url, err
@@ -347,43 +621,12 @@ impl BuildPlatform for LocalDocker {
             return Err(self.engine_error(EngineErrorCause::Internal, message));
         }
 
-        let mut disable_build_cache = false;
-        let mut env_var_args: Vec<String> = Vec::with_capacity(build.options.environment_variables.len());
-
-        for ev in &build.options.environment_variables {
-            if ev.key == "QOVERY_DISABLE_BUILD_CACHE" && ev.value.to_lowercase() == "true" {
-                // this is a special flag to disable build cache dynamically
-                // -- do not pass this env var key/value to as build parameter
-                disable_build_cache = true;
-            } else {
-                env_var_args.push(format!("{}={}", ev.key, ev.value));
-            }
-        }
-
-        // ensure docker_path is a mounted volume, otherwise ignore because it's not what Qovery does in production
-        // ex: this cause regular cleanup on CI, leading to random tests errors
-        match env::var_os("CI") {
-            Some(_) => info!("CI environment variable found, no docker prune will be made"),
-            None => {
-                // ensure there is enough disk space left before building a new image
-                let docker_path_string = "/var/lib/docker";
-                let docker_path = Path::new(docker_path_string);
-
-                // get system info
-                let mut system = sysinfo::System::new_all();
-                system.refresh_all();
+        // the build context is read from the checked out files only
+        drop(mirror_lock);
 
-                for disk in system.get_disks() {
-                    if disk.get_mount_point() == docker_path {
-                        match check_docker_space_usage_and_clean(disk, self.get_docker_host_envs()) {
-                            Ok(msg) => info!("{:?}", msg),
-                            Err(e) => error!("{:?}", e.message),
-                        }
-                        break;
-                    };
-                }
-            }
-        }
+        let disable_build_cache = build.options.disable_build_cache;
+        let (mut env_var_args, secret_env_vars) =
+            split_build_environment_variables(&build.options.environment_variables);
 
         let app_id = build.image.application_id.clone();
         let build_context_path = format!("{}/{}/.", repository_root_path.as_str(), build.git_repository.root_path);
@@ -425,15 +668,45 @@ impl BuildPlatform for LocalDocker {
                 ));
             }
 
-            self.build_image_with_docker(
+            // secrets are read from files by BuildKit, they are removed once the image is built
+            let secrets_path = workspace_directory(
+                self.context.workspace_root_dir(),
+                self.context.execution_id(),
+                format!("build-secrets/{}", build.image.name.as_str()),
+            );
+
+            let secret_args = match write_build_secrets(secrets_path.as_str(), &secret_env_vars) {
+                Ok(secret_args) => secret_args,
+                Err(err) => {
+                    let _ = std::fs::remove_dir_all(&secrets_path);
+                    let cause = match err.kind() {
+                        std::io::ErrorKind::InvalidInput => EngineErrorCause::User(
+                            "The name of a build secret must only contain letters, digits and underscores",
+                        ),
+                        _ => EngineErrorCause::Internal,
+                    };
+                    return Err(self.engine_error(cause, format!("error while writing the build secrets: {:?}", err)));
+                }
+            };
+
+            let result = self.build_image_with_docker(
                 build,
                 dockerfile_absolute_path.as_str(),
                 build_context_path.as_str(),
                 env_var_args,
+                secret_args,
                 !disable_build_cache,
                 &listeners_helper,
-            )
+                is_task_canceled,
+            );
+
+            let _ = std::fs::remove_dir_all(&secrets_path);
+
+            result
         } else {
+            // buildpacks can't mount secrets, the build environment is not kept in the image anyway
+            env_var_args.extend(secret_env_vars.iter().map(|(key, value)| format!("{}={}", key, value)));
+
             // build container with Buildpacks
             self.build_image_with_buildpacks(
                 build,
@@ -441,6 +714,7 @@ impl BuildPlatform for LocalDocker {
                 env_var_args,
                 !disable_build_cache,
                 &listeners_helper,
+                is_task_canceled,
             )
         };
 
@@ -522,6 +796,7 @@ fn docker_prune_images(envs: Vec<(&str, &str)>) -> Result<(), SimpleError> {
         vec!["container", "prune", "-f"],
         vec!["image", "prune", "-a", "-f"],
         vec!["builder", "prune", "-a", "-f"],
+        vec!["buildx", "prune", "--builder", BUILDX_BUILDER_NAME, "-a", "-f"],
         vec!["volume", "prune", "-f"],
     ];
 
@@ -547,3 +822,58 @@ fn docker_prune_images(envs: Vec<(&str, &str)>) -> Result<(), SimpleError> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    extern crate tempdir;
+
+    use crate::build_platform::local_docker::{split_build_environment_variables, write_build_secrets};
+    use crate::build_platform::EnvironmentVariable;
+    use tempdir::TempDir;
+
+    fn environment_variable(key: &str, value: &str, is_secret: bool) -> EnvironmentVariable {
+        EnvironmentVariable {
+            key: key.to_string(),
+            value: value.to_string(),
+            is_secret,
+        }
+    }
+
+    #[test]
+    fn test_split_build_environment_variables() {
+        let (env_var_args, secret_env_vars) = split_build_environment_variables(&[
+            environment_variable("NODE_ENV", "production", false),
+            environment_variable("NPM_TOKEN", "token", true),
+        ]);
+
+        assert_eq!(env_var_args, vec!["NODE_ENV=production".to_string()]);
+        assert_eq!(secret_env_vars, vec![("NPM_TOKEN".to_string(), "token".to_string())]);
+    }
+
+    #[test]
+    fn test_write_build_secrets() {
+        let tmp_dir = TempDir::new("build_secrets").expect("error creating temporary dir");
+        let secrets_path = tmp_dir.path().join("secrets");
+        let secrets_path = secrets_path.to_str().unwrap();
+
+        let secret_args = write_build_secrets(secrets_path, &[("NPM_TOKEN".to_string(), "token".to_string())]).unwrap();
+        assert_eq!(
+            secret_args,
+            vec![format!("id=NPM_TOKEN,src={}/NPM_TOKEN", secrets_path)]
+        );
+        assert_eq!(
+            std::fs::read_to_string(format!("{}/NPM_TOKEN", secrets_path)).unwrap(),
+            "token"
+        );
+
+        // the key is a file name
+        for key in &["../../.ssh/authorized_keys", "/etc/passwd", "..", "", "1TOKEN"] {
+            assert_eq!(
+                write_build_secrets(secrets_path, &[(key.to_string(), "token".to_string())])
+                    .unwrap_err()
+                    .kind(),
+                std::io::ErrorKind::InvalidInput
+            );
+        }
+    }
+}
@@ -1,12 +1,14 @@
 use serde::{Deserialize, Serialize};
 
+use crate::container_registry::PushDestination;
 use crate::error::{EngineError, EngineErrorCause, EngineErrorScope};
 use crate::git::Credentials;
 use crate::models::{Context, Listen};
 
+pub mod buildkit;
 pub mod local_docker;
 
-pub trait BuildPlatform: Listen {
+pub trait BuildPlatform: Listen + Send + Sync {
     fn context(&self) -> &Context;
     fn kind(&self) -> Kind;
     fn id(&self) -> &str;
@@ -15,8 +17,19 @@ pub trait BuildPlatform: Listen {
         format!("{} ({})", self.name(), self.id())
     }
     fn is_valid(&self) -> Result<(), EngineError>;
-    fn build(&self, build: Build, force_build: bool) -> Result<BuildResult, EngineError>;
+    fn build(
+        &self,
+        build: Build,
+        force_build: bool,
+        is_task_canceled: &dyn Fn() -> bool,
+    ) -> Result<BuildResult, EngineError>;
     fn build_error(&self, build: Build) -> Result<BuildResult, EngineError>;
+    /// called once before the builds of an environment, never while one of them is running
+    fn prepare_builds(&self) {}
+    /// the image is pushed to the container registry by the build itself, not from the engine host
+    fn is_image_pushed_by_build(&self) -> bool {
+        false
+    }
     fn engine_error_scope(&self) -> EngineErrorScope {
         EngineErrorScope::BuildPlatform(self.id().to_string(), self.name().to_string())
     }
@@ -38,17 +51,26 @@ pub struct Build {
 
 pub struct BuildOptions {
     pub environment_variables: Vec<EnvironmentVariable>,
+    // set by the QOVERY_DISABLE_BUILD_CACHE environment variable
+    pub disable_build_cache: bool,
+    // registry reference BuildKit imports and exports the build cache from
+    pub cache_reference: Option<String>,
+    // only set for the build platforms pushing the image by themselves
+    pub push_destination: Option<PushDestination>,
 }
 
 pub struct EnvironmentVariable {
     pub key: String,
     pub value: String,
+    pub is_secret: bool,
 }
 
 pub struct GitRepository {
     pub url: String,
     pub credentials: Option<Credentials>,
     pub commit_id: String,
+    // the history of the branch is fetched when the commit alone can't be
+    pub branch: String,
     pub dockerfile_path: Option<String>,
     pub root_path: String,
 }
@@ -87,4 +109,15 @@ impl BuildResult {
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Kind {
     LocalDocker,
+    Buildkit,
+}
+
+/// the key of a build argument or secret is used as an environment variable or a file name by the build
+pub(crate) fn is_valid_build_variable_key(key: &str) -> bool {
+    let mut chars = key.chars();
+
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => chars.all(|c| c.is_ascii_alphanumeric() || c == '_'),
+        _ => false,
+    }
 }
@@ -1,22 +1,32 @@
 use tera::Context as TeraContext;
 
 use crate::build_platform::Image;
+use crate::cloud_provider::environment::Environment;
+use crate::cloud_provider::kubernetes::Kubernetes;
 use crate::cloud_provider::models::{
-    EnvironmentVariable, EnvironmentVariableDataTemplate, Storage, StorageDataTemplate,
+    Autoscaling, EnvironmentVariable, EnvironmentVariableDataTemplate, Probes, PublicAccess, PublicPortDataTemplate,
+    RolloutStrategy, Storage, StorageDataTemplate,
 };
+use crate::cloud_provider::plan::ServicePlan;
 use crate::cloud_provider::service::{
-    default_tera_context, delete_stateless_service, deploy_stateless_service_error, deploy_user_stateless_service,
-    scale_down_application, send_progress_on_long_task, Action, Application as CApplication, Create, Delete, Helm,
-    Pause, Service, ServiceType, StatelessService,
+    default_tera_context, delete_user_application, deploy_stateless_service_error, deploy_user_application,
+    get_autoscaling_replicas, plan_stateless_service, report_public_access_address, restore_application_storage,
+    scale_down_application, send_progress_on_long_task, send_progress_on_long_task_with_message,
+    snapshot_application_storage, Action, Application as CApplication, Backup, Create, Delete, Helm, Pause, Plan,
+    Service, ServiceType, StatelessService,
 };
 use crate::cloud_provider::utilities::{sanitize_name, validate_k8s_required_cpu_and_burstable};
 use crate::cloud_provider::DeploymentTarget;
 use crate::cmd::helm::Timeout;
+use crate::cmd::kubectl::kubectl_exec_get_pvc;
 use crate::cmd::kubectl::ScalingKind::{Deployment, Statefulset};
 use crate::error::EngineErrorCause::Internal;
-use crate::error::{EngineError, EngineErrorScope};
+use crate::error::{cast_simple_error_to_engine_error, EngineError, EngineErrorScope};
 use crate::models::{Context, Listen, Listener, Listeners, ListenersHelper};
 
+// storage classes of the volumes provisioned by the EBS CSI driver, which can be snapshotted
+const CSI_STORAGE_CLASS_PREFIX: &str = "aws-ebs-csi-";
+
 pub struct Application {
     context: Context,
     id: String,
@@ -28,6 +38,10 @@ pub struct Application {
     total_ram_in_mib: u32,
     total_instances: u16,
     start_timeout_in_seconds: u32,
+    probes: Probes,
+    autoscaling: Option<Autoscaling>,
+    rollout_strategy: RolloutStrategy,
+    public_access: Option<PublicAccess>,
     image: Image,
     storage: Vec<Storage<StorageType>>,
     environment_variables: Vec<EnvironmentVariable>,
@@ -46,6 +60,10 @@ impl Application {
         total_ram_in_mib: u32,
         total_instances: u16,
         start_timeout_in_seconds: u32,
+        probes: Probes,
+        autoscaling: Option<Autoscaling>,
+        rollout_strategy: RolloutStrategy,
+        public_access: Option<PublicAccess>,
         image: Image,
         storage: Vec<Storage<StorageType>>,
         environment_variables: Vec<EnvironmentVariable>,
@@ -62,6 +80,10 @@ impl Application {
             total_ram_in_mib,
             total_instances,
             start_timeout_in_seconds,
+            probes,
+            autoscaling,
+            rollout_strategy,
+            public_access,
             image,
             storage,
             environment_variables,
@@ -102,7 +124,11 @@ impl Helm for Application {
     }
 }
 
-impl StatelessService for Application {}
+impl StatelessService for Application {
+    fn as_backup(&self) -> Option<&dyn Backup> {
+        Some(self)
+    }
+}
 
 impl Service for Application {
     fn context(&self) -> &Context {
@@ -157,6 +183,22 @@ impl Service for Application {
         self.total_instances
     }
 
+    fn max_instances(&self) -> u16 {
+        match &self.autoscaling {
+            Some(autoscaling) => autoscaling.max_instances,
+            None => self.total_instances,
+        }
+    }
+
+    fn rollout_strategy(&self) -> RolloutStrategy {
+        // an application with storage has a single statefulset, its volumes can't be shared with another release
+        if self.is_stateful() {
+            RolloutStrategy::RollingUpdate
+        } else {
+            self.rollout_strategy.clone()
+        }
+    }
+
     fn tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
         let (kubernetes, environment) = match target {
             DeploymentTarget::ManagedServices(k, env) => (*k, *env),
@@ -236,6 +278,7 @@ impl Service for Application {
                 size_in_gib: s.size_in_gib,
                 mount_point: s.mount_point.clone(),
                 snapshot_retention_in_days: s.snapshot_retention_in_days,
+                restore_from_snapshot: s.restore_from_snapshot.clone(),
             })
             .collect::<Vec<_>>();
 
@@ -243,8 +286,50 @@ impl Service for Application {
 
         context.insert("storage", &storage);
         context.insert("is_storage", &is_storage);
+        context.insert("volume_snapshot_class", "aws-ebs-snapshot");
+        context.insert(
+            "in_tree_storage_ids",
+            &in_tree_storage_ids(kubernetes, environment, self, is_storage)?,
+        );
         context.insert("clone", &false);
         context.insert("start_timeout_in_seconds", &self.start_timeout_in_seconds);
+        context.insert(
+            "readiness_probe",
+            &self.probes.readiness.as_ref().map(|p| p.to_probe_data_template()),
+        );
+        context.insert(
+            "liveness_probe",
+            &self.probes.liveness.as_ref().map(|p| p.to_probe_data_template()),
+        );
+        context.insert(
+            "startup_probe",
+            &self.probes.startup.as_ref().map(|p| p.to_probe_data_template()),
+        );
+        context.insert(
+            "autoscaling",
+            &self.autoscaling.as_ref().map(|a| a.to_autoscaling_data_template()),
+        );
+        if let Some(autoscaling) = &self.autoscaling {
+            context.insert(
+                "autoscaling_replicas",
+                &get_autoscaling_replicas(kubernetes, environment, self, autoscaling)?,
+            );
+        }
+
+        // overridden by the blue/green and canary rollout strategies
+        context.insert("resource_name", &self.sanitized_name());
+        context.insert("service_selector_app", &self.sanitized_name());
+        context.insert("is_workload", &true);
+        context.insert("is_service", &true);
+        context.insert("is_canary_service", &false);
+
+        match &self.public_access {
+            Some(public_access) if !public_access.ports.is_empty() => {
+                context.insert("public_fqdn", public_access.fqdn.as_str());
+                context.insert("public_ports", &public_access.to_public_port_data_templates());
+            }
+            _ => context.insert("public_ports", &Vec::<PublicPortDataTemplate>::new()),
+        }
 
         if self.context.resource_expiration_in_seconds().is_some() {
             context.insert(
@@ -257,7 +342,8 @@ impl Service for Application {
     }
 
     fn selector(&self) -> String {
-        format!("app={}", self.sanitized_name())
+        // the pods are labelled with their slot by the blue/green and canary rollout strategies
+        format!("appId={}", self.id())
     }
 
     fn engine_error_scope(&self) -> EngineErrorScope {
@@ -270,7 +356,24 @@ impl Create for Application {
         info!("AWS.application.on_create() called for {}", self.name());
 
         send_progress_on_long_task(self, crate::cloud_provider::service::Action::Create, || {
-            deploy_user_stateless_service(target, self)
+            deploy_user_application(target, self)?;
+
+            match &self.public_access {
+                Some(public_access) => report_public_access_address(
+                    target,
+                    self,
+                    public_access,
+                    |kubernetes_config, namespace, selector, envs| {
+                        crate::cmd::kubectl::kubectl_exec_get_external_ingress_hostname(
+                            kubernetes_config,
+                            namespace,
+                            selector,
+                            envs,
+                        )
+                    },
+                ),
+                None => Ok(()),
+            }
         })
     }
 
@@ -317,7 +420,7 @@ impl Delete for Application {
         info!("AWS.application.on_delete() called for {}", self.name());
 
         send_progress_on_long_task(self, crate::cloud_provider::service::Action::Delete, || {
-            delete_stateless_service(target, self, false)
+            delete_user_application(target, self, false)
         })
     }
 
@@ -329,11 +432,93 @@ impl Delete for Application {
         warn!("AWS.application.on_delete_error() called for {}", self.name());
 
         send_progress_on_long_task(self, crate::cloud_provider::service::Action::Delete, || {
-            delete_stateless_service(target, self, true)
+            delete_user_application(target, self, true)
         })
     }
 }
 
+impl Backup for Application {
+    fn on_backup(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("AWS.application.on_backup() called for {}", self.name());
+
+        if !self.is_stateful() {
+            return Ok(());
+        }
+
+        send_progress_on_long_task_with_message(
+            self,
+            Some(format!(
+                "{} '{}' volumes snapshot is in progress...",
+                self.service_type().name(),
+                self.name_with_id()
+            )),
+            crate::cloud_provider::service::Action::Create,
+            || snapshot_application_storage(target, self),
+        )
+    }
+
+    fn on_backup_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_backup_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("AWS.application.on_backup_error() called for {}", self.name());
+
+        Ok(())
+    }
+
+    fn on_restore(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("AWS.application.on_restore() called for {}", self.name());
+
+        send_progress_on_long_task_with_message(
+            self,
+            Some(format!(
+                "{} '{}' volumes restore is in progress...",
+                self.service_type().name(),
+                self.name_with_id()
+            )),
+            crate::cloud_provider::service::Action::Create,
+            || {
+                restore_application_storage(
+                    target,
+                    self,
+                    &self.storage,
+                    format!("{}/aws/charts/q-volume-restore", self.context.lib_root_dir()).as_str(),
+                )
+            },
+        )
+    }
+
+    fn on_restore_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_restore_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("AWS.application.on_restore_error() called for {}", self.name());
+
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Create, || {
+            deploy_stateless_service_error(target, self)
+        })
+    }
+}
+
+impl Plan for Application {
+    fn on_plan(&self, target: &DeploymentTarget) -> Result<ServicePlan, EngineError> {
+        info!("AWS.application.on_plan() called for {}", self.name());
+
+        plan_stateless_service(
+            target,
+            self,
+            Some(
+                self.image
+                    .registry_url
+                    .clone()
+                    .unwrap_or_else(|| self.image.name_with_tag()),
+            ),
+        )
+    }
+}
+
 impl Listen for Application {
     fn listeners(&self) -> &Listeners {
         &self.listeners
@@ -344,6 +529,45 @@ impl Listen for Application {
     }
 }
 
+/// the volumes created before the EBS CSI driver keep their in-tree storage class, the volume claim templates of a
+/// statefulset can't be updated. They can only be snapshotted once they have been replaced by a restore.
+fn in_tree_storage_ids(
+    kubernetes: &dyn Kubernetes,
+    environment: &Environment,
+    application: &Application,
+    is_storage: bool,
+) -> Result<Vec<String>, EngineError> {
+    if !is_storage {
+        return Ok(vec![]);
+    }
+
+    let pvcs = cast_simple_error_to_engine_error(
+        application.engine_error_scope(),
+        application.context().execution_id(),
+        kubectl_exec_get_pvc(
+            kubernetes.config_file_path()?.as_str(),
+            environment.namespace(),
+            format!("appId={}", application.id()).as_str(),
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )?;
+
+    let mut storage_ids = pvcs
+        .items
+        .iter()
+        .filter(|pvc| match &pvc.spec.storage_class_name {
+            Some(storage_class) => !storage_class.starts_with(CSI_STORAGE_CLASS_PREFIX),
+            None => false,
+        })
+        .filter_map(|pvc| pvc.metadata.labels.get("diskId").cloned())
+        .collect::<Vec<_>>();
+
+    storage_ids.sort();
+    storage_ids.dedup();
+
+    Ok(storage_ids)
+}
+
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub enum StorageType {
     SC1,
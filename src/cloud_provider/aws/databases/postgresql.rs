@@ -2,13 +2,19 @@ use std::collections::HashMap;
 
 use tera::Context as TeraContext;
 
-use crate::cloud_provider::aws::databases::utilities::rds_name_sanitizer;
+use crate::cloud_provider::aws::databases::utilities::{
+    backup_managed_database, clone_managed_database, downgrade_managed_database, rds_name_sanitizer,
+    restore_managed_database, upgrade_managed_database,
+};
 use crate::cloud_provider::environment::Kind;
+use crate::cloud_provider::plan::ServicePlan;
 use crate::cloud_provider::service::{
-    check_service_version, default_tera_context, delete_stateful_service, deploy_stateful_service, get_tfstate_name,
-    get_tfstate_suffix, scale_down_database, send_progress_on_long_task, Action, Backup, Create, Database,
-    DatabaseOptions, DatabaseType, Delete, Downgrade, Helm, Pause, Service, ServiceType, StatefulService, Terraform,
-    Upgrade,
+    backup_stateful_service, check_service_version, clone_stateful_service, default_tera_context,
+    delete_database_backup_job, delete_stateful_service, deploy_stateful_service, downgrade_stateful_service,
+    get_tfstate_name, get_tfstate_suffix, plan_stateful_service, restore_stateful_service, scale_down_database,
+    send_progress_on_long_task, send_progress_on_long_task_with_message, upgrade_stateful_service, Action, Backup,
+    Create, Database, DatabaseBackupAction, DatabaseOptions, DatabaseType, Delete, Downgrade, Helm, Pause, Plan,
+    Service, ServiceType, StatefulService, Terraform, Upgrade,
 };
 use crate::cloud_provider::utilities::{
     generate_supported_version, get_self_hosted_postgres_version, get_supported_version_to_use,
@@ -177,6 +183,16 @@ impl Service for PostgreSQL {
         context.insert("database_ram_size_in_mib", &self.total_ram_in_mib);
         context.insert("database_total_cpus", &self.total_cpus);
         context.insert("database_fqdn", &self.options.host.as_str());
+        context.insert("publicly_accessible", &self.options.public_access.is_some());
+        context.insert(
+            "public_ports",
+            &self
+                .options
+                .public_access
+                .as_ref()
+                .map(|public_access| public_access.to_public_port_data_templates())
+                .unwrap_or_default(),
+        );
         context.insert("database_id", &self.id());
         context.insert("tfstate_suffix_name", &get_tfstate_suffix(self));
         context.insert("tfstate_name", &get_tfstate_name(self));
@@ -242,7 +258,27 @@ impl Create for PostgreSQL {
         info!("AWS.PostgreSQL.on_create() called for {}", self.name());
 
         send_progress_on_long_task(self, crate::cloud_provider::service::Action::Create, || {
-            deploy_stateful_service(target, self)
+            deploy_stateful_service(target, self)?;
+
+            // managed databases are exposed by the cloud provider, not by a load balancer of the cluster
+            match (target, &self.options.public_access) {
+                (DeploymentTarget::SelfHosted(..), Some(public_access)) => {
+                    crate::cloud_provider::service::report_public_access_address(
+                        target,
+                        self,
+                        public_access,
+                        |kubernetes_config, namespace, selector, envs| {
+                            crate::cmd::kubectl::kubectl_exec_get_external_ingress_hostname(
+                                kubernetes_config,
+                                namespace,
+                                selector,
+                                envs,
+                            )
+                        },
+                    )
+                }
+                _ => Ok(()),
+            }
         })
     }
 
@@ -297,71 +333,165 @@ impl Delete for PostgreSQL {
     }
 }
 
+impl Plan for PostgreSQL {
+    fn on_plan(&self, target: &DeploymentTarget) -> Result<ServicePlan, EngineError> {
+        info!("AWS.PostgreSQL.on_plan() called for {}", self.name());
+
+        let is_managed_services = match target {
+            DeploymentTarget::ManagedServices(_, _) => true,
+            DeploymentTarget::SelfHosted(_, _) => false,
+        };
+
+        plan_stateful_service(
+            target,
+            self,
+            self.matching_correct_version(is_managed_services)?.as_str(),
+        )
+    }
+}
+
 impl crate::cloud_provider::service::Clone for PostgreSQL {
-    fn on_clone(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_clone(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("AWS.PostgreSQL.on_clone() called for {}", self.name());
+
+        send_progress_on_long_task_with_message(
+            self,
+            Some(format!(
+                "{} '{}' clone is in progress...",
+                self.service_type().name(),
+                self.name_with_id()
+            )),
+            Action::Create,
+            || match target {
+                DeploymentTarget::ManagedServices(_, _) => clone_managed_database(target, self),
+                DeploymentTarget::SelfHosted(_, _) => clone_stateful_service(target, self),
+            },
+        )
     }
 
     fn on_clone_check(&self) -> Result<(), EngineError> {
-        unimplemented!()
+        Ok(())
     }
 
-    fn on_clone_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_clone_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("AWS.PostgreSQL.on_clone_error() called for {}", self.name());
+
+        let _ = delete_database_backup_job(target, self, DatabaseBackupAction::Clone)?;
+        delete_database_backup_job(target, self, DatabaseBackupAction::Restore)
     }
 }
 
 impl Upgrade for PostgreSQL {
-    fn on_upgrade(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_upgrade(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("AWS.PostgreSQL.on_upgrade() called for {}", self.name());
+
+        send_progress_on_long_task(self, Action::Create, || match target {
+            DeploymentTarget::ManagedServices(_, _) => {
+                upgrade_managed_database(target, self, self.matching_correct_version(true)?.as_str())
+            }
+            DeploymentTarget::SelfHosted(_, _) => {
+                upgrade_stateful_service(target, self, self.matching_correct_version(false)?.as_str())
+            }
+        })
     }
 
     fn on_upgrade_check(&self) -> Result<(), EngineError> {
-        unimplemented!()
+        Ok(())
     }
 
-    fn on_upgrade_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_upgrade_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("AWS.PostgreSQL.on_upgrade_error() called for {}", self.name());
+
+        let _ = delete_database_backup_job(target, self, DatabaseBackupAction::Backup)?;
+        delete_database_backup_job(target, self, DatabaseBackupAction::Restore)
     }
 }
 
 impl Downgrade for PostgreSQL {
-    fn on_downgrade(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_downgrade(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("AWS.PostgreSQL.on_downgrade() called for {}", self.name());
+
+        send_progress_on_long_task(self, Action::Create, || match target {
+            DeploymentTarget::ManagedServices(_, _) => {
+                downgrade_managed_database(target, self, self.matching_correct_version(true)?.as_str())
+            }
+            DeploymentTarget::SelfHosted(_, _) => {
+                downgrade_stateful_service(target, self, self.matching_correct_version(false)?.as_str())
+            }
+        })
     }
 
     fn on_downgrade_check(&self) -> Result<(), EngineError> {
-        unimplemented!()
+        Ok(())
     }
 
-    fn on_downgrade_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_downgrade_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("AWS.PostgreSQL.on_downgrade_error() called for {}", self.name());
+
+        let _ = delete_database_backup_job(target, self, DatabaseBackupAction::Backup)?;
+        delete_database_backup_job(target, self, DatabaseBackupAction::Restore)
     }
 }
 
 impl Backup for PostgreSQL {
-    fn on_backup(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_backup(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("AWS.PostgreSQL.on_backup() called for {}", self.name());
+
+        send_progress_on_long_task_with_message(
+            self,
+            Some(format!(
+                "{} '{}' backup is in progress...",
+                self.service_type().name(),
+                self.name_with_id()
+            )),
+            Action::Create,
+            || match target {
+                DeploymentTarget::ManagedServices(_, _) => backup_managed_database(target, self),
+                DeploymentTarget::SelfHosted(_, _) => backup_stateful_service(target, self),
+            },
+        )
     }
 
     fn on_backup_check(&self) -> Result<(), EngineError> {
-        unimplemented!()
+        Ok(())
     }
 
-    fn on_backup_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_backup_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("AWS.PostgreSQL.on_backup_error() called for {}", self.name());
+
+        delete_database_backup_job(target, self, DatabaseBackupAction::Backup)
     }
 
-    fn on_restore(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_restore(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("AWS.PostgreSQL.on_restore() called for {}", self.name());
+
+        send_progress_on_long_task_with_message(
+            self,
+            Some(format!(
+                "{} '{}' restore is in progress...",
+                self.service_type().name(),
+                self.name_with_id()
+            )),
+            Action::Create,
+            || match target {
+                DeploymentTarget::ManagedServices(_, _) => {
+                    restore_managed_database(target, self, self.options.restore_from.as_ref())
+                }
+                DeploymentTarget::SelfHosted(_, _) => {
+                    restore_stateful_service(target, self, self.options.restore_from.as_ref())
+                }
+            },
+        )
     }
 
     fn on_restore_check(&self) -> Result<(), EngineError> {
-        unimplemented!()
+        Ok(())
     }
 
-    fn on_restore_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_restore_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("AWS.PostgreSQL.on_restore_error() called for {}", self.name());
+
+        delete_database_backup_job(target, self, DatabaseBackupAction::Restore)
     }
 }
 
@@ -466,6 +596,8 @@ mod tests_postgres {
                 port: 5432,
                 disk_size_in_gib: 10,
                 database_disk_type: "gp2".to_string(),
+                restore_from: None,
+                public_access: None,
             },
             vec![],
         );
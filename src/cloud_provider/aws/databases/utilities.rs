@@ -1,6 +1,18 @@
-use crate::cloud_provider::utilities::get_version_number;
-use crate::error::StringError;
-use crate::models::DatabaseKind;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+
+use tera::Context as TeraContext;
+
+use crate::cloud_provider::kubernetes::Kubernetes;
+use crate::cloud_provider::service::{
+    get_tfstate_name, DatabaseRestoreSource, DatabaseType, Helm, ServiceType, StatefulService, Terraform,
+};
+use crate::cloud_provider::utilities::{compare_database_major_versions, get_version_number};
+use crate::cloud_provider::DeploymentTarget;
+use crate::error::{cast_simple_error_to_engine_error, EngineError, EngineErrorCause, StringError};
+use crate::models::{DatabaseKind, ListenersHelper, ProgressInfo, ProgressLevel};
 
 pub fn rds_name_sanitizer(max_size: usize, prefix: &str, name: &str) -> String {
     let max_size = max_size - prefix.len();
@@ -35,9 +47,620 @@ pub fn get_parameter_group_from_version(version: &str, database_kind: DatabaseKi
     }
 }
 
+/// manual snapshot identifier, unique per database and per execution.
+/// only letters, digits and single hyphens are allowed by RDS, DocumentDB and ElastiCache.
+pub fn managed_database_snapshot_name(sanitized_name: &str, execution_id: &str) -> String {
+    let mut snapshot_name = String::new();
+
+    for c in format!("{}-{}", sanitized_name, execution_id).to_lowercase().chars() {
+        let c = if c.is_ascii_alphanumeric() { c } else { '-' };
+        if c == '-' && (snapshot_name.is_empty() || snapshot_name.ends_with('-')) {
+            continue;
+        }
+
+        snapshot_name.push(c);
+    }
+
+    crate::string::cut(snapshot_name.trim_end_matches('-').to_string(), 255)
+}
+
+/// create a manual snapshot of a managed database (RDS, DocumentDB or ElastiCache).
+/// the snapshot is named and tagged after the execution id, and outlives the database.
+pub fn backup_managed_database<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+where
+    T: StatefulService + Terraform,
+{
+    let database = managed_database(target, service)?;
+    let snapshot_name =
+        managed_database_snapshot_name(service.sanitized_name().as_str(), service.context().execution_id());
+
+    let _ = snapshot_managed_database(target, service, &database, snapshot_name.as_str(), None)?;
+
+    Ok(())
+}
+
+/// recreate a managed database from a snapshot, or from a point in time for RDS,
+/// by forcing the re-creation of the database through its own Terraform workspace.
+/// the restore source is checked and the current database is snapshotted before anything is replaced.
+pub fn restore_managed_database<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    restore_from: Option<&DatabaseRestoreSource>,
+) -> Result<(), EngineError>
+where
+    T: StatefulService + Helm + Terraform,
+{
+    let restore_from = match restore_from {
+        Some(restore_from) => restore_from,
+        None => {
+            info!("nothing to restore for {}", service.name_with_id());
+            return Ok(());
+        }
+    };
+
+    let database = managed_database(target, service)?;
+
+    if let Err(message) = check_managed_database_restore_source(database.kind, restore_from, Utc::now()) {
+        return Err(service.engine_error(
+            EngineErrorCause::User(message),
+            format!(
+                "can't restore {} {}: {}",
+                service.service_type().name(),
+                service.name_with_id(),
+                message
+            ),
+        ));
+    }
+
+    let restore_source = find_managed_database_restore_source(target, service, &database, restore_from)?;
+
+    // the database is only replaced by a real deployment
+    if service.context().is_dry_run_deploy() {
+        return Ok(());
+    }
+
+    // the database is replaced, its current state is kept in a snapshot
+    let snapshot_name = managed_database_snapshot_name(
+        service.sanitized_name().as_str(),
+        format!("pre-restore-{}", service.context().execution_id()).as_str(),
+    );
+    let _ = snapshot_managed_database(target, service, &database, snapshot_name.as_str(), None)?;
+
+    let mut context = service.tera_context(target)?;
+
+    match restore_from {
+        DatabaseRestoreSource::Snapshot { snapshot_id } => {
+            let mut snapshot = HashMap::new();
+            snapshot.insert("snapshot_id", snapshot_id.as_str());
+            context.insert("snapshot", &snapshot);
+        }
+        DatabaseRestoreSource::PointInTime { restore_time } => {
+            context.insert("restore_time", &restore_time.to_rfc3339_opts(SecondsFormat::Secs, true));
+            context.insert("restore_source_resource_id", restore_source.as_str());
+            // the automated backups of the replaced instance are the restore source, they must outlive it
+            context.insert("delete_automated_backups", &false);
+        }
+        // refused by check_managed_database_restore_source
+        DatabaseRestoreSource::Backup { .. } => {}
+    };
+
+    apply_managed_database_workspace(service, &context, Some(database.terraform_resource))
+}
+
+/// check the restore source of a managed database before anything is applied
+fn check_managed_database_restore_source(
+    database_kind: &str,
+    restore_from: &DatabaseRestoreSource,
+    now: DateTime<Utc>,
+) -> Result<(), &'static str> {
+    match restore_from {
+        DatabaseRestoreSource::Snapshot { snapshot_id } => {
+            if snapshot_id.is_empty() {
+                return Err("A snapshot id is required to restore a managed database");
+            }
+
+            // the snapshot id ends up in Terraform templates and aws cli commands
+            if !snapshot_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == ':')
+            {
+                return Err("Snapshot ids can only contain letters, digits, hyphens and colons");
+            }
+
+            Ok(())
+        }
+        DatabaseRestoreSource::PointInTime { .. } if database_kind != "postgresql" && database_kind != "mysql" => {
+            Err("Point in time restores are only available for PostgreSQL and MySQL databases")
+        }
+        DatabaseRestoreSource::PointInTime { restore_time } if *restore_time > now => {
+            Err("A database can't be restored to a point in time in the future")
+        }
+        DatabaseRestoreSource::PointInTime { .. } => Ok(()),
+        DatabaseRestoreSource::Backup { .. } => {
+            Err("Managed databases can only be restored from a snapshot or a point in time")
+        }
+    }
+}
+
+/// ensure the snapshot or the instance to restore from exists, in a read only Terraform workspace.
+/// returns the snapshot id, or the resource id of the instance for a point in time restore.
+fn find_managed_database_restore_source<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    database: &ManagedDatabase,
+    restore_from: &DatabaseRestoreSource,
+) -> Result<String, EngineError>
+where
+    T: StatefulService + Terraform,
+{
+    let workspace_dir = format!(
+        "{}/restore-source-{}",
+        service.workspace_directory(),
+        service.context().execution_id()
+    );
+
+    let mut context = service.tera_context(target)?;
+    context.insert("restore_database_kind", database.kind);
+    if let DatabaseRestoreSource::Snapshot { snapshot_id } = restore_from {
+        context.insert("restore_snapshot_id", snapshot_id.as_str());
+    }
+
+    let _ = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::template::generate_and_copy_all_files_into_dir(
+            format!("{}/aws/services/restore-source", service.context().lib_root_dir()),
+            workspace_dir.as_str(),
+            &context,
+        ),
+    )?;
+
+    // the data sources are read by the plan, a missing source fails before the database is touched
+    if let Err(err) = crate::cmd::terraform::terraform_init_validate_plan_apply(
+        workspace_dir.as_str(),
+        service.context().is_dry_run_deploy(),
+    ) {
+        return Err(service.engine_error(
+            EngineErrorCause::User("The snapshot or the database to restore from can't be found"),
+            format!(
+                "can't find the restore source of {} {}: {:?}",
+                service.service_type().name(),
+                service.name_with_id(),
+                err.message
+            ),
+        ));
+    }
+
+    if service.context().is_dry_run_deploy() {
+        return Ok(String::new());
+    }
+
+    let output = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::terraform::terraform_exec(workspace_dir.as_str(), vec!["output", "-json", "restore_source"]),
+    )?;
+
+    match serde_json::from_str::<String>(output.join("").as_str()) {
+        Ok(restore_source) => Ok(restore_source),
+        Err(err) => Err(service.engine_error(
+            EngineErrorCause::Internal,
+            format!("can't read the restore source from Terraform output: {:?}", err),
+        )),
+    }
+}
+
+/// create a managed database, on its first deployment, from a snapshot of the database having the same name
+/// in the environment it is cloned from.
+/// Note: the master username of a database created from a snapshot is the one of the cloned database.
+pub fn clone_managed_database<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+where
+    T: StatefulService + Helm + Terraform,
+{
+    let database = managed_database(target, service)?;
+    let environment = match target {
+        DeploymentTarget::ManagedServices(_, environment) => *environment,
+        DeploymentTarget::SelfHosted(_, environment) => *environment,
+    };
+
+    let source_environment_id = match environment.clone_from_environment_id.as_ref() {
+        Some(environment_id) => environment_id,
+        None => return Ok(()),
+    };
+
+    // the tfstate only exists once the database has been created
+    let tfstate_secrets = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::kubectl::kubectl_exec_get_secrets(
+            database.kubernetes.config_file_path()?.as_str(),
+            environment.namespace(),
+            "app.kubernetes.io/managed-by=terraform,tfstate=true",
+            database.kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )?;
+
+    if tfstate_secrets
+        .items
+        .iter()
+        .any(|secret| secret.metadata.name == get_tfstate_name(service))
+    {
+        info!("{} already exists, it is not cloned again", service.name_with_id());
+        return Ok(());
+    }
+
+    let snapshot_name = managed_database_snapshot_name(
+        service.sanitized_name().as_str(),
+        format!("clone-{}", service.context().execution_id()).as_str(),
+    );
+
+    let snapshot_name = match snapshot_managed_database(
+        target,
+        service,
+        &database,
+        snapshot_name.as_str(),
+        Some(source_environment_id.as_str()),
+    )? {
+        Some(snapshot_name) => snapshot_name,
+        None => {
+            info!(
+                "there is no {} named {} in environment {}, nothing to clone",
+                service.service_type().name(),
+                service.name(),
+                source_environment_id
+            );
+            return Ok(());
+        }
+    };
+
+    let mut snapshot = HashMap::new();
+    snapshot.insert("snapshot_id", snapshot_name.as_str());
+
+    let mut context = service.tera_context(target)?;
+    context.insert("snapshot", &snapshot);
+
+    apply_managed_database_workspace(service, &context, None)
+}
+
+/// upgrade the major version of an RDS instance in place, other version changes are regular deployments.
+/// DocumentDB and ElastiCache versions are left to their own Terraform workspace.
+pub fn upgrade_managed_database<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    requested_version: &str,
+) -> Result<(), EngineError>
+where
+    T: StatefulService + Helm + Terraform,
+{
+    let database = managed_database(target, service)?;
+    let deployed_version = match managed_database_deployed_version(target, service, &database)? {
+        Some(version) => version,
+        None => return Ok(()),
+    };
+
+    if compare_managed_database_versions(service, &database, deployed_version.as_str(), requested_version)?
+        != Ordering::Less
+    {
+        return Ok(());
+    }
+
+    ListenersHelper::new(database.kubernetes.listeners()).deployment_in_progress(ProgressInfo::new(
+        service.progress_scope(),
+        ProgressLevel::Info,
+        Some(format!(
+            "{} '{}' is upgraded from version {} to {}",
+            service.service_type().name(),
+            service.name_with_id(),
+            deployed_version,
+            requested_version
+        )),
+        service.context().execution_id(),
+    ));
+
+    let mut context = service.tera_context(target)?;
+    context.insert("allow_major_version_upgrade", &true);
+
+    apply_managed_database_workspace(service, &context, None)
+}
+
+/// RDS can't downgrade the major version of an instance, the request is refused before anything is applied
+pub fn downgrade_managed_database<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    requested_version: &str,
+) -> Result<(), EngineError>
+where
+    T: StatefulService + Helm + Terraform,
+{
+    let database = managed_database(target, service)?;
+    let deployed_version = match managed_database_deployed_version(target, service, &database)? {
+        Some(version) => version,
+        None => return Ok(()),
+    };
+
+    if compare_managed_database_versions(service, &database, deployed_version.as_str(), requested_version)?
+        != Ordering::Greater
+    {
+        return Ok(());
+    }
+
+    Err(service.engine_error(
+        EngineErrorCause::User(
+            "Managed databases can't be downgraded to an older major version, \
+            restore a snapshot taken with the requested version instead",
+        ),
+        format!(
+            "can't downgrade {} {} from version {} to {}",
+            service.service_type().name(),
+            service.name_with_id(),
+            deployed_version,
+            requested_version
+        ),
+    ))
+}
+
+/// version of the RDS instance, if it is deployed. It is read from an annotation of its external name service
+/// to avoid going through its Terraform workspace on every deployment.
+fn managed_database_deployed_version<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    database: &ManagedDatabase,
+) -> Result<Option<String>, EngineError>
+where
+    T: StatefulService + Helm + Terraform,
+{
+    if database.kind != "postgresql" && database.kind != "mysql" {
+        return Ok(None);
+    }
+
+    let environment = match target {
+        DeploymentTarget::ManagedServices(_, environment) => *environment,
+        DeploymentTarget::SelfHosted(_, environment) => *environment,
+    };
+
+    let annotations = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::kubectl::kubectl_exec_get_service_annotations(
+            database.kubernetes.config_file_path()?.as_str(),
+            environment.namespace(),
+            format!("{}-dns", service.id()).as_str(),
+            database.kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )?;
+
+    // services deployed before the annotation existed don't have it until the next deployment
+    Ok(annotations.and_then(|annotations| annotations.get(MANAGED_DATABASE_VERSION_ANNOTATION).cloned()))
+}
+
+fn compare_managed_database_versions<T>(
+    service: &T,
+    database: &ManagedDatabase,
+    deployed_version: &str,
+    requested_version: &str,
+) -> Result<Ordering, EngineError>
+where
+    T: StatefulService,
+{
+    let database_kind = match database.kind {
+        "postgresql" => DatabaseKind::Postgresql,
+        "mysql" => DatabaseKind::Mysql,
+        "mongodb" => DatabaseKind::Mongodb,
+        _ => DatabaseKind::Redis,
+    };
+
+    compare_database_major_versions(&database_kind, deployed_version, requested_version)
+        .map_err(|err| service.engine_error(EngineErrorCause::Internal, err))
+}
+
+// set by the external-name-svc chart
+const MANAGED_DATABASE_VERSION_ANNOTATION: &str = "qovery.com/engine-version";
+
+struct ManagedDatabase<'a> {
+    kubernetes: &'a dyn Kubernetes,
+    // matches the Terraform templates
+    kind: &'static str,
+    terraform_resource: &'static str,
+    // resource type of the AWS resource groups tagging API
+    resource_type: &'static str,
+}
+
+fn managed_database<'a, T>(target: &DeploymentTarget<'a>, service: &T) -> Result<ManagedDatabase<'a>, EngineError>
+where
+    T: StatefulService,
+{
+    let kubernetes = match target {
+        DeploymentTarget::ManagedServices(kubernetes, _) => *kubernetes,
+        DeploymentTarget::SelfHosted(_, _) => {
+            return Err(service.engine_error(
+                EngineErrorCause::Internal,
+                format!("{} is not a managed service", service.name_with_id()),
+            ))
+        }
+    };
+
+    let (kind, terraform_resource, resource_type) = match service.service_type() {
+        ServiceType::Database(DatabaseType::PostgreSQL(_)) => {
+            ("postgresql", "aws_db_instance.postgresql_instance", "rds:db")
+        }
+        ServiceType::Database(DatabaseType::MySQL(_)) => ("mysql", "aws_db_instance.mysql_instance", "rds:db"),
+        ServiceType::Database(DatabaseType::MongoDB(_)) => {
+            ("mongodb", "aws_docdb_cluster.documentdb_cluster", "rds:cluster")
+        }
+        ServiceType::Database(DatabaseType::Redis(_)) => (
+            "redis",
+            "aws_elasticache_cluster.elasticache_cluster",
+            "elasticache:cluster",
+        ),
+        _ => {
+            return Err(service.engine_error(
+                EngineErrorCause::Internal,
+                format!("{} is not a database", service.name_with_id()),
+            ))
+        }
+    };
+
+    Ok(ManagedDatabase {
+        kubernetes,
+        kind,
+        terraform_resource,
+        resource_type,
+    })
+}
+
+/// snapshot the database, or the one with the same name in the source environment.
+/// returns the name of the snapshot if there was something to snapshot.
+fn snapshot_managed_database<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    database: &ManagedDatabase,
+    snapshot_name: &str,
+    source_environment_id: Option<&str>,
+) -> Result<Option<String>, EngineError>
+where
+    T: StatefulService + Terraform,
+{
+    let workspace_dir = format!("{}/snapshot-{}", service.workspace_directory(), snapshot_name);
+
+    let mut context = service.tera_context(target)?;
+    context.insert("snapshot_database_kind", database.kind);
+    context.insert("snapshot_name", snapshot_name);
+    context.insert("execution_id", service.context().execution_id());
+    if let Some(source_environment_id) = source_environment_id {
+        context.insert("snapshot_source_environment_id", source_environment_id);
+        context.insert("snapshot_source_resource_type", database.resource_type);
+    }
+
+    let _ = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::template::generate_and_copy_all_files_into_dir(
+            format!("{}/aws/services/snapshot", service.context().lib_root_dir()),
+            workspace_dir.as_str(),
+            &context,
+        ),
+    )?;
+
+    let _ = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::terraform::terraform_init_validate_plan_apply(
+            workspace_dir.as_str(),
+            service.context().is_dry_run_deploy(),
+        ),
+    )?;
+
+    if service.context().is_dry_run_deploy() {
+        return Ok(None);
+    }
+
+    let output = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::terraform::terraform_exec(workspace_dir.as_str(), vec!["output", "-json", "snapshot_name"]),
+    )?;
+
+    let snapshot_name = match serde_json::from_str::<String>(output.join("").as_str()) {
+        Ok(snapshot_name) if snapshot_name.is_empty() => return Ok(None),
+        Ok(snapshot_name) => snapshot_name,
+        Err(err) => {
+            return Err(service.engine_error(
+                EngineErrorCause::Internal,
+                format!("can't read the snapshot name from Terraform output: {:?}", err),
+            ))
+        }
+    };
+
+    ListenersHelper::new(database.kubernetes.listeners()).deployment_in_progress(ProgressInfo::new(
+        service.progress_scope(),
+        ProgressLevel::Info,
+        Some(format!(
+            "{} '{}' snapshot {} has been created",
+            service.service_type().name(),
+            service.name_with_id(),
+            snapshot_name
+        )),
+        service.context().execution_id(),
+    ));
+
+    Ok(Some(snapshot_name))
+}
+
+/// render and apply the Terraform workspace of the database, optionally forcing the re-creation of a resource
+fn apply_managed_database_workspace<T>(
+    service: &T,
+    context: &TeraContext,
+    tainted_resource: Option<&str>,
+) -> Result<(), EngineError>
+where
+    T: StatefulService + Helm + Terraform,
+{
+    let workspace_dir = render_managed_database_workspace(service, context)?;
+
+    cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        match tainted_resource {
+            Some(resource) => crate::cmd::terraform::terraform_init_validate_taint_plan_apply(
+                workspace_dir.as_str(),
+                resource,
+                service.context().is_dry_run_deploy(),
+            ),
+            None => crate::cmd::terraform::terraform_init_validate_plan_apply(
+                workspace_dir.as_str(),
+                service.context().is_dry_run_deploy(),
+            ),
+        },
+    )
+}
+
+fn render_managed_database_workspace<T>(service: &T, context: &TeraContext) -> Result<String, EngineError>
+where
+    T: StatefulService + Helm + Terraform,
+{
+    let workspace_dir = service.workspace_directory();
+
+    let _ = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::template::generate_and_copy_all_files_into_dir(
+            service.terraform_common_resource_dir_path(),
+            workspace_dir.as_str(),
+            context,
+        ),
+    )?;
+
+    let _ = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::template::generate_and_copy_all_files_into_dir(
+            service.terraform_resource_dir_path(),
+            workspace_dir.as_str(),
+            context,
+        ),
+    )?;
+
+    let _ = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::template::generate_and_copy_all_files_into_dir(
+            service.helm_chart_external_name_service_dir(),
+            format!("{}/{}", workspace_dir, "external-name-svc"),
+            context,
+        ),
+    )?;
+
+    Ok(workspace_dir)
+}
+
 #[cfg(test)]
 mod tests_aws_databases_parameters {
-    use crate::cloud_provider::aws::databases::utilities::get_parameter_group_from_version;
+    use chrono::{Duration, Utc};
+
+    use crate::cloud_provider::aws::databases::utilities::{
+        check_managed_database_restore_source, get_parameter_group_from_version, managed_database_snapshot_name,
+    };
+    use crate::cloud_provider::service::DatabaseRestoreSource;
     use crate::models::DatabaseKind;
 
     #[test]
@@ -54,4 +677,49 @@ mod tests_aws_databases_parameters {
             "Can't determine the minor version, to select parameter group for Mysql version 8"
         );
     }
+
+    #[test]
+    fn check_managed_database_snapshot_name() {
+        assert_eq!(
+            managed_database_snapshot_name("postgresqlmydb", "2021-06-01T10:00:00.123456+00:00"),
+            "postgresqlmydb-2021-06-01t10-00-00-123456-00-00"
+        );
+        assert_eq!(
+            managed_database_snapshot_name("redisCache", "--exec__id--"),
+            "rediscache-exec-id"
+        );
+    }
+
+    #[test]
+    fn check_managed_database_restore_sources() {
+        let now = Utc::now();
+        let snapshot = |snapshot_id: &str| DatabaseRestoreSource::Snapshot {
+            snapshot_id: snapshot_id.to_string(),
+        };
+
+        assert!(check_managed_database_restore_source("redis", &snapshot("rediscache-2021-06-01"), now).is_ok());
+        assert!(check_managed_database_restore_source(
+            "mysql",
+            &snapshot("arn:aws:rds:us-east-2:123456789012:snapshot:mysqldb-1"),
+            now
+        )
+        .is_ok());
+        assert!(check_managed_database_restore_source("mysql", &snapshot(""), now).is_err());
+        assert!(check_managed_database_restore_source("redis", &snapshot("snap; rm -rf /"), now).is_err());
+
+        let point_in_time = |restore_time| DatabaseRestoreSource::PointInTime { restore_time };
+        assert!(
+            check_managed_database_restore_source("postgresql", &point_in_time(now - Duration::hours(1)), now).is_ok()
+        );
+        assert!(check_managed_database_restore_source("mysql", &point_in_time(now + Duration::hours(1)), now).is_err());
+        assert!(
+            check_managed_database_restore_source("mongodb", &point_in_time(now - Duration::hours(1)), now).is_err()
+        );
+
+        let backup = DatabaseRestoreSource::Backup {
+            database_id: "database-id".to_string(),
+            backup_name: "backup".to_string(),
+        };
+        assert!(check_managed_database_restore_source("postgresql", &backup, now).is_err());
+    }
 }
@@ -2,10 +2,11 @@ use tera::Context as TeraContext;
 
 use crate::build_platform::Image;
 use crate::cloud_provider::models::{EnvironmentVariable, EnvironmentVariableDataTemplate};
+use crate::cloud_provider::plan::ServicePlan;
 use crate::cloud_provider::service::{
     default_tera_context, delete_stateless_service, deploy_stateless_service_error, deploy_user_stateless_service,
-    send_progress_on_long_task, Action, Application as AApplication, Create, Delete, Helm, Pause, Service, ServiceType,
-    StatelessService,
+    plan_stateless_service, send_progress_on_long_task, Action, Application as AApplication, Create, Delete, Helm,
+    Pause, Plan, Service, ServiceType, StatelessService,
 };
 use crate::cloud_provider::DeploymentTarget;
 use crate::cmd::helm::Timeout;
@@ -169,6 +170,22 @@ impl Service for ExternalService {
 
         context.insert("environment_variables", &environment_variables);
 
+        match self.image.registry_name.as_ref() {
+            Some(registry_name) => {
+                context.insert("is_registry_secret", &true);
+                context.insert("registry_secret", registry_name);
+            }
+            None => {
+                context.insert("is_registry_secret", &false);
+            }
+        };
+
+        // an external service runs as a one-shot job of the q-job chart
+        context.insert("command", &Vec::<String>::new());
+        context.insert("cron_schedule", &None::<String>);
+        context.insert("job_max_restarts", &0);
+        context.insert("job_max_duration_in_seconds", &3600);
+
         Ok(context)
     }
 
@@ -244,6 +261,23 @@ impl Delete for ExternalService {
     }
 }
 
+impl Plan for ExternalService {
+    fn on_plan(&self, target: &DeploymentTarget) -> Result<ServicePlan, EngineError> {
+        info!("AWS.external_service.on_plan() called for {}", self.name());
+
+        plan_stateless_service(
+            target,
+            self,
+            Some(
+                self.image
+                    .registry_url
+                    .clone()
+                    .unwrap_or_else(|| self.image.name_with_tag()),
+            ),
+        )
+    }
+}
+
 impl Listen for ExternalService {
     fn listeners(&self) -> &Listeners {
         &self.listeners
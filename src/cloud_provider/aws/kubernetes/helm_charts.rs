@@ -99,6 +99,19 @@ pub fn aws_helm_charts(
         },
     };
 
+    // EBS CSI driver with the volume snapshot controller, to snapshot the applications storage
+    let aws_ebs_csi_driver = CommonChart {
+        chart_info: ChartInfo {
+            name: "aws-ebs-csi-driver".to_string(),
+            path: chart_path("charts/aws-ebs-csi-driver"),
+            values: vec![ChartSetValue {
+                key: "region".to_string(),
+                value: chart_config_prerequisites.region.clone(),
+            }],
+            ..Default::default()
+        },
+    };
+
     let mut aws_vpc_cni_chart = AwsVpcCniChart {
         chart_info: ChartInfo {
             name: "aws-vpc-cni".to_string(),
@@ -1179,6 +1192,7 @@ datasources:
     // chart deployment order matters!!!
     let level_1: Vec<Box<dyn HelmChart>> = vec![
         Box::new(q_storage_class),
+        Box::new(aws_ebs_csi_driver),
         Box::new(coredns_config),
         Box::new(aws_vpc_cni_chart),
     ];
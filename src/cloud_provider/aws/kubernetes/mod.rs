@@ -2,7 +2,7 @@ use std::env;
 use std::str::FromStr;
 
 use itertools::Itertools;
-use retry::delay::{Fibonacci, Fixed};
+use retry::delay::Fibonacci;
 use retry::Error::Operation;
 use retry::OperationResult;
 use rusoto_core::Region;
@@ -16,24 +16,17 @@ use crate::cloud_provider::aws::AWS;
 use crate::cloud_provider::environment::Environment;
 use crate::cloud_provider::helm::deploy_charts_levels;
 use crate::cloud_provider::kubernetes::{
-    is_kubernetes_upgrade_required, uninstall_cert_manager, Kind, Kubernetes, KubernetesNode, KubernetesNodesType,
-    KubernetesUpgradeStatus,
+    is_kubernetes_upgrade_required, Kind, Kubernetes, KubernetesNode, KubernetesNodesType, KubernetesUpgradeStatus,
 };
 use crate::cloud_provider::models::WorkerNodeDataTemplate;
+use crate::cloud_provider::plan::EnvironmentPlan;
 use crate::cloud_provider::{kubernetes, CloudProvider};
 use crate::cmd;
-use crate::cmd::kubectl::{
-    kubectl_exec_api_custom_metrics, kubectl_exec_get_all_namespaces, kubectl_exec_scale_replicas, ScalingKind,
-};
-use crate::cmd::structs::HelmChart;
+use crate::cmd::kubectl::{kubectl_exec_scale_replicas, ScalingKind};
 use crate::cmd::terraform::{terraform_exec, terraform_init_validate_plan_apply, terraform_init_validate_state_list};
-use crate::deletion_utilities::{get_firsts_namespaces_to_delete, get_qovery_managed_namespaces};
 use crate::dns_provider;
 use crate::dns_provider::DnsProvider;
-use crate::error::EngineErrorCause::Internal;
-use crate::error::{
-    cast_simple_error_to_engine_error, EngineError, EngineErrorCause, EngineErrorScope, SimpleError, SimpleErrorKind,
-};
+use crate::error::{cast_simple_error_to_engine_error, EngineError, EngineErrorCause, EngineErrorScope};
 use crate::fs::workspace_directory;
 use crate::models::{
     Context, Features, Listen, Listener, Listeners, ListenersHelper, ProgressInfo, ProgressLevel, ProgressScope,
@@ -336,6 +329,7 @@ impl<'a> EKS<'a> {
         context.insert("aws_terraform_backend_dynamodb_table", "qovery-terrafom-tfstates");
         context.insert("vpc_cidr_block", &vpc_cidr_block);
         context.insert("s3_kubeconfig_bucket", &self.kubeconfig_bucket_name());
+        context.insert("s3_database_backups_bucket", &self.database_backups_bucket_name());
 
         // AWS - EKS
         context.insert("eks_cidr_subnet", &eks_cidr_subnet.clone());
@@ -1014,70 +1008,8 @@ impl<'a> Kubernetes for EKS<'a> {
             });
         }
 
-        let kubernetes_config_file_path = self.config_file_path()?;
-
-        // pause: wait 1h for the engine to have 0 running jobs before pausing and avoid getting unreleased lock (from helm or terraform for example)
-        let metric_name = "taskmanager_nb_running_tasks";
-        let wait_engine_job_finish = retry::retry(Fixed::from_millis(60000).take(60), || {
-            return match kubectl_exec_api_custom_metrics(
-                &kubernetes_config_file_path,
-                self.cloud_provider().credentials_environment_variables(),
-                "qovery",
-                None,
-                metric_name,
-            ) {
-                Ok(metrics) => {
-                    let mut current_engine_jobs = 0;
-
-                    for metric in metrics.items {
-                        match metric.value.parse::<i32>() {
-                            Ok(job_count) if job_count > 0 => current_engine_jobs += 1,
-                            Err(e) => {
-                                error!("error while looking at the API metric value {}. {:?}", metric_name, e);
-                                return OperationResult::Retry(SimpleError {
-                                    kind: SimpleErrorKind::Other,
-                                    message: Some(e.to_string()),
-                                });
-                            }
-                            _ => {}
-                        }
-                    }
-
-                    if current_engine_jobs == 0 {
-                        OperationResult::Ok(())
-                    } else {
-                        OperationResult::Retry(SimpleError {
-                            kind: SimpleErrorKind::Other,
-                            message: Some("can't pause the infrastructure now, Engine jobs are currently running, retrying later...".to_string()),
-                        })
-                    }
-                }
-                Err(e) => {
-                    error!("error while looking at the API metric value {}. {:?}", metric_name, e);
-                    OperationResult::Retry(e)
-                }
-            };
-        });
-
-        match wait_engine_job_finish {
-            Ok(_) => info!("no current running jobs on the Engine, infrastructure pause is allowed to start"),
-            Err(Operation { error, .. }) => {
-                return Err(EngineError {
-                    cause: EngineErrorCause::Internal,
-                    scope: EngineErrorScope::Engine,
-                    execution_id: self.context.execution_id().to_string(),
-                    message: error.message,
-                })
-            }
-            Err(retry::Error::Internal(msg)) => {
-                return Err(EngineError::new(
-                    EngineErrorCause::Internal,
-                    EngineErrorScope::Engine,
-                    self.context.execution_id(),
-                    Some(msg),
-                ))
-            }
-        }
+        // pause: wait for the engine to have 0 running jobs before pausing and avoid getting unreleased lock (from helm or terraform for example)
+        let _ = kubernetes::wait_for_engine_jobs_to_finish(self)?;
 
         let mut terraform_args_string = vec!["apply".to_string(), "-auto-approve".to_string()];
         for x in tf_workers_resources {
@@ -1163,13 +1095,6 @@ impl<'a> Kubernetes for EKS<'a> {
             ),
         )?;
 
-        let kubernetes_config_file_path = self.config_file_path()?;
-
-        let all_namespaces = kubectl_exec_get_all_namespaces(
-            &kubernetes_config_file_path,
-            self.cloud_provider().credentials_environment_variables(),
-        );
-
         // should apply before destroy to be sure destroy will compute on all resources
         // don't exit on failure, it can happen if we resume a destroy process
         let message = format!(
@@ -1189,158 +1114,8 @@ impl<'a> Kubernetes for EKS<'a> {
             error!("An issue occurred during the apply before destroy of Terraform, it may be expected if you're resuming a destroy: {:?}", e.message);
         };
 
-        // should make the diff between all namespaces and qovery managed namespaces
-        let message = format!(
-            "Deleting all non-Qovery deployed applications and dependencies for cluster {}/{}",
-            self.name(),
-            self.id()
-        );
-        info!("{}", &message);
-        send_to_customer(&message);
-
-        match all_namespaces {
-            Ok(namespace_vec) => {
-                let namespaces_as_str = namespace_vec.iter().map(std::ops::Deref::deref).collect();
-                let namespaces_to_delete = get_firsts_namespaces_to_delete(namespaces_as_str);
-
-                info!("Deleting non Qovery namespaces");
-                for namespace_to_delete in namespaces_to_delete.iter() {
-                    info!("Starting namespace {} deletion process", namespace_to_delete);
-                    let deletion = cmd::kubectl::kubectl_exec_delete_namespace(
-                        &kubernetes_config_file_path,
-                        namespace_to_delete,
-                        self.cloud_provider().credentials_environment_variables(),
-                    );
-
-                    match deletion {
-                        Ok(_) => info!("Namespace {} is deleted", namespace_to_delete),
-                        Err(e) => {
-                            if e.message.is_some() && e.message.unwrap().contains("not found") {
-                                {}
-                            } else {
-                                error!("Can't delete the namespace {}", namespace_to_delete);
-                            }
-                        }
-                    }
-                }
-            }
-
-            Err(e) => error!(
-                "Error while getting all namespaces for Kubernetes cluster {}: error {:?}",
-                self.name_with_id(),
-                e.message
-            ),
-        }
-
-        let message = format!(
-            "Deleting all Qovery deployed elements and associated dependencies for cluster {}/{}",
-            self.name(),
-            self.id()
-        );
-        info!("{}", &message);
-        send_to_customer(&message);
-
-        // delete custom metrics api to avoid stale namespaces on deletion
-        let _ = cmd::helm::helm_uninstall_list(
-            &kubernetes_config_file_path,
-            vec![HelmChart {
-                name: "metrics-server".to_string(),
-                namespace: "kube-system".to_string(),
-            }],
-            self.cloud_provider().credentials_environment_variables(),
-        );
-
-        // required to avoid namespace stuck on deletion
-        match uninstall_cert_manager(
-            &kubernetes_config_file_path,
-            self.cloud_provider().credentials_environment_variables(),
-        ) {
-            Ok(_) => {}
-            Err(e) => {
-                return Err(EngineError::new(
-                    Internal,
-                    self.engine_error_scope(),
-                    self.context().execution_id(),
-                    e.message,
-                ))
-            }
-        };
-
-        info!("Deleting Qovery managed helm charts");
-        let qovery_namespaces = get_qovery_managed_namespaces();
-        for qovery_namespace in qovery_namespaces.iter() {
-            info!(
-                "Starting Qovery managed charts deletion process in {} namespace",
-                qovery_namespace
-            );
-            let charts_to_delete = cmd::helm::helm_list(
-                &kubernetes_config_file_path,
-                self.cloud_provider().credentials_environment_variables(),
-                Some(qovery_namespace),
-            );
-            match charts_to_delete {
-                Ok(charts) => {
-                    for chart in charts {
-                        info!("Deleting chart {} in {} namespace", chart.name, chart.namespace);
-                        match cmd::helm::helm_exec_uninstall(
-                            &kubernetes_config_file_path,
-                            &chart.namespace,
-                            &chart.name,
-                            self.cloud_provider().credentials_environment_variables(),
-                        ) {
-                            Ok(_) => info!("chart {} deleted", chart.name),
-                            Err(e) => error!("{:?}", e),
-                        }
-                    }
-                }
-                Err(e) => {
-                    if e.message.is_some() && e.message.unwrap().contains("not found") {
-                        {}
-                    } else {
-                        error!("Can't delete the namespace {}", qovery_namespace);
-                    }
-                }
-            }
-        }
-
-        info!("Deleting Qovery managed Namespaces");
-        for qovery_namespace in qovery_namespaces.iter() {
-            info!("Starting namespace {} deletion process", qovery_namespace);
-            let deletion = cmd::kubectl::kubectl_exec_delete_namespace(
-                &kubernetes_config_file_path,
-                qovery_namespace,
-                self.cloud_provider().credentials_environment_variables(),
-            );
-            match deletion {
-                Ok(_) => info!("Namespace {} is fully deleted", qovery_namespace),
-                Err(e) => {
-                    if e.message.is_some() && e.message.unwrap().contains("not found") {
-                        {}
-                    } else {
-                        error!("Can't delete the namespace {}", qovery_namespace);
-                    }
-                }
-            }
-        }
-
-        info!("Delete all remaining deployed helm applications");
-        match cmd::helm::helm_list(
-            &kubernetes_config_file_path,
-            self.cloud_provider().credentials_environment_variables(),
-            None,
-        ) {
-            Ok(helm_charts) => {
-                for chart in helm_charts {
-                    info!("Deleting chart {} in progress...", chart.name);
-                    let _ = cmd::helm::helm_uninstall_list(
-                        &kubernetes_config_file_path,
-                        vec![chart],
-                        self.cloud_provider().credentials_environment_variables(),
-                    );
-                }
-            }
-            Err(_) => error!("Unable to get helm list"),
-        }
+        // delete everything deployed on the cluster, not to leave cloud resources behind (e.g. load balancers)
+        let _ = kubernetes::delete_all_deployments(self, &send_to_customer)?;
 
         let message = format!("Deleting Kubernetes cluster {}/{}", self.name(), self.id());
         info!("{}", &message);
@@ -1419,6 +1194,26 @@ impl<'a> Kubernetes for EKS<'a> {
         warn!("EKS.delete_environment_error() called for {}", self.name());
         Ok(())
     }
+
+    fn backup_environment(&self, environment: &Environment) -> Result<(), EngineError> {
+        info!("EKS.backup_environment() called for {}", self.name());
+        kubernetes::backup_environment(self, environment)
+    }
+
+    fn restore_environment(&self, environment: &Environment) -> Result<(), EngineError> {
+        info!("EKS.restore_environment() called for {}", self.name());
+        kubernetes::restore_environment(self, environment)
+    }
+
+    fn clone_environment(&self, environment: &Environment) -> Result<(), EngineError> {
+        info!("EKS.clone_environment() called for {}", self.name());
+        kubernetes::clone_environment(self, environment)
+    }
+
+    fn plan_environment(&self, environment: &Environment) -> Result<EnvironmentPlan, EngineError> {
+        info!("EKS.plan_environment() called for {}", self.name());
+        kubernetes::plan_environment(self, environment)
+    }
 }
 
 impl<'a> Listen for EKS<'a> {
@@ -13,6 +13,7 @@ use crate::runtime::block_on;
 pub mod application;
 pub mod databases;
 pub mod external_service;
+pub mod job;
 pub mod kubernetes;
 pub mod router;
 
@@ -1,10 +1,13 @@
 use tera::Context as TeraContext;
 
+use crate::cloud_provider::plan::ServicePlan;
 use crate::cloud_provider::service::{
-    check_service_version, default_tera_context, delete_stateful_service, deploy_stateful_service, get_tfstate_name,
-    get_tfstate_suffix, scale_down_database, send_progress_on_long_task, Action, Backup, Create, Database,
-    DatabaseOptions, DatabaseType, Delete, Downgrade, Helm, Pause, Service, ServiceType, StatefulService, Terraform,
-    Upgrade,
+    backup_stateful_service, check_service_version, clone_stateful_service, default_tera_context,
+    delete_database_backup_job, delete_stateful_service, deploy_stateful_service, downgrade_stateful_service,
+    get_tfstate_name, get_tfstate_suffix, plan_stateful_service, restore_stateful_service, scale_down_database,
+    send_progress_on_long_task, send_progress_on_long_task_with_message, upgrade_stateful_service, Action, Backup,
+    Create, Database, DatabaseBackupAction, DatabaseOptions, DatabaseType, Delete, Downgrade, Helm, Pause, Plan,
+    Service, ServiceType, StatefulService, Terraform, Upgrade,
 };
 use crate::cloud_provider::utilities::{get_self_hosted_mongodb_version, sanitize_name};
 use crate::cloud_provider::DeploymentTarget;
@@ -162,6 +165,16 @@ impl Service for MongoDB {
         context.insert("database_ram_size_in_mib", &self.total_ram_in_mib);
         context.insert("database_total_cpus", &self.total_cpus);
         context.insert("database_fqdn", &self.options.host.as_str());
+        context.insert("publicly_accessible", &self.options.public_access.is_some());
+        context.insert(
+            "public_ports",
+            &self
+                .options
+                .public_access
+                .as_ref()
+                .map(|public_access| public_access.to_public_port_data_templates())
+                .unwrap_or_default(),
+        );
         context.insert("database_id", &self.id());
         context.insert("tfstate_suffix_name", &get_tfstate_suffix(self));
         context.insert("tfstate_name", &get_tfstate_name(self));
@@ -224,7 +237,27 @@ impl Create for MongoDB {
         info!("DO.MongoDB.on_create() called for {}", self.name());
 
         send_progress_on_long_task(self, crate::cloud_provider::service::Action::Create, || {
-            deploy_stateful_service(target, self)
+            deploy_stateful_service(target, self)?;
+
+            // managed databases are exposed by the cloud provider, not by a load balancer of the cluster
+            match (target, &self.options.public_access) {
+                (DeploymentTarget::SelfHosted(..), Some(public_access)) => {
+                    crate::cloud_provider::service::report_public_access_address(
+                        target,
+                        self,
+                        public_access,
+                        |kubernetes_config, namespace, selector, envs| {
+                            crate::cmd::kubectl::do_kubectl_exec_get_external_ingress_ip(
+                                kubernetes_config,
+                                namespace,
+                                selector,
+                                envs,
+                            )
+                        },
+                    )
+                }
+                _ => Ok(()),
+            }
         })
     }
 
@@ -277,71 +310,133 @@ impl Delete for MongoDB {
     }
 }
 
+impl Plan for MongoDB {
+    fn on_plan(&self, target: &DeploymentTarget) -> Result<ServicePlan, EngineError> {
+        info!("DO.MongoDB.on_plan() called for {}", self.name());
+
+        plan_stateful_service(target, self, self.matching_correct_version()?.as_str())
+    }
+}
+
 impl crate::cloud_provider::service::Clone for MongoDB {
-    fn on_clone(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_clone(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("DO.MongoDB.on_clone() called for {}", self.name());
+
+        send_progress_on_long_task_with_message(
+            self,
+            Some(format!(
+                "{} '{}' clone is in progress...",
+                self.service_type().name(),
+                self.name_with_id()
+            )),
+            Action::Create,
+            || clone_stateful_service(target, self),
+        )
     }
 
     fn on_clone_check(&self) -> Result<(), EngineError> {
-        unimplemented!()
+        Ok(())
     }
 
-    fn on_clone_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_clone_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("DO.MongoDB.on_clone_error() called for {}", self.name());
+
+        let _ = delete_database_backup_job(target, self, DatabaseBackupAction::Clone)?;
+        delete_database_backup_job(target, self, DatabaseBackupAction::Restore)
     }
 }
 
 impl Upgrade for MongoDB {
-    fn on_upgrade(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_upgrade(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("DO.MongoDB.on_upgrade() called for {}", self.name());
+
+        send_progress_on_long_task(self, Action::Create, || {
+            upgrade_stateful_service(target, self, self.matching_correct_version()?.as_str())
+        })
     }
 
     fn on_upgrade_check(&self) -> Result<(), EngineError> {
-        unimplemented!()
+        Ok(())
     }
 
-    fn on_upgrade_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_upgrade_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("DO.MongoDB.on_upgrade_error() called for {}", self.name());
+
+        let _ = delete_database_backup_job(target, self, DatabaseBackupAction::Backup)?;
+        delete_database_backup_job(target, self, DatabaseBackupAction::Restore)
     }
 }
 
 impl Downgrade for MongoDB {
-    fn on_downgrade(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_downgrade(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("DO.MongoDB.on_downgrade() called for {}", self.name());
+
+        send_progress_on_long_task(self, Action::Create, || {
+            downgrade_stateful_service(target, self, self.matching_correct_version()?.as_str())
+        })
     }
 
     fn on_downgrade_check(&self) -> Result<(), EngineError> {
-        unimplemented!()
+        Ok(())
     }
 
-    fn on_downgrade_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_downgrade_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("DO.MongoDB.on_downgrade_error() called for {}", self.name());
+
+        let _ = delete_database_backup_job(target, self, DatabaseBackupAction::Backup)?;
+        delete_database_backup_job(target, self, DatabaseBackupAction::Restore)
     }
 }
 
 impl Backup for MongoDB {
-    fn on_backup(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_backup(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("DO.MongoDB.on_backup() called for {}", self.name());
+
+        send_progress_on_long_task_with_message(
+            self,
+            Some(format!(
+                "{} '{}' backup is in progress...",
+                self.service_type().name(),
+                self.name_with_id()
+            )),
+            Action::Create,
+            || backup_stateful_service(target, self),
+        )
     }
 
     fn on_backup_check(&self) -> Result<(), EngineError> {
-        unimplemented!()
+        Ok(())
     }
 
-    fn on_backup_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_backup_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("DO.MongoDB.on_backup_error() called for {}", self.name());
+
+        delete_database_backup_job(target, self, DatabaseBackupAction::Backup)
     }
 
-    fn on_restore(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_restore(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("DO.MongoDB.on_restore() called for {}", self.name());
+
+        send_progress_on_long_task_with_message(
+            self,
+            Some(format!(
+                "{} '{}' restore is in progress...",
+                self.service_type().name(),
+                self.name_with_id()
+            )),
+            Action::Create,
+            || restore_stateful_service(target, self, self.options.restore_from.as_ref()),
+        )
     }
 
     fn on_restore_check(&self) -> Result<(), EngineError> {
-        unimplemented!()
+        Ok(())
     }
 
-    fn on_restore_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
-        unimplemented!()
+    fn on_restore_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("DO.MongoDB.on_restore_error() called for {}", self.name());
+
+        delete_database_backup_job(target, self, DatabaseBackupAction::Restore)
     }
 }
 
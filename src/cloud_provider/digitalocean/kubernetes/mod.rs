@@ -1,16 +1,27 @@
 use itertools::Itertools;
+use retry::delay::Fibonacci;
+use retry::Error::Operation;
+use retry::OperationResult;
 use serde::{Deserialize, Serialize};
 use tera::Context as TeraContext;
 
 use crate::cloud_provider::digitalocean::kubernetes::node::Node;
 use crate::cloud_provider::digitalocean::DO;
 use crate::cloud_provider::environment::Environment;
-use crate::cloud_provider::kubernetes::{Kind, Kubernetes, KubernetesNode};
+use crate::cloud_provider::kubernetes::{
+    is_kubernetes_upgrade_required, required_upgrade_on_create, Kind, Kubernetes, KubernetesNode, KubernetesNodesType,
+    KubernetesUpgradeStatus,
+};
 use crate::cloud_provider::models::WorkerNodeDataTemplate;
+use crate::cloud_provider::plan::EnvironmentPlan;
 use crate::cloud_provider::{kubernetes, CloudProvider};
+use crate::cmd::terraform::{
+    terraform_exec, terraform_init_validate_destroy, terraform_init_validate_plan_apply,
+    terraform_init_validate_state_list,
+};
 use crate::dns_provider;
 use crate::dns_provider::DnsProvider;
-use crate::error::{cast_simple_error_to_engine_error, EngineError};
+use crate::error::{cast_simple_error_to_engine_error, EngineError, EngineErrorCause};
 use crate::fs::workspace_directory;
 use crate::models::{
     Context, Features, Listen, Listener, Listeners, ListenersHelper, ProgressInfo, ProgressLevel, ProgressScope,
@@ -198,6 +209,7 @@ impl<'a> DOKS<'a> {
 
         let space_kubeconfig_bucket = format!("qovery-kubeconfigs-{}", self.id.as_str());
         context.insert("space_bucket_kubeconfig", &space_kubeconfig_bucket);
+        context.insert("space_bucket_database_backups", &self.database_backups_bucket_name());
 
         // AWS S3 tfstates storage tfstates
         context.insert(
@@ -247,9 +259,194 @@ impl<'a> DOKS<'a> {
             .collect::<Vec<WorkerNodeDataTemplate>>();
 
         context.insert("doks_worker_nodes", &worker_nodes);
+        context.insert("doks_paused", &false);
 
         context
     }
+
+    // render the bootstrap terraform files into the workspace directory, and return it
+    fn generate_bootstrap_files(&self, context: &TeraContext) -> Result<String, EngineError> {
+        let temp_dir = workspace_directory(
+            self.context.workspace_root_dir(),
+            self.context.execution_id(),
+            format!("digitalocean/bootstrap/{}", self.name()),
+        );
+
+        let _ = cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            crate::template::generate_and_copy_all_files_into_dir(
+                self.template_directory.as_str(),
+                temp_dir.as_str(),
+                context,
+            ),
+        )?;
+
+        // copy lib/common/bootstrap/charts directory (and sub directory) into the lib/digitalocean/bootstrap/common/charts directory.
+        // this is due to the required dependencies of lib/digitalocean/bootstrap/*.tf files
+        let common_charts_temp_dir = format!("{}/common/charts", temp_dir.as_str());
+        let _ = cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            crate::template::copy_non_template_files(
+                format!("{}/common/bootstrap/charts", self.context.lib_root_dir()),
+                common_charts_temp_dir.as_str(),
+            ),
+        )?;
+
+        Ok(temp_dir)
+    }
+
+    fn upgrade(&self, kubernetes_upgrade_status: KubernetesUpgradeStatus) -> Result<(), EngineError> {
+        let listeners_helper = ListenersHelper::new(&self.listeners);
+        let send_to_customer = |message: &str| {
+            listeners_helper.upgrade_in_progress(ProgressInfo::new(
+                ProgressScope::Infrastructure {
+                    execution_id: self.context.execution_id().to_string(),
+                },
+                ProgressLevel::Info,
+                Some(message),
+                self.context.execution_id(),
+            ))
+        };
+        send_to_customer(
+            format!(
+                "Start preparing DOKS upgrade process {} cluster with id {}",
+                self.name(),
+                self.id()
+            )
+            .as_str(),
+        );
+
+        let kubeconfig = match self.config_file() {
+            Ok(f) => f.0,
+            Err(e) => {
+                error!("Can't perform a Kubernetes upgrade, can't locate kubeconfig");
+                return Err(e);
+            }
+        };
+
+        //
+        // Upgrade master nodes
+        //
+
+        match &kubernetes_upgrade_status.required_upgrade_on {
+            Some(KubernetesNodesType::Masters) => {
+                let message = format!(
+                    "Start upgrading process for master nodes on {}/{}",
+                    self.name(),
+                    self.id()
+                );
+                info!("{}", &message);
+                send_to_customer(&message);
+
+                // DigitalOcean upgrades the control plane first, then rolls the node pools to the same version (surge upgrade),
+                // so only the cluster version has to be changed
+                let temp_dir = self.generate_bootstrap_files(&self.tera_context())?;
+
+                send_to_customer(format!("Upgrading Kubernetes {} master nodes", self.name()).as_str());
+
+                match cast_simple_error_to_engine_error(
+                    self.engine_error_scope(),
+                    self.context.execution_id(),
+                    terraform_init_validate_plan_apply(temp_dir.as_str(), self.context.is_dry_run_deploy()),
+                ) {
+                    Ok(_) => {
+                        let message = format!(
+                            "Kubernetes {} master nodes have been successfully upgraded",
+                            self.name()
+                        );
+                        info!("{}", &message);
+                        send_to_customer(&message);
+                    }
+                    Err(e) => {
+                        error!(
+                            "Error while upgrading master nodes for cluster {} with id {}.",
+                            self.name(),
+                            self.id()
+                        );
+                        return Err(e);
+                    }
+                }
+            }
+            Some(KubernetesNodesType::Workers) => {
+                info!("No need to perform Kubernetes master upgrade, they are already up to date")
+            }
+            None => {
+                info!("No Kubernetes upgrade required, masters and workers are already up to date");
+                return Ok(());
+            }
+        }
+
+        //
+        // Check worker nodes
+        //
+
+        let message = format!(
+            "Waiting for worker nodes of Kubernetes cluster {} to be upgraded",
+            self.name()
+        );
+        info!("{}", &message);
+        send_to_customer(message.as_str());
+
+        // node pools are replaced one node at a time by DigitalOcean, it can take a while
+        let workers_upgrade_result =
+            retry::retry(
+                Fibonacci::from_millis(10000).take(10),
+                || match is_kubernetes_upgrade_required(
+                    &kubeconfig,
+                    self.version(),
+                    self.cloud_provider.credentials_environment_variables(),
+                ) {
+                    Ok(status) => match status.required_upgrade_on {
+                        None => OperationResult::Ok(()),
+                        Some(_) => {
+                            info!(
+                                "worker nodes of cluster {} are still in version {}, waiting for {}",
+                                self.name(),
+                                status.deployed_workers_version,
+                                status.requested_version
+                            );
+                            OperationResult::Retry(format!(
+                                "worker nodes are still in version {} instead of {}",
+                                status.deployed_workers_version, status.requested_version
+                            ))
+                        }
+                    },
+                    Err(e) => OperationResult::Retry(format!("{:?}", e.message)),
+                },
+            );
+
+        match workers_upgrade_result {
+            Ok(_) => {
+                let message = format!(
+                    "Kubernetes {} workers nodes have been successfully upgraded",
+                    self.name()
+                );
+                info!("{}", &message);
+                send_to_customer(&message);
+                Ok(())
+            }
+            Err(Operation { error, .. }) => Err(self.engine_error(
+                EngineErrorCause::Internal,
+                format!(
+                    "Error while upgrading worker nodes for cluster {} with id {}: {}",
+                    self.name(),
+                    self.id(),
+                    error
+                ),
+            )),
+            Err(retry::Error::Internal(msg)) => Err(self.engine_error(
+                EngineErrorCause::Internal,
+                format!(
+                    "Error while upgrading worker nodes for cluster {} with id {}: {}",
+                    self.name(),
+                    self.id(),
+                    msg
+                ),
+            )),
+        }
+    }
 }
 
 impl<'a> Kubernetes for DOKS<'a> {
@@ -311,44 +508,29 @@ impl<'a> Kubernetes for DOKS<'a> {
             self.context.execution_id(),
         ));
 
-        let temp_dir = workspace_directory(
-            self.context.workspace_root_dir(),
-            self.context.execution_id(),
-            format!("digitalocean/bootstrap/{}", self.name()),
-        );
+        // upgrade cluster instead if required
+        match self.config_file() {
+            Ok(f) => {
+                if let Some(x) = required_upgrade_on_create(is_kubernetes_upgrade_required(
+                    f.0,
+                    &self.version,
+                    self.cloud_provider.credentials_environment_variables(),
+                )) {
+                    return self.upgrade(x);
+                }
+            }
+            Err(_) => {
+                info!("Kubernetes cluster upgrade not required, config file is not found and cluster have certainly never been deployed before");
+            }
+        };
 
         // generate terraform files and copy them into temp dir
-        let context = self.tera_context();
+        let temp_dir = self.generate_bootstrap_files(&self.tera_context())?;
 
         let _ = cast_simple_error_to_engine_error(
             self.engine_error_scope(),
             self.context.execution_id(),
-            crate::template::generate_and_copy_all_files_into_dir(
-                self.template_directory.as_str(),
-                temp_dir.as_str(),
-                &context,
-            ),
-        )?;
-
-        // copy lib/common/bootstrap/charts directory (and sub directory) into the lib/aws/bootstrap/common/charts directory.
-        // this is due to the required dependencies of lib/aws/bootstrap/*.tf files
-        let common_charts_temp_dir = format!("{}/common/charts", temp_dir.as_str());
-        let _ = cast_simple_error_to_engine_error(
-            self.engine_error_scope(),
-            self.context.execution_id(),
-            crate::template::copy_non_template_files(
-                format!("{}/common/bootstrap/charts", self.context.lib_root_dir()),
-                common_charts_temp_dir.as_str(),
-            ),
-        )?;
-
-        let _ = cast_simple_error_to_engine_error(
-            self.engine_error_scope(),
-            self.context.execution_id(),
-            crate::cmd::terraform::terraform_init_validate_plan_apply(
-                temp_dir.as_str(),
-                self.context.is_dry_run_deploy(),
-            ),
+            terraform_init_validate_plan_apply(temp_dir.as_str(), self.context.is_dry_run_deploy()),
         )?;
 
         Ok(())
@@ -359,34 +541,222 @@ impl<'a> Kubernetes for DOKS<'a> {
     }
 
     fn on_upgrade(&self) -> Result<(), EngineError> {
-        Ok(())
+        info!("DOKS.on_upgrade() called for {}", self.name());
+
+        let kubeconfig = match self.config_file() {
+            Ok(f) => f.0,
+            Err(e) => return Err(e),
+        };
+
+        match is_kubernetes_upgrade_required(
+            kubeconfig,
+            &self.version,
+            self.cloud_provider.credentials_environment_variables(),
+        ) {
+            Ok(x) => self.upgrade(x),
+            Err(e) => {
+                let msg = format!(
+                    "Error detected, upgrade won't occurs, but standard deployment. {:?}",
+                    e.message
+                );
+                error!("{}", &msg);
+                Err(self.engine_error(EngineErrorCause::Internal, msg))
+            }
+        }
     }
 
     fn on_upgrade_error(&self) -> Result<(), EngineError> {
+        warn!("DOKS.on_upgrade_error() called for {}", self.name());
         Ok(())
     }
 
     fn on_downgrade(&self) -> Result<(), EngineError> {
+        info!("DOKS.on_downgrade() called for {}", self.name());
         Ok(())
     }
 
     fn on_downgrade_error(&self) -> Result<(), EngineError> {
+        warn!("DOKS.on_downgrade_error() called for {}", self.name());
         Ok(())
     }
 
     fn on_pause(&self) -> Result<(), EngineError> {
-        todo!()
+        info!("DOKS.on_pause() called for {}", self.name());
+
+        let listeners_helper = ListenersHelper::new(&self.listeners);
+        let send_to_customer = |message: &str| {
+            listeners_helper.pause_in_progress(ProgressInfo::new(
+                ProgressScope::Infrastructure {
+                    execution_id: self.context.execution_id().to_string(),
+                },
+                ProgressLevel::Info,
+                Some(message),
+                self.context.execution_id(),
+            ))
+        };
+        send_to_customer(format!("Preparing DOKS {} cluster pause with id {}", self.name(), self.id()).as_str());
+
+        // pause: remove the additional node pools and shrink the default one to a single node (DigitalOcean rejects an empty default node pool)
+        // to reduce the bill but keep the control plane to keep all the deployment config, certificates etc...
+        let mut context = self.tera_context();
+        context.insert("doks_paused", &true);
+
+        let temp_dir = self.generate_bootstrap_files(&context)?;
+
+        // pause: only select terraform node pools elements to pause to avoid applying on the whole config
+        // the first node pool is part of the cluster resource
+        let tf_workers_resources = cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            terraform_init_validate_state_list(temp_dir.as_str()),
+        )?
+        .into_iter()
+        .filter(|name| {
+            name.starts_with("digitalocean_kubernetes_node_pool.")
+                || name.starts_with("digitalocean_kubernetes_cluster.")
+        })
+        .collect::<Vec<_>>();
+
+        if tf_workers_resources.is_empty() {
+            return Err(self.engine_error(
+                EngineErrorCause::Internal,
+                "No worker nodes present, can't Pause the infrastructure. This can happen if there where a manual operations on the workers or the infrastructure is already pause.".to_string(),
+            ));
+        }
+
+        // pause: wait for the engine to have 0 running jobs before pausing and avoid getting unreleased lock (from helm or terraform for example)
+        let _ = kubernetes::wait_for_engine_jobs_to_finish(self)?;
+
+        let mut terraform_args_string = vec!["apply".to_string(), "-auto-approve".to_string()];
+        for x in tf_workers_resources {
+            terraform_args_string.push(format!("-target={}", x));
+        }
+        let terraform_args = terraform_args_string.iter().map(|x| &**x).collect();
+
+        let message = format!("Pausing DOKS {} cluster deployment with id {}", self.name(), self.id());
+        info!("{}", &message);
+        send_to_customer(&message);
+
+        match cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            terraform_exec(temp_dir.as_str(), terraform_args),
+        ) {
+            Ok(_) => {
+                let message = format!("Kubernetes cluster {} successfully paused", self.name());
+                info!("{}", &message);
+                send_to_customer(&message);
+                Ok(())
+            }
+            Err(e) => {
+                error!("Error while pausing cluster {} with id {}.", self.name(), self.id());
+                Err(e)
+            }
+        }
     }
 
     fn on_pause_error(&self) -> Result<(), EngineError> {
-        todo!()
+        warn!("DOKS.on_pause_error() called for {}", self.name());
+        Err(self.engine_error(
+            EngineErrorCause::Internal,
+            format!("{} Kubernetes cluster failed to pause", self.name()),
+        ))
     }
 
     fn on_delete(&self) -> Result<(), EngineError> {
+        info!("DOKS.on_delete() called for {}", self.name());
+
+        let listeners_helper = ListenersHelper::new(&self.listeners);
+        let send_to_customer = |message: &str| {
+            listeners_helper.delete_in_progress(ProgressInfo::new(
+                ProgressScope::Infrastructure {
+                    execution_id: self.context.execution_id().to_string(),
+                },
+                ProgressLevel::Info,
+                Some(message),
+                self.context.execution_id(),
+            ))
+        };
+        send_to_customer(format!("Preparing to delete DOKS cluster {} with id {}", self.name(), self.id()).as_str());
+
+        // generate terraform files and copy them into temp dir
+        let temp_dir = self.generate_bootstrap_files(&self.tera_context())?;
+
+        // should apply before destroy to be sure destroy will compute on all resources
+        // don't exit on failure, it can happen if we resume a destroy process
+        let message = format!(
+            "Ensuring everything is up to date before deleting cluster {}/{}",
+            self.name(),
+            self.id()
+        );
+        info!("{}", &message);
+        send_to_customer(&message);
+
+        info!("Running Terraform apply before running a delete");
+        if let Err(e) = cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            terraform_init_validate_plan_apply(temp_dir.as_str(), false),
+        ) {
+            error!("An issue occurred during the apply before destroy of Terraform, it may be expected if you're resuming a destroy: {:?}", e.message);
+        };
+
+        // delete everything deployed on the cluster, not to leave cloud resources behind (e.g. load balancers, volumes)
+        let _ = kubernetes::delete_all_deployments(self, &send_to_customer)?;
+
+        let message = format!("Deleting Kubernetes cluster {}/{}", self.name(), self.id());
+        info!("{}", &message);
+        send_to_customer(&message);
+
+        info!("Running Terraform destroy");
+        let terraform_result =
+            retry::retry(
+                Fibonacci::from_millis(60000).take(3),
+                || match cast_simple_error_to_engine_error(
+                    self.engine_error_scope(),
+                    self.context.execution_id(),
+                    terraform_init_validate_destroy(temp_dir.as_str(), false),
+                ) {
+                    Ok(_) => OperationResult::Ok(()),
+                    Err(e) => OperationResult::Retry(e),
+                },
+            );
+
+        match terraform_result {
+            Ok(_) => {}
+            Err(Operation { error, .. }) => return Err(error),
+            Err(retry::Error::Internal(msg)) => {
+                return Err(self.engine_error(
+                    EngineErrorCause::Internal,
+                    format!(
+                        "Error while deleting cluster {} with id {}: {}",
+                        self.name(),
+                        self.id(),
+                        msg
+                    ),
+                ))
+            }
+        }
+
+        // the kubeconfig bucket is force destroyed by terraform, make sure it does not remain if the state was lost
+        let kubeconfig_bucket_name = format!("qovery-kubeconfigs-{}", self.id());
+        if let Err(e) = self.spaces.delete_bucket(kubeconfig_bucket_name.as_str()) {
+            debug!(
+                "kubeconfig bucket {} is already deleted or can't be deleted: {:?}",
+                kubeconfig_bucket_name, e.message
+            );
+        }
+
+        let message = format!("Kubernetes cluster {}/{} successfully deleted", self.name(), self.id());
+        info!("{}", &message);
+        send_to_customer(&message);
+
         Ok(())
     }
 
     fn on_delete_error(&self) -> Result<(), EngineError> {
+        warn!("DOKS.on_delete_error() called for {}", self.name());
+
         Ok(())
     }
 
@@ -419,6 +789,26 @@ impl<'a> Kubernetes for DOKS<'a> {
         warn!("DOKS.delete_environment_error() called for {}", self.name());
         Ok(())
     }
+
+    fn backup_environment(&self, environment: &Environment) -> Result<(), EngineError> {
+        info!("DOKS.backup_environment() called for {}", self.name());
+        kubernetes::backup_environment(self, environment)
+    }
+
+    fn restore_environment(&self, environment: &Environment) -> Result<(), EngineError> {
+        info!("DOKS.restore_environment() called for {}", self.name());
+        kubernetes::restore_environment(self, environment)
+    }
+
+    fn clone_environment(&self, environment: &Environment) -> Result<(), EngineError> {
+        info!("DOKS.clone_environment() called for {}", self.name());
+        kubernetes::clone_environment(self, environment)
+    }
+
+    fn plan_environment(&self, environment: &Environment) -> Result<EnvironmentPlan, EngineError> {
+        info!("DOKS.plan_environment() called for {}", self.name());
+        kubernetes::plan_environment(self, environment)
+    }
 }
 
 impl<'a> Listen for DOKS<'a> {
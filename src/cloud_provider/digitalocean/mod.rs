@@ -12,6 +12,7 @@ use crate::models::{Context, Listen, Listener, Listeners};
 pub mod application;
 pub mod common;
 pub mod databases;
+pub mod job;
 pub mod kubernetes;
 pub mod models;
 pub mod router;
@@ -5,10 +5,13 @@ use tera::Context as TeraContext;
 use crate::cloud_provider::digitalocean::common::do_get_load_balancer_ip;
 use crate::cloud_provider::digitalocean::DO;
 use crate::cloud_provider::environment::Kind;
-use crate::cloud_provider::models::{CustomDomain, CustomDomainDataTemplate, Route, RouteDataTemplate};
+use crate::cloud_provider::models::{BasicAuthUser, CustomDomain, Route};
+use crate::cloud_provider::plan::ServicePlan;
 use crate::cloud_provider::service::{
-    default_tera_context, delete_stateless_service, send_progress_on_long_task, Action, Create, Delete, Helm, Pause,
-    Service, ServiceType, StatelessService,
+    apply_custom_domain_certificate_secrets, check_custom_domain_certificates, check_router_access_control,
+    check_router_routes, default_tera_context, delete_custom_domain_certificate_secrets, delete_stateless_service,
+    get_canary_weight_percent, plan_stateless_service, send_progress_on_long_task, Action, Create, Delete, Helm, Pause,
+    Plan, Service, ServiceType, StatelessService,
 };
 use crate::cloud_provider::utilities::{check_cname_for, sanitize_name};
 use crate::cloud_provider::DeploymentTarget;
@@ -26,6 +29,8 @@ pub struct Router {
     default_domain: String,
     custom_domains: Vec<CustomDomain>,
     routes: Vec<Route>,
+    whitelist_source_ranges: Vec<String>,
+    basic_auth_users: Vec<BasicAuthUser>,
     listeners: Listeners,
 }
 
@@ -38,6 +43,8 @@ impl Router {
         default_domain: &str,
         custom_domains: Vec<CustomDomain>,
         routes: Vec<Route>,
+        whitelist_source_ranges: Vec<String>,
+        basic_auth_users: Vec<BasicAuthUser>,
         listeners: Listeners,
     ) -> Self {
         Router {
@@ -48,6 +55,8 @@ impl Router {
             default_domain: default_domain.to_string(),
             custom_domains,
             routes,
+            whitelist_source_ranges,
+            basic_auth_users,
             listeners,
         }
     }
@@ -112,6 +121,9 @@ impl Service for Router {
             DeploymentTarget::SelfHosted(k, env) => (*k, *env),
         };
 
+        check_router_access_control(self, &self.whitelist_source_ranges, &self.basic_auth_users)?;
+        check_router_routes(self, &self.routes)?;
+
         let digitalocean = kubernetes.cloud_provider().as_any().downcast_ref::<DO>().unwrap();
 
         let mut context = default_tera_context(self, kubernetes, environment);
@@ -129,17 +141,11 @@ impl Service for Router {
             .custom_domains
             .iter()
             .map(|cd| {
-                let domain_hash = crate::crypto::to_sha1_truncate_16(cd.domain.as_str());
-
                 // https://github.com/digitalocean/digitalocean-cloud-controller-manager/issues/291
                 // Can only manage 1 host at a time on an DO load balancer
                 context.insert("custom_domain_name", cd.domain.as_str());
 
-                CustomDomainDataTemplate {
-                    domain: cd.domain.clone(),
-                    domain_hash,
-                    target_domain: cd.target_domain.clone(),
-                }
+                cd.to_custom_domain_data_template()
             })
             .collect::<Vec<_>>();
 
@@ -152,11 +158,11 @@ impl Service for Router {
                     .find(|app| app.name() == r.application_name.as_str())
                 {
                     Some(application) => match application.private_port() {
-                        Some(private_port) => Some(RouteDataTemplate {
-                            path: r.path.clone(),
-                            application_name: application.sanitized_name().to_string(),
-                            application_port: private_port,
-                        }),
+                        Some(private_port) => Some(r.to_route_data_template(
+                            application.sanitized_name().to_string(),
+                            private_port,
+                            get_canary_weight_percent(kubernetes, environment, application.as_ref()),
+                        )),
                         _ => None,
                     },
                     _ => None,
@@ -299,6 +305,17 @@ impl Service for Router {
         context.insert("router_default_domain_hash", router_default_domain_hash.as_str());
         context.insert("custom_domains", &custom_domain_data_templates);
         context.insert("routes", &route_data_templates);
+        context.insert("whitelist_source_ranges", &self.whitelist_source_ranges);
+        // content of the htpasswd file of the basic auth secret, one line per user
+        context.insert(
+            "basic_auth_htpasswd",
+            &self
+                .basic_auth_users
+                .iter()
+                .map(|user| user.to_htpasswd(self.id()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
         context.insert("spec_acme_email", "tls@qovery.com"); // TODO CHANGE ME
         context.insert("metadata_annotations_cert_manager_cluster_issuer", "letsencrypt-qovery");
 
@@ -308,8 +325,6 @@ impl Service for Router {
         };
         context.insert("spec_acme_server", lets_encrypt_url);
 
-        eprintln!("{}", context.clone().into_json());
-
         Ok(context)
     }
 
@@ -332,6 +347,12 @@ impl crate::cloud_provider::service::Router for Router {
 
         _domains
     }
+
+    fn routes_to(&self, application_name: &str) -> bool {
+        self.routes
+            .iter()
+            .any(|route| route.application_name == application_name)
+    }
 }
 
 impl Helm for Router {
@@ -365,7 +386,11 @@ impl Listen for Router {
     }
 }
 
-impl StatelessService for Router {}
+impl StatelessService for Router {
+    fn as_router(&self) -> Option<&dyn crate::cloud_provider::service::Router> {
+        Some(self)
+    }
+}
 
 impl Create for Router {
     fn on_create(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
@@ -380,6 +405,8 @@ impl Create for Router {
 
         let kubernetes_config_file_path = kubernetes.config_file_path()?;
 
+        let _ = check_custom_domain_certificates(target, self, &self.custom_domains)?;
+
         // respect order - getting the context here and not before is mandatory
         // the nginx-ingress must be available to get the external dns target if necessary
         let mut context = self.tera_context(target)?;
@@ -466,6 +493,8 @@ impl Create for Router {
             crate::template::generate_and_copy_all_files_into_dir(from_dir.as_str(), workspace_dir.as_str(), &context),
         )?;
 
+        let _ = apply_custom_domain_certificate_secrets(target, self, &self.custom_domains)?;
+
         // do exec helm upgrade and return the last deployment status
         let helm_history_row = cast_simple_error_to_engine_error(
             self.engine_error_scope(),
@@ -547,6 +576,7 @@ impl Delete for Router {
 
         send_progress_on_long_task(self, crate::cloud_provider::service::Action::Delete, || {
             delete_stateless_service(target, self, false)
+                .and_then(|_| delete_custom_domain_certificate_secrets(target, self, &[]))
         })
     }
 
@@ -562,3 +592,11 @@ impl Delete for Router {
         })
     }
 }
+
+impl Plan for Router {
+    fn on_plan(&self, target: &DeploymentTarget) -> Result<ServicePlan, EngineError> {
+        info!("DigitalOcean.router.on_plan() called for {}", self.name());
+
+        plan_stateless_service(target, self, None)
+    }
+}
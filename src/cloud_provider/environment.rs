@@ -11,6 +11,7 @@ pub struct Environment {
     pub organization_id: String,
     pub stateless_services: Vec<Box<dyn StatelessService>>,
     pub stateful_services: Vec<Box<dyn StatefulService>>,
+    pub clone_from_environment_id: Option<String>,
 }
 
 impl Environment {
@@ -22,6 +23,7 @@ impl Environment {
         organization_id: &str,
         stateless_services: Vec<Box<dyn StatelessService>>,
         stateful_services: Vec<Box<dyn StatefulService>>,
+        clone_from_environment_id: Option<String>,
     ) -> Self {
         Environment {
             namespace: format!("{}-{}", project_id, id),
@@ -32,6 +34,7 @@ impl Environment {
             organization_id: organization_id.to_string(),
             stateless_services,
             stateful_services,
+            clone_from_environment_id,
         }
     }
 
@@ -39,6 +42,13 @@ impl Environment {
         self.namespace.as_str()
     }
 
+    /// namespace of the environment this one is cloned from, if any
+    pub fn clone_from_namespace(&self) -> Option<String> {
+        self.clone_from_environment_id
+            .as_ref()
+            .map(|environment_id| format!("{}-{}", self.project_id, environment_id))
+    }
+
     pub fn is_valid(&self) -> Result<(), EngineError> {
         for service in self.stateful_services.iter() {
             if let Err(err) = service.is_valid() {
@@ -55,37 +65,34 @@ impl Environment {
         Ok(())
     }
 
-    /// compute the required resources for this environment from
-    /// applications, external services, routers, and databases
+    /// list the pods this environment will run on the targeted Kubernetes cluster once deployed,
+    /// with the resources requested by each of them
     /// Note: Even if external services don't run on the targeted Kubernetes cluster, it requires CPU and memory resources to run the container(s)
-    pub fn required_resources(&self) -> EnvironmentResources {
-        let mut total_cpu_for_stateless_services: f32 = 0.0;
-        let mut total_ram_in_mib_for_stateless_services: u32 = 0;
-        let mut required_pods = self.stateless_services.len() as u16;
+    pub fn required_pods(&self) -> Vec<PodResources> {
+        let mut required_pods = vec![];
 
         for service in &self.stateless_services {
             match *service.action() {
-                Action::Create | Action::Nothing => {
-                    total_cpu_for_stateless_services += cpu_string_to_float(&service.total_cpus());
-                    total_ram_in_mib_for_stateless_services += &service.total_ram_in_mib();
-                    required_pods += service.total_instances();
-                }
+                // autoscaled services must be able to reach their max instances
+                Action::Create | Action::Nothing => required_pods.extend(pods_resources(
+                    service.total_cpus().as_str(),
+                    service.total_ram_in_mib(),
+                    service.max_instances(),
+                )),
                 Action::Delete | Action::Pause => {}
             }
         }
 
-        let mut total_cpu_for_stateful_services: f32 = 0.0;
-        let mut total_ram_in_mib_for_stateful_services: u32 = 0;
-
         match self.kind {
             Kind::Development => {
                 // development means stateful services are running on Kubernetes
                 for service in &self.stateful_services {
                     match *service.action() {
-                        Action::Create | Action::Nothing => {
-                            total_cpu_for_stateful_services += cpu_string_to_float(&service.total_cpus());
-                            total_ram_in_mib_for_stateful_services += &service.total_ram_in_mib();
-                        }
+                        Action::Create | Action::Nothing => required_pods.extend(pods_resources(
+                            service.total_cpus().as_str(),
+                            service.total_ram_in_mib(),
+                            service.total_instances(),
+                        )),
                         Action::Delete | Action::Pause => {}
                     }
                 }
@@ -93,28 +100,32 @@ impl Environment {
             Kind::Production => {} // production means databases are running on managed services - so it consumes 0 cpu
         };
 
-        match self.kind {
-            crate::cloud_provider::environment::Kind::Production => {}
-            crate::cloud_provider::environment::Kind::Development => {
-                for service in &self.stateful_services {
-                    match *service.action() {
-                        Action::Create | Action::Nothing => {
-                            required_pods += service.total_instances();
-                        }
-                        Action::Delete | Action::Pause => {}
-                    }
-                }
-            }
-        }
+        required_pods
+    }
+
+    /// compute the required resources for this environment from
+    /// applications, external services, routers, and databases
+    pub fn required_resources(&self) -> EnvironmentResources {
+        let required_pods = self.required_pods();
 
         EnvironmentResources {
-            pods: required_pods,
-            cpu: total_cpu_for_stateless_services + total_cpu_for_stateful_services,
-            ram_in_mib: total_ram_in_mib_for_stateless_services + total_ram_in_mib_for_stateless_services,
+            pods: required_pods.len() as u16,
+            cpu: required_pods.iter().map(|pod| pod.cpu).sum(),
+            ram_in_mib: required_pods.iter().map(|pod| pod.ram_in_mib).sum(),
         }
     }
 }
 
+/// cpu and ram requests are set per pod, so a service with several instances requests them for each instance
+fn pods_resources(total_cpus: &str, total_ram_in_mib: u32, total_instances: u16) -> Vec<PodResources> {
+    let pod = PodResources {
+        cpu: cpu_string_to_float(total_cpus),
+        ram_in_mib: total_ram_in_mib,
+    };
+
+    vec![pod; total_instances as usize]
+}
+
 pub enum Kind {
     Production,
     Development,
@@ -125,3 +136,9 @@ pub struct EnvironmentResources {
     pub cpu: f32,
     pub ram_in_mib: u32,
 }
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PodResources {
+    pub cpu: f32,
+    pub ram_in_mib: u32,
+}
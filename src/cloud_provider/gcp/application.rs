@@ -0,0 +1,525 @@
+use tera::Context as TeraContext;
+
+use crate::build_platform::Image;
+use crate::cloud_provider::models::{
+    Autoscaling, EnvironmentVariable, EnvironmentVariableDataTemplate, Probes, PublicAccess, PublicPortDataTemplate,
+    RolloutStrategy, Storage, StorageDataTemplate,
+};
+use crate::cloud_provider::plan::ServicePlan;
+use crate::cloud_provider::service::{
+    default_tera_context, delete_user_application, deploy_stateless_service_error, deploy_user_application,
+    get_autoscaling_replicas, plan_stateless_service, report_public_access_address, restore_application_storage,
+    scale_down_application, send_progress_on_long_task, send_progress_on_long_task_with_message,
+    snapshot_application_storage, Action, Backup, Create, Delete, Helm, Pause, Plan, Service, ServiceType,
+    StatelessService,
+};
+use crate::cloud_provider::utilities::{sanitize_name, validate_k8s_required_cpu_and_burstable};
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::helm::Timeout;
+use crate::cmd::kubectl::ScalingKind::{Deployment, Statefulset};
+use crate::error::EngineErrorCause::Internal;
+use crate::error::{EngineError, EngineErrorScope};
+use crate::models::{Context, Listen, Listener, Listeners, ListenersHelper};
+
+pub struct Application {
+    context: Context,
+    id: String,
+    action: Action,
+    name: String,
+    private_port: Option<u16>,
+    total_cpus: String,
+    cpu_burst: String,
+    total_ram_in_mib: u32,
+    total_instances: u16,
+    start_timeout_in_seconds: u32,
+    probes: Probes,
+    autoscaling: Option<Autoscaling>,
+    rollout_strategy: RolloutStrategy,
+    public_access: Option<PublicAccess>,
+    image: Image,
+    storage: Vec<Storage<StorageType>>,
+    environment_variables: Vec<EnvironmentVariable>,
+    listeners: Listeners,
+}
+
+impl Application {
+    pub fn new(
+        context: Context,
+        id: &str,
+        action: Action,
+        name: &str,
+        private_port: Option<u16>,
+        total_cpus: String,
+        cpu_burst: String,
+        total_ram_in_mib: u32,
+        total_instances: u16,
+        start_timeout_in_seconds: u32,
+        probes: Probes,
+        autoscaling: Option<Autoscaling>,
+        rollout_strategy: RolloutStrategy,
+        public_access: Option<PublicAccess>,
+        image: Image,
+        storage: Vec<Storage<StorageType>>,
+        environment_variables: Vec<EnvironmentVariable>,
+        listeners: Listeners,
+    ) -> Self {
+        Application {
+            context,
+            id: id.to_string(),
+            action,
+            name: name.to_string(),
+            private_port,
+            total_cpus,
+            cpu_burst,
+            total_ram_in_mib,
+            total_instances,
+            start_timeout_in_seconds,
+            probes,
+            autoscaling,
+            rollout_strategy,
+            public_access,
+            image,
+            storage,
+            environment_variables,
+            listeners,
+        }
+    }
+
+    fn is_stateful(&self) -> bool {
+        self.storage.len() > 0
+    }
+}
+
+impl crate::cloud_provider::service::Application for Application {
+    fn image(&self) -> &Image {
+        &self.image
+    }
+
+    fn set_image(&mut self, image: Image) {
+        self.image = image;
+    }
+}
+
+impl Helm for Application {
+    fn helm_release_name(&self) -> String {
+        crate::string::cut(format!("application-{}-{}", self.name, self.id), 50)
+    }
+
+    fn helm_chart_dir(&self) -> String {
+        format!("{}/gcp/charts/q-application", self.context.lib_root_dir())
+    }
+
+    fn helm_chart_values_dir(&self) -> String {
+        String::new()
+    }
+
+    fn helm_chart_external_name_service_dir(&self) -> String {
+        String::new()
+    }
+}
+
+impl StatelessService for Application {
+    fn as_backup(&self) -> Option<&dyn Backup> {
+        Some(self)
+    }
+}
+
+impl Service for Application {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn service_type(&self) -> ServiceType {
+        ServiceType::Application
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn sanitized_name(&self) -> String {
+        sanitize_name("app", self.name())
+    }
+
+    fn version(&self) -> &str {
+        self.image.commit_id.as_str()
+    }
+
+    fn action(&self) -> &Action {
+        &self.action
+    }
+
+    fn private_port(&self) -> Option<u16> {
+        self.private_port
+    }
+
+    fn start_timeout(&self) -> Timeout<u32> {
+        Timeout::Value(self.start_timeout_in_seconds)
+    }
+
+    fn total_cpus(&self) -> String {
+        self.total_cpus.to_string()
+    }
+
+    fn cpu_burst(&self) -> String {
+        self.cpu_burst.to_string()
+    }
+
+    fn total_ram_in_mib(&self) -> u32 {
+        self.total_ram_in_mib
+    }
+
+    fn total_instances(&self) -> u16 {
+        self.total_instances
+    }
+
+    fn max_instances(&self) -> u16 {
+        match &self.autoscaling {
+            Some(autoscaling) => autoscaling.max_instances,
+            None => self.total_instances,
+        }
+    }
+
+    fn rollout_strategy(&self) -> RolloutStrategy {
+        // an application with storage has a single statefulset, its volumes can't be shared with another release
+        if self.is_stateful() {
+            RolloutStrategy::RollingUpdate
+        } else {
+            self.rollout_strategy.clone()
+        }
+    }
+
+    fn tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        let (kubernetes, environment) = match target {
+            DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+            DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+        };
+
+        let mut context = default_tera_context(self, kubernetes, environment);
+        let commit_id = self.image.commit_id.as_str();
+
+        context.insert("helm_app_version", &commit_id[..7]);
+
+        match &self.image.registry_url {
+            Some(registry_url) => context.insert("image_name_with_tag", registry_url.as_str()),
+            None => {
+                let image_name_with_tag = self.image.name_with_tag();
+                warn!(
+                    "there is no registry url, use image name with tag with the default container registry: {}",
+                    image_name_with_tag.as_str()
+                );
+                context.insert("image_name_with_tag", image_name_with_tag.as_str());
+            }
+        }
+
+        let cpu_limits = match validate_k8s_required_cpu_and_burstable(
+            &ListenersHelper::new(&self.listeners),
+            &self.context.execution_id(),
+            &self.id,
+            self.total_cpus(),
+            self.cpu_burst(),
+        ) {
+            Ok(l) => l,
+            Err(e) => {
+                return Err(EngineError::new(
+                    Internal,
+                    EngineErrorScope::Application(self.id().to_string(), self.name().to_string()),
+                    self.context.execution_id(),
+                    Some(e.to_string()),
+                ));
+            }
+        };
+        context.insert("cpu_burst", &cpu_limits.cpu_limit);
+
+        let environment_variables = self
+            .environment_variables
+            .iter()
+            .map(|ev| EnvironmentVariableDataTemplate {
+                key: ev.key.clone(),
+                value: ev.value.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        context.insert("environment_variables", &environment_variables);
+
+        match self.image.registry_name.as_ref() {
+            Some(registry_name) => {
+                context.insert("is_registry_secret", &true);
+                context.insert("registry_secret", registry_name);
+            }
+            None => {
+                context.insert("is_registry_secret", &false);
+            }
+        };
+
+        let storage = self
+            .storage
+            .iter()
+            .map(|s| StorageDataTemplate {
+                id: s.id.clone(),
+                name: s.name.clone(),
+                storage_type: match s.storage_type {
+                    StorageType::Standard => "gcp-pd-standard",
+                    StorageType::Ssd => "gcp-pd-ssd",
+                }
+                .to_string(),
+                size_in_gib: s.size_in_gib,
+                mount_point: s.mount_point.clone(),
+                snapshot_retention_in_days: s.snapshot_retention_in_days,
+                restore_from_snapshot: s.restore_from_snapshot.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        let is_storage = storage.len() > 0;
+
+        context.insert("storage", &storage);
+        context.insert("is_storage", &is_storage);
+        context.insert("volume_snapshot_class", "gcp-pd-snapshot");
+        context.insert("clone", &false);
+        context.insert("start_timeout_in_seconds", &self.start_timeout_in_seconds);
+        context.insert(
+            "readiness_probe",
+            &self.probes.readiness.as_ref().map(|p| p.to_probe_data_template()),
+        );
+        context.insert(
+            "liveness_probe",
+            &self.probes.liveness.as_ref().map(|p| p.to_probe_data_template()),
+        );
+        context.insert(
+            "startup_probe",
+            &self.probes.startup.as_ref().map(|p| p.to_probe_data_template()),
+        );
+        context.insert(
+            "autoscaling",
+            &self.autoscaling.as_ref().map(|a| a.to_autoscaling_data_template()),
+        );
+        if let Some(autoscaling) = &self.autoscaling {
+            context.insert(
+                "autoscaling_replicas",
+                &get_autoscaling_replicas(kubernetes, environment, self, autoscaling)?,
+            );
+        }
+
+        // overridden by the blue/green and canary rollout strategies
+        context.insert("resource_name", &self.sanitized_name());
+        context.insert("service_selector_app", &self.sanitized_name());
+        context.insert("is_workload", &true);
+        context.insert("is_service", &true);
+        context.insert("is_canary_service", &false);
+
+        match &self.public_access {
+            Some(public_access) if !public_access.ports.is_empty() => {
+                context.insert("public_fqdn", public_access.fqdn.as_str());
+                context.insert("public_ports", &public_access.to_public_port_data_templates());
+            }
+            _ => context.insert("public_ports", &Vec::<PublicPortDataTemplate>::new()),
+        }
+
+        if self.context.resource_expiration_in_seconds().is_some() {
+            context.insert(
+                "resource_expiration_in_seconds",
+                &self.context.resource_expiration_in_seconds(),
+            )
+        }
+
+        Ok(context)
+    }
+
+    fn selector(&self) -> String {
+        // the pods are labelled with their slot by the blue/green and canary rollout strategies
+        format!("appId={}", self.id())
+    }
+
+    fn engine_error_scope(&self) -> EngineErrorScope {
+        EngineErrorScope::Application(self.id().to_string(), self.name().to_string())
+    }
+}
+
+impl Create for Application {
+    fn on_create(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.application.on_create() called for {}", self.name);
+
+        // the nodes pull the images from Artifact Registry with their own service account
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Create, || {
+            deploy_user_application(target, self)?;
+
+            match &self.public_access {
+                Some(public_access) => report_public_access_address(
+                    target,
+                    self,
+                    public_access,
+                    |kubernetes_config, namespace, selector, envs| {
+                        crate::cmd::kubectl::do_kubectl_exec_get_external_ingress_ip(
+                            kubernetes_config,
+                            namespace,
+                            selector,
+                            envs,
+                        )
+                    },
+                ),
+                None => Ok(()),
+            }
+        })
+    }
+
+    fn on_create_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_create_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.application.on_create_error() called for {}", self.name());
+
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Create, || {
+            deploy_stateless_service_error(target, self)
+        })
+    }
+}
+
+impl Pause for Application {
+    fn on_pause(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.application.on_pause() called for {}", self.name());
+
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Pause, || {
+            scale_down_application(
+                target,
+                self,
+                0,
+                if self.is_stateful() { Statefulset } else { Deployment },
+            )
+        })
+    }
+
+    fn on_pause_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_pause_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.application.on_pause_error() called for {}", self.name());
+
+        Ok(())
+    }
+}
+
+impl Delete for Application {
+    fn on_delete(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.application.on_delete() called for {}", self.name());
+
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Delete, || {
+            delete_user_application(target, self, false)
+        })
+    }
+
+    fn on_delete_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_delete_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.application.on_delete_error() called for {}", self.name());
+
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Delete, || {
+            delete_user_application(target, self, true)
+        })
+    }
+}
+
+impl Backup for Application {
+    fn on_backup(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.application.on_backup() called for {}", self.name());
+
+        if !self.is_stateful() {
+            return Ok(());
+        }
+
+        send_progress_on_long_task_with_message(
+            self,
+            Some(format!(
+                "{} '{}' volumes snapshot is in progress...",
+                self.service_type().name(),
+                self.name_with_id()
+            )),
+            crate::cloud_provider::service::Action::Create,
+            || snapshot_application_storage(target, self),
+        )
+    }
+
+    fn on_backup_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_backup_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.application.on_backup_error() called for {}", self.name());
+
+        Ok(())
+    }
+
+    fn on_restore(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.application.on_restore() called for {}", self.name());
+
+        send_progress_on_long_task_with_message(
+            self,
+            Some(format!(
+                "{} '{}' volumes restore is in progress...",
+                self.service_type().name(),
+                self.name_with_id()
+            )),
+            crate::cloud_provider::service::Action::Create,
+            || {
+                restore_application_storage(
+                    target,
+                    self,
+                    &self.storage,
+                    format!("{}/gcp/charts/q-volume-restore", self.context.lib_root_dir()).as_str(),
+                )
+            },
+        )
+    }
+
+    fn on_restore_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_restore_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.application.on_restore_error() called for {}", self.name());
+
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Create, || {
+            deploy_stateless_service_error(target, self)
+        })
+    }
+}
+
+impl Plan for Application {
+    fn on_plan(&self, target: &DeploymentTarget) -> Result<ServicePlan, EngineError> {
+        info!("GCP.application.on_plan() called for {}", self.name);
+
+        plan_stateless_service(
+            target,
+            self,
+            Some(
+                self.image
+                    .registry_url
+                    .clone()
+                    .unwrap_or_else(|| self.image.name_with_tag()),
+            ),
+        )
+    }
+}
+
+impl Listen for Application {
+    fn listeners(&self) -> &Listeners {
+        &self.listeners
+    }
+
+    fn add_listener(&mut self, listener: Listener) {
+        self.listeners.push(listener);
+    }
+}
+
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub enum StorageType {
+    Standard,
+    Ssd,
+}
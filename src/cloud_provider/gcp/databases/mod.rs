@@ -0,0 +1,4 @@
+pub mod mongodb;
+pub mod mysql;
+pub mod postgresql;
+pub mod redis;
@@ -0,0 +1,461 @@
+use tera::Context as TeraContext;
+
+use crate::cloud_provider::plan::ServicePlan;
+use crate::cloud_provider::service::{
+    backup_stateful_service, check_service_version, clone_stateful_service, default_tera_context,
+    delete_database_backup_job, delete_stateful_service, deploy_stateful_service, downgrade_stateful_service,
+    get_tfstate_name, get_tfstate_suffix, plan_stateful_service, restore_stateful_service, scale_down_database,
+    send_progress_on_long_task, send_progress_on_long_task_with_message, upgrade_stateful_service, Action, Backup,
+    Create, Database, DatabaseBackupAction, DatabaseOptions, DatabaseType, Delete, Downgrade, Helm, Pause, Plan,
+    Service, ServiceType, StatefulService, Terraform, Upgrade,
+};
+use crate::cloud_provider::utilities::{get_self_hosted_postgres_version, sanitize_name};
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::helm::Timeout;
+use crate::cmd::kubectl;
+use crate::error::{EngineError, EngineErrorScope};
+use crate::models::{Context, Listen, Listener, Listeners};
+
+pub struct PostgreSQL {
+    context: Context,
+    id: String,
+    action: Action,
+    name: String,
+    version: String,
+    fqdn: String,
+    fqdn_id: String,
+    total_cpus: String,
+    total_ram_in_mib: u32,
+    database_instance_type: String,
+    options: DatabaseOptions,
+    listeners: Listeners,
+}
+
+impl PostgreSQL {
+    pub fn new(
+        context: Context,
+        id: &str,
+        action: Action,
+        name: &str,
+        version: &str,
+        fqdn: &str,
+        fqdn_id: &str,
+        total_cpus: String,
+        total_ram_in_mib: u32,
+        database_instance_type: &str,
+        options: DatabaseOptions,
+        listeners: Listeners,
+    ) -> Self {
+        PostgreSQL {
+            context,
+            action,
+            id: id.to_string(),
+            name: name.to_string(),
+            version: version.to_string(),
+            fqdn: fqdn.to_string(),
+            fqdn_id: fqdn_id.to_string(),
+            total_cpus,
+            total_ram_in_mib,
+            database_instance_type: database_instance_type.to_string(),
+            options,
+            listeners,
+        }
+    }
+
+    fn matching_correct_version(&self) -> Result<String, EngineError> {
+        check_service_version(get_self_hosted_postgres_version(self.version()), self)
+    }
+}
+
+impl StatefulService for PostgreSQL {}
+
+impl Service for PostgreSQL {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn service_type(&self) -> ServiceType {
+        ServiceType::Database(DatabaseType::PostgreSQL(&self.options))
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn sanitized_name(&self) -> String {
+        sanitize_name("postgresql", self.name())
+    }
+
+    fn version(&self) -> &str {
+        self.version.as_str()
+    }
+
+    fn action(&self) -> &Action {
+        &self.action
+    }
+
+    fn private_port(&self) -> Option<u16> {
+        Some(self.options.port)
+    }
+
+    fn start_timeout(&self) -> Timeout<u32> {
+        Timeout::Default
+    }
+
+    fn total_cpus(&self) -> String {
+        self.total_cpus.to_string()
+    }
+
+    fn cpu_burst(&self) -> String {
+        self.total_cpus.to_string()
+    }
+
+    fn total_ram_in_mib(&self) -> u32 {
+        self.total_ram_in_mib
+    }
+
+    fn total_instances(&self) -> u16 {
+        1
+    }
+
+    fn tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        let (kubernetes, environment) = match target {
+            DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+            DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+        };
+
+        let mut context = default_tera_context(self, kubernetes, environment);
+
+        // we need the kubernetes config file to store tfstates file in kube secrets
+        let kube_config_file_path = kubernetes.config_file_path()?;
+        context.insert("kubeconfig_path", &kube_config_file_path);
+
+        kubectl::kubectl_exec_create_namespace_without_labels(
+            &environment.namespace(),
+            kube_config_file_path.as_str(),
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        );
+
+        context.insert("namespace", environment.namespace());
+
+        let version = self.matching_correct_version()?;
+        context.insert("version", &version);
+
+        for (k, v) in kubernetes.cloud_provider().tera_context_environment_variables() {
+            context.insert(k, v);
+        }
+
+        context.insert("kubernetes_cluster_id", kubernetes.id());
+        context.insert("kubernetes_cluster_name", kubernetes.name());
+
+        context.insert("fqdn_id", self.fqdn_id.as_str());
+        context.insert("fqdn", self.fqdn.as_str());
+
+        context.insert("database_db_name", self.name());
+        context.insert("database_login", self.options.login.as_str());
+        context.insert("database_password", self.options.password.as_str());
+        context.insert("database_port", &self.private_port());
+        context.insert("database_disk_size_in_gib", &self.options.disk_size_in_gib);
+        context.insert("database_instance_type", &self.database_instance_type);
+        context.insert("database_disk_type", &self.options.database_disk_type);
+        context.insert("database_ram_size_in_mib", &self.total_ram_in_mib);
+        context.insert("database_total_cpus", &self.total_cpus);
+        context.insert("database_fqdn", &self.options.host.as_str());
+        context.insert("publicly_accessible", &self.options.public_access.is_some());
+        context.insert(
+            "public_ports",
+            &self
+                .options
+                .public_access
+                .as_ref()
+                .map(|public_access| public_access.to_public_port_data_templates())
+                .unwrap_or_default(),
+        );
+        context.insert("database_id", &self.id());
+        context.insert("tfstate_suffix_name", &get_tfstate_suffix(self));
+        context.insert("tfstate_name", &get_tfstate_name(self));
+
+        context.insert("delete_automated_backups", &self.context().is_test_cluster());
+
+        if self.context.resource_expiration_in_seconds().is_some() {
+            context.insert(
+                "resource_expiration_in_seconds",
+                &self.context.resource_expiration_in_seconds(),
+            )
+        }
+
+        Ok(context)
+    }
+
+    fn selector(&self) -> String {
+        format!("app={}", self.sanitized_name())
+    }
+
+    fn engine_error_scope(&self) -> EngineErrorScope {
+        EngineErrorScope::Database(
+            self.id().to_string(),
+            self.service_type().name().to_string(),
+            self.name().to_string(),
+        )
+    }
+}
+
+impl Database for PostgreSQL {}
+
+impl Helm for PostgreSQL {
+    fn helm_release_name(&self) -> String {
+        crate::string::cut(format!("postgresql-{}", self.id()), 50)
+    }
+
+    fn helm_chart_dir(&self) -> String {
+        format!("{}/common/services/postgresql", self.context.lib_root_dir())
+    }
+
+    fn helm_chart_values_dir(&self) -> String {
+        format!("{}/gcp/chart_values/postgresql", self.context.lib_root_dir())
+    }
+
+    fn helm_chart_external_name_service_dir(&self) -> String {
+        format!("{}/common/charts/external-name-svc", self.context.lib_root_dir())
+    }
+}
+
+impl Terraform for PostgreSQL {
+    fn terraform_common_resource_dir_path(&self) -> String {
+        format!("{}/gcp/services/common", self.context.lib_root_dir())
+    }
+
+    fn terraform_resource_dir_path(&self) -> String {
+        format!("{}/gcp/services/postgresql", self.context.lib_root_dir())
+    }
+}
+
+impl Create for PostgreSQL {
+    fn on_create(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.PostgreSQL.on_create() called for {}", self.name());
+
+        send_progress_on_long_task(
+            self,
+            crate::cloud_provider::service::Action::Create,
+            Box::new(|| {
+                deploy_stateful_service(target, self)?;
+
+                // managed databases are exposed by the cloud provider, not by a load balancer of the cluster
+                match (target, &self.options.public_access) {
+                    (DeploymentTarget::SelfHosted(..), Some(public_access)) => {
+                        crate::cloud_provider::service::report_public_access_address(
+                            target,
+                            self,
+                            public_access,
+                            |kubernetes_config, namespace, selector, envs| {
+                                crate::cmd::kubectl::do_kubectl_exec_get_external_ingress_ip(
+                                    kubernetes_config,
+                                    namespace,
+                                    selector,
+                                    envs,
+                                )
+                            },
+                        )
+                    }
+                    _ => Ok(()),
+                }
+            }),
+        )
+    }
+
+    fn on_create_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_create_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.PostgreSQL.on_create_error() called for {}", self.name());
+
+        Ok(())
+    }
+}
+
+impl Pause for PostgreSQL {
+    fn on_pause(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.PostgreSQL.on_pause() called for {}", self.name());
+
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Pause, || {
+            scale_down_database(target, self, 0)
+        })
+    }
+
+    fn on_pause_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_pause_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.PostgreSQL.on_pause_error() called for {}", self.name());
+
+        Ok(())
+    }
+}
+
+impl Delete for PostgreSQL {
+    fn on_delete(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.PostgreSQL.on_delete() called for {}", self.name());
+
+        send_progress_on_long_task(
+            self,
+            crate::cloud_provider::service::Action::Delete,
+            Box::new(|| delete_stateful_service(target, self)),
+        )
+    }
+
+    fn on_delete_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_delete_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.PostgreSQL.on_create_error() called for {}", self.name());
+
+        Ok(())
+    }
+}
+
+impl Plan for PostgreSQL {
+    fn on_plan(&self, target: &DeploymentTarget) -> Result<ServicePlan, EngineError> {
+        info!("GCP.PostgreSQL.on_plan() called for {}", self.name());
+
+        plan_stateful_service(target, self, self.matching_correct_version()?.as_str())
+    }
+}
+
+impl crate::cloud_provider::service::Clone for PostgreSQL {
+    fn on_clone(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.PostgreSQL.on_clone() called for {}", self.name());
+
+        send_progress_on_long_task_with_message(
+            self,
+            Some(format!(
+                "{} '{}' clone is in progress...",
+                self.service_type().name(),
+                self.name_with_id()
+            )),
+            Action::Create,
+            || clone_stateful_service(target, self),
+        )
+    }
+
+    fn on_clone_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_clone_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.PostgreSQL.on_clone_error() called for {}", self.name());
+
+        let _ = delete_database_backup_job(target, self, DatabaseBackupAction::Clone)?;
+        delete_database_backup_job(target, self, DatabaseBackupAction::Restore)
+    }
+}
+
+impl Upgrade for PostgreSQL {
+    fn on_upgrade(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.PostgreSQL.on_upgrade() called for {}", self.name());
+
+        send_progress_on_long_task(self, Action::Create, || {
+            upgrade_stateful_service(target, self, self.matching_correct_version()?.as_str())
+        })
+    }
+
+    fn on_upgrade_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_upgrade_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.PostgreSQL.on_upgrade_error() called for {}", self.name());
+
+        let _ = delete_database_backup_job(target, self, DatabaseBackupAction::Backup)?;
+        delete_database_backup_job(target, self, DatabaseBackupAction::Restore)
+    }
+}
+
+impl Downgrade for PostgreSQL {
+    fn on_downgrade(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.PostgreSQL.on_downgrade() called for {}", self.name());
+
+        send_progress_on_long_task(self, Action::Create, || {
+            downgrade_stateful_service(target, self, self.matching_correct_version()?.as_str())
+        })
+    }
+
+    fn on_downgrade_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_downgrade_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.PostgreSQL.on_downgrade_error() called for {}", self.name());
+
+        let _ = delete_database_backup_job(target, self, DatabaseBackupAction::Backup)?;
+        delete_database_backup_job(target, self, DatabaseBackupAction::Restore)
+    }
+}
+
+impl Backup for PostgreSQL {
+    fn on_backup(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.PostgreSQL.on_backup() called for {}", self.name());
+
+        send_progress_on_long_task_with_message(
+            self,
+            Some(format!(
+                "{} '{}' backup is in progress...",
+                self.service_type().name(),
+                self.name_with_id()
+            )),
+            Action::Create,
+            || backup_stateful_service(target, self),
+        )
+    }
+
+    fn on_backup_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_backup_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.PostgreSQL.on_backup_error() called for {}", self.name());
+
+        delete_database_backup_job(target, self, DatabaseBackupAction::Backup)
+    }
+
+    fn on_restore(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.PostgreSQL.on_restore() called for {}", self.name());
+
+        send_progress_on_long_task_with_message(
+            self,
+            Some(format!(
+                "{} '{}' restore is in progress...",
+                self.service_type().name(),
+                self.name_with_id()
+            )),
+            Action::Create,
+            || restore_stateful_service(target, self, self.options.restore_from.as_ref()),
+        )
+    }
+
+    fn on_restore_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_restore_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.PostgreSQL.on_restore_error() called for {}", self.name());
+
+        delete_database_backup_job(target, self, DatabaseBackupAction::Restore)
+    }
+}
+
+impl Listen for PostgreSQL {
+    fn listeners(&self) -> &Listeners {
+        &self.listeners
+    }
+
+    fn add_listener(&mut self, listener: Listener) {
+        self.listeners.push(listener);
+    }
+}
@@ -0,0 +1,311 @@
+use tera::Context as TeraContext;
+
+use crate::build_platform::Image;
+use crate::cloud_provider::models::{EnvironmentVariable, EnvironmentVariableDataTemplate, JobSchedule};
+use crate::cloud_provider::plan::ServicePlan;
+use crate::cloud_provider::service::{
+    default_tera_context, delete_stateless_service, deploy_stateless_service_error, deploy_user_job, pause_job,
+    plan_stateless_service, send_progress_on_long_task, Action, Create, Delete, Helm, Pause, Plan, Service,
+    ServiceType, StatelessService,
+};
+use crate::cloud_provider::utilities::sanitize_name;
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::helm::Timeout;
+use crate::error::{EngineError, EngineErrorScope};
+use crate::models::{Context, Listen, Listener, Listeners};
+
+pub struct Job {
+    context: Context,
+    id: String,
+    action: Action,
+    name: String,
+    total_cpus: String,
+    total_ram_in_mib: u32,
+    schedule: JobSchedule,
+    command: Vec<String>,
+    max_duration_in_seconds: u32,
+    max_restarts: u32,
+    image: Image,
+    environment_variables: Vec<EnvironmentVariable>,
+    listeners: Listeners,
+}
+
+impl Job {
+    pub fn new(
+        context: Context,
+        id: &str,
+        action: Action,
+        name: &str,
+        total_cpus: String,
+        total_ram_in_mib: u32,
+        schedule: JobSchedule,
+        command: Vec<String>,
+        max_duration_in_seconds: u32,
+        max_restarts: u32,
+        image: Image,
+        environment_variables: Vec<EnvironmentVariable>,
+        listeners: Listeners,
+    ) -> Self {
+        Job {
+            context,
+            id: id.to_string(),
+            action,
+            name: name.to_string(),
+            total_cpus,
+            total_ram_in_mib,
+            schedule,
+            command,
+            max_duration_in_seconds,
+            max_restarts,
+            image,
+            environment_variables,
+            listeners,
+        }
+    }
+}
+
+impl crate::cloud_provider::service::Job for Job {
+    fn schedule(&self) -> &JobSchedule {
+        &self.schedule
+    }
+
+    fn max_duration_in_seconds(&self) -> u32 {
+        self.max_duration_in_seconds
+    }
+}
+
+impl crate::cloud_provider::service::Application for Job {
+    fn image(&self) -> &Image {
+        &self.image
+    }
+
+    fn set_image(&mut self, image: Image) {
+        self.image = image;
+    }
+}
+
+impl Helm for Job {
+    fn helm_release_name(&self) -> String {
+        crate::string::cut(format!("job-{}-{}", self.name, self.id), 50)
+    }
+
+    fn helm_chart_dir(&self) -> String {
+        format!("{}/common/services/q-job", self.context.lib_root_dir())
+    }
+
+    fn helm_chart_values_dir(&self) -> String {
+        String::new()
+    }
+
+    fn helm_chart_external_name_service_dir(&self) -> String {
+        String::new()
+    }
+}
+
+impl StatelessService for Job {}
+
+impl Service for Job {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn service_type(&self) -> ServiceType {
+        ServiceType::Job
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn sanitized_name(&self) -> String {
+        sanitize_name("job", self.name())
+    }
+
+    fn version(&self) -> &str {
+        self.image.commit_id.as_str()
+    }
+
+    fn action(&self) -> &Action {
+        &self.action
+    }
+
+    fn private_port(&self) -> Option<u16> {
+        None
+    }
+
+    fn start_timeout(&self) -> Timeout<u32> {
+        Timeout::Default
+    }
+
+    fn total_cpus(&self) -> String {
+        self.total_cpus.to_string()
+    }
+
+    fn cpu_burst(&self) -> String {
+        self.total_cpus.to_string()
+    }
+
+    fn total_ram_in_mib(&self) -> u32 {
+        self.total_ram_in_mib
+    }
+
+    fn total_instances(&self) -> u16 {
+        1
+    }
+
+    fn tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        let (kubernetes, environment) = match target {
+            DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+            DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+        };
+
+        let mut context = default_tera_context(self, kubernetes, environment);
+        let commit_id = self.image.commit_id.as_str();
+
+        context.insert("helm_app_version", &commit_id[..7]);
+
+        match &self.image.registry_url {
+            Some(registry_url) => context.insert("image_name_with_tag", registry_url.as_str()),
+            None => {
+                let image_name_with_tag = self.image.name_with_tag();
+                warn!(
+                    "there is no registry url, use image name with tag with the default container registry: {}",
+                    image_name_with_tag.as_str()
+                );
+                context.insert("image_name_with_tag", image_name_with_tag.as_str());
+            }
+        }
+
+        let environment_variables = self
+            .environment_variables
+            .iter()
+            .map(|ev| EnvironmentVariableDataTemplate {
+                key: ev.key.clone(),
+                value: ev.value.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        context.insert("environment_variables", &environment_variables);
+
+        match self.image.registry_name.as_ref() {
+            Some(registry_name) => {
+                context.insert("is_registry_secret", &true);
+                context.insert("registry_secret", registry_name);
+            }
+            None => {
+                context.insert("is_registry_secret", &false);
+            }
+        };
+
+        context.insert("command", &self.command);
+        context.insert("cron_schedule", &self.schedule.cron_schedule());
+        context.insert("job_suspended", &false);
+        context.insert("job_max_restarts", &self.max_restarts);
+        context.insert("job_max_duration_in_seconds", &self.max_duration_in_seconds);
+
+        Ok(context)
+    }
+
+    fn selector(&self) -> String {
+        format!("app={}", self.sanitized_name())
+    }
+
+    fn engine_error_scope(&self) -> EngineErrorScope {
+        EngineErrorScope::Job(self.id().to_string(), self.name().to_string())
+    }
+}
+
+impl Create for Job {
+    fn on_create(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.job.on_create() called for {}", self.name());
+
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Create, || {
+            deploy_user_job(target, self)
+        })
+    }
+
+    fn on_create_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_create_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.job.on_create_error() called for {}", self.name());
+
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Create, || {
+            deploy_stateless_service_error(target, self)
+        })
+    }
+}
+
+impl Pause for Job {
+    fn on_pause(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.job.on_pause() called for {}", self.name());
+
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Pause, || {
+            pause_job(target, self)
+        })
+    }
+
+    fn on_pause_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_pause_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.job.on_pause_error() called for {}", self.name());
+
+        Ok(())
+    }
+}
+
+impl Delete for Job {
+    fn on_delete(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.job.on_delete() called for {}", self.name());
+
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Delete, || {
+            delete_stateless_service(target, self, false)
+        })
+    }
+
+    fn on_delete_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_delete_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.job.on_delete_error() called for {}", self.name());
+
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Delete, || {
+            delete_stateless_service(target, self, true)
+        })
+    }
+}
+
+impl Plan for Job {
+    fn on_plan(&self, target: &DeploymentTarget) -> Result<ServicePlan, EngineError> {
+        info!("GCP.job.on_plan() called for {}", self.name());
+
+        plan_stateless_service(
+            target,
+            self,
+            Some(
+                self.image
+                    .registry_url
+                    .clone()
+                    .unwrap_or_else(|| self.image.name_with_tag()),
+            ),
+        )
+    }
+}
+
+impl Listen for Job {
+    fn listeners(&self) -> &Listeners {
+        &self.listeners
+    }
+
+    fn add_listener(&mut self, listener: Listener) {
+        self.listeners.push(listener);
+    }
+}
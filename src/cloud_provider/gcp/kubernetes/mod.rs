@@ -0,0 +1,823 @@
+use itertools::Itertools;
+use retry::delay::Fibonacci;
+use retry::Error::Operation;
+use retry::OperationResult;
+use serde::{Deserialize, Serialize};
+use tera::Context as TeraContext;
+
+use crate::cloud_provider::environment::Environment;
+use crate::cloud_provider::gcp::kubernetes::node::Node;
+use crate::cloud_provider::gcp::GCP;
+use crate::cloud_provider::kubernetes::{
+    is_kubernetes_upgrade_required, required_upgrade_on_create, Kind, Kubernetes, KubernetesNode, KubernetesNodesType,
+    KubernetesUpgradeStatus,
+};
+use crate::cloud_provider::models::WorkerNodeDataTemplate;
+use crate::cloud_provider::plan::EnvironmentPlan;
+use crate::cloud_provider::{kubernetes, CloudProvider};
+use crate::cmd::terraform::{
+    terraform_exec, terraform_init_validate_destroy, terraform_init_validate_plan_apply,
+    terraform_init_validate_state_list,
+};
+use crate::dns_provider;
+use crate::dns_provider::DnsProvider;
+use crate::error::{cast_simple_error_to_engine_error, EngineError, EngineErrorCause};
+use crate::fs::workspace_directory;
+use crate::models::{
+    Context, Features, Listen, Listener, Listeners, ListenersHelper, ProgressInfo, ProgressLevel, ProgressScope,
+};
+use crate::object_storage::gcs::GCS;
+use crate::object_storage::ObjectStorage;
+use crate::string::terraform_list_format;
+
+pub mod node;
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Options {
+    // GCP
+    pub vpc_name: String,
+    // range of the nodes subnet, pods and services ranges are allocated by GKE
+    pub vpc_cidr_block: String,
+    // Qovery
+    pub qovery_api_url: String,
+    pub engine_version_controller_token: String,
+    pub agent_version_controller_token: String,
+    pub grafana_admin_user: String,
+    pub grafana_admin_password: String,
+    pub discord_api_key: String,
+    pub qovery_nats_url: String,
+    pub qovery_nats_user: String,
+    pub qovery_nats_password: String,
+    pub qovery_ssh_key: String,
+    // Others
+    pub tls_email_report: String,
+}
+
+pub struct GKE<'a> {
+    context: Context,
+    id: String,
+    name: String,
+    version: String,
+    region: String,
+    cloud_provider: &'a GCP,
+    nodes: Vec<Node>,
+    dns_provider: &'a dyn DnsProvider,
+    gcs: GCS,
+    template_directory: String,
+    options: Options,
+    listeners: Listeners,
+}
+
+impl<'a> GKE<'a> {
+    pub fn new(
+        context: Context,
+        id: &str,
+        name: &str,
+        version: &str,
+        region: &str,
+        cloud_provider: &'a GCP,
+        dns_provider: &'a dyn DnsProvider,
+        options: Options,
+        nodes: Vec<Node>,
+    ) -> Self {
+        let template_directory = format!("{}/gcp/bootstrap", context.lib_root_dir());
+
+        let gcs = GCS::new(
+            context.clone(),
+            "gcs-temp-id".to_string(),
+            "my-gcs-object-storage".to_string(),
+            cloud_provider.gcs_access_key_id.clone(),
+            cloud_provider.gcs_secret_access_key.clone(),
+            region.to_string(),
+        );
+
+        GKE {
+            context,
+            id: id.to_string(),
+            name: name.to_string(),
+            version: version.to_string(),
+            region: region.to_string(),
+            cloud_provider,
+            dns_provider,
+            gcs,
+            options,
+            nodes,
+            template_directory,
+            listeners: cloud_provider.listeners.clone(), // copy listeners from CloudProvider
+        }
+    }
+
+    // create a context to render tf files (terraform) contained in lib/gcp/
+    fn tera_context(&self) -> TeraContext {
+        let mut context = TeraContext::new();
+
+        // GKE
+        context.insert("gke_cluster_id", &self.id());
+        context.insert("gke_master_name", &self.name());
+        context.insert("gke_version", &self.version());
+        context.insert("force_upgrade", &self.context.requires_forced_upgrade());
+
+        // Network
+        context.insert("vpc_name", self.options.vpc_name.as_str());
+        context.insert("vpc_cidr_block", self.options.vpc_cidr_block.as_str());
+
+        // Qovery
+        context.insert("organization_id", self.cloud_provider.organization_id());
+        context.insert(
+            "engine_version_controller_token",
+            &self.options.engine_version_controller_token,
+        );
+
+        context.insert(
+            "agent_version_controller_token",
+            &self.options.agent_version_controller_token,
+        );
+
+        context.insert("test_cluster", &self.context.is_test_cluster());
+        context.insert("qovery_api_url", self.options.qovery_api_url.as_str());
+        context.insert("qovery_nats_url", self.options.qovery_nats_url.as_str());
+        context.insert("qovery_nats_user", self.options.qovery_nats_user.as_str());
+        context.insert("qovery_nats_password", self.options.qovery_nats_password.as_str());
+        context.insert("qovery_ssh_key", self.options.qovery_ssh_key.as_str());
+        context.insert("discord_api_key", self.options.discord_api_key.as_str());
+
+        // Qovery features
+        context.insert(
+            "log_history_enabled",
+            &self.context.is_feature_enabled(&Features::LogsHistory),
+        );
+        context.insert(
+            "metrics_history_enabled",
+            &self.context.is_feature_enabled(&Features::MetricsHistory),
+        );
+
+        // grafana credentials
+        context.insert("grafana_admin_user", self.options.grafana_admin_user.as_str());
+
+        context.insert("grafana_admin_password", self.options.grafana_admin_password.as_str());
+
+        // TLS
+        let lets_encrypt_url = match self.context.is_test_cluster() {
+            true => "https://acme-staging-v02.api.letsencrypt.org/directory",
+            false => "https://acme-v02.api.letsencrypt.org/directory",
+        };
+
+        context.insert("acme_server_url", lets_encrypt_url);
+        context.insert("dns_email_report", &self.options.tls_email_report);
+
+        // DNS management
+        let managed_dns_list = vec![self.dns_provider.name()];
+        let managed_dns_domains_helm_format = vec![format!("\"{}\"", self.dns_provider.domain())];
+        let managed_dns_domains_terraform_format = terraform_list_format(vec![self.dns_provider.domain().to_string()]);
+
+        let managed_dns_resolvers: Vec<String> = self
+            .dns_provider
+            .resolvers()
+            .iter()
+            .map(|x| format!("{}", x.clone().to_string()))
+            .collect();
+
+        let managed_dns_resolvers_terraform_format = terraform_list_format(managed_dns_resolvers);
+
+        context.insert("managed_dns", &managed_dns_list);
+        context.insert("managed_dns_domains_helm_format", &managed_dns_domains_helm_format);
+
+        context.insert(
+            "managed_dns_domains_terraform_format",
+            &managed_dns_domains_terraform_format,
+        );
+
+        context.insert(
+            "managed_dns_resolvers_terraform_format",
+            &managed_dns_resolvers_terraform_format,
+        );
+
+        match self.dns_provider.kind() {
+            dns_provider::Kind::Cloudflare => {
+                context.insert("external_dns_provider", "cloudflare");
+                context.insert("cloudflare_api_token", self.dns_provider.token());
+                context.insert("cloudflare_email", self.dns_provider.account());
+            }
+        };
+
+        // GCP
+        context.insert("gcp_project_id", &self.cloud_provider.project_id);
+        context.insert("gcp_region", &self.region);
+
+        // GCS Credentials
+        context.insert("gcs_access_key_id", &self.cloud_provider.gcs_access_key_id);
+        context.insert("gcs_secret_access_key", &self.cloud_provider.gcs_secret_access_key);
+
+        // GCS buckets
+        let gcs_kubeconfig_bucket = format!("qovery-kubeconfigs-{}", self.id.as_str());
+        context.insert("gcs_bucket_kubeconfig", &gcs_kubeconfig_bucket);
+        context.insert("gcs_bucket_database_backups", &self.database_backups_bucket_name());
+        context.insert("gcp_terraform_backend_bucket", &self.tfstates_bucket_name());
+
+        context.insert("nginx_enable_horizontal_autoscaler", "true");
+        context.insert("nginx_minimum_replicas", "2");
+        context.insert("nginx_maximum_replicas", "2");
+        context.insert("nginx_requests_cpu", "250m");
+        context.insert("nginx_requests_memory", "384Mi");
+        context.insert("nginx_limit_cpu", "1");
+        context.insert("nginx_limit_memory", "384Mi");
+
+        // kubernetes workers
+        let worker_nodes = self
+            .nodes
+            .iter()
+            .group_by(|e| e.instance_type())
+            .into_iter()
+            .map(|(instance_type, group)| (instance_type, group.collect::<Vec<_>>()))
+            .map(|(instance_type, nodes)| WorkerNodeDataTemplate {
+                instance_type: instance_type.to_string(),
+                desired_size: "3".to_string(),
+                max_size: nodes.len().to_string(),
+                min_size: "3".to_string(),
+            })
+            .collect::<Vec<WorkerNodeDataTemplate>>();
+
+        context.insert("gke_worker_nodes", &worker_nodes);
+        context.insert("gke_paused", &false);
+
+        context
+    }
+
+    // the terraform state is kept in the customer project, next to the cluster
+    fn tfstates_bucket_name(&self) -> String {
+        format!("qovery-terraform-tfstates-{}", self.id())
+    }
+
+    // render the bootstrap terraform files into the workspace directory, and return it
+    fn generate_bootstrap_files(&self, context: &TeraContext) -> Result<String, EngineError> {
+        // the terraform gcs backend only accepts a credentials file
+        let mut context = context.clone();
+        context.insert("gcp_credentials_file", &self.cloud_provider.credentials_file_path()?);
+
+        let temp_dir = workspace_directory(
+            self.context.workspace_root_dir(),
+            self.context.execution_id(),
+            format!("gcp/bootstrap/{}", self.name()),
+        );
+
+        let _ = cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            crate::template::generate_and_copy_all_files_into_dir(
+                self.template_directory.as_str(),
+                temp_dir.as_str(),
+                &context,
+            ),
+        )?;
+
+        // copy lib/common/bootstrap/charts directory (and sub directory) into the lib/gcp/bootstrap/common/charts directory.
+        // this is due to the required dependencies of lib/gcp/bootstrap/*.tf files
+        let common_charts_temp_dir = format!("{}/common/charts", temp_dir.as_str());
+        let _ = cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            crate::template::copy_non_template_files(
+                format!("{}/common/bootstrap/charts", self.context.lib_root_dir()),
+                common_charts_temp_dir.as_str(),
+            ),
+        )?;
+
+        Ok(temp_dir)
+    }
+
+    fn upgrade(&self, kubernetes_upgrade_status: KubernetesUpgradeStatus) -> Result<(), EngineError> {
+        let listeners_helper = ListenersHelper::new(&self.listeners);
+        let send_to_customer = |message: &str| {
+            listeners_helper.upgrade_in_progress(ProgressInfo::new(
+                ProgressScope::Infrastructure {
+                    execution_id: self.context.execution_id().to_string(),
+                },
+                ProgressLevel::Info,
+                Some(message),
+                self.context.execution_id(),
+            ))
+        };
+        send_to_customer(
+            format!(
+                "Start preparing GKE upgrade process {} cluster with id {}",
+                self.name(),
+                self.id()
+            )
+            .as_str(),
+        );
+
+        let kubeconfig = match self.config_file() {
+            Ok(f) => f.0,
+            Err(e) => {
+                error!("Can't perform a Kubernetes upgrade, can't locate kubeconfig");
+                return Err(e);
+            }
+        };
+
+        //
+        // Upgrade master nodes
+        //
+
+        match &kubernetes_upgrade_status.required_upgrade_on {
+            Some(KubernetesNodesType::Masters) => {
+                let message = format!(
+                    "Start upgrading process for master nodes on {}/{}",
+                    self.name(),
+                    self.id()
+                );
+                info!("{}", &message);
+                send_to_customer(&message);
+
+                // the master and the node pools share the same version variable, GKE upgrades the control plane first,
+                // then the node pools are upgraded by surge, one node at a time
+                let temp_dir = self.generate_bootstrap_files(&self.tera_context())?;
+
+                send_to_customer(format!("Upgrading Kubernetes {} master nodes", self.name()).as_str());
+
+                match cast_simple_error_to_engine_error(
+                    self.engine_error_scope(),
+                    self.context.execution_id(),
+                    terraform_init_validate_plan_apply(temp_dir.as_str(), self.context.is_dry_run_deploy()),
+                ) {
+                    Ok(_) => {
+                        let message = format!(
+                            "Kubernetes {} master nodes have been successfully upgraded",
+                            self.name()
+                        );
+                        info!("{}", &message);
+                        send_to_customer(&message);
+                    }
+                    Err(e) => {
+                        error!(
+                            "Error while upgrading master nodes for cluster {} with id {}.",
+                            self.name(),
+                            self.id()
+                        );
+                        return Err(e);
+                    }
+                }
+            }
+            Some(KubernetesNodesType::Workers) => {
+                info!("No need to perform Kubernetes master upgrade, they are already up to date")
+            }
+            None => {
+                info!("No Kubernetes upgrade required, masters and workers are already up to date");
+                return Ok(());
+            }
+        }
+
+        //
+        // Check worker nodes
+        //
+
+        let message = format!(
+            "Waiting for worker nodes of Kubernetes cluster {} to be upgraded",
+            self.name()
+        );
+        info!("{}", &message);
+        send_to_customer(message.as_str());
+
+        // node pools are replaced one node at a time by GKE, it can take a while
+        let workers_upgrade_result =
+            retry::retry(
+                Fibonacci::from_millis(10000).take(10),
+                || match is_kubernetes_upgrade_required(
+                    &kubeconfig,
+                    self.version(),
+                    self.cloud_provider.credentials_environment_variables(),
+                ) {
+                    Ok(status) => match status.required_upgrade_on {
+                        None => OperationResult::Ok(()),
+                        Some(_) => {
+                            info!(
+                                "worker nodes of cluster {} are still in version {}, waiting for {}",
+                                self.name(),
+                                status.deployed_workers_version,
+                                status.requested_version
+                            );
+                            OperationResult::Retry(format!(
+                                "worker nodes are still in version {} instead of {}",
+                                status.deployed_workers_version, status.requested_version
+                            ))
+                        }
+                    },
+                    Err(e) => OperationResult::Retry(format!("{:?}", e.message)),
+                },
+            );
+
+        match workers_upgrade_result {
+            Ok(_) => {
+                let message = format!(
+                    "Kubernetes {} workers nodes have been successfully upgraded",
+                    self.name()
+                );
+                info!("{}", &message);
+                send_to_customer(&message);
+                Ok(())
+            }
+            Err(Operation { error, .. }) => Err(self.engine_error(
+                EngineErrorCause::Internal,
+                format!(
+                    "Error while upgrading worker nodes for cluster {} with id {}: {}",
+                    self.name(),
+                    self.id(),
+                    error
+                ),
+            )),
+            Err(retry::Error::Internal(msg)) => Err(self.engine_error(
+                EngineErrorCause::Internal,
+                format!(
+                    "Error while upgrading worker nodes for cluster {} with id {}: {}",
+                    self.name(),
+                    self.id(),
+                    msg
+                ),
+            )),
+        }
+    }
+}
+
+impl<'a> Kubernetes for GKE<'a> {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::Gke
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn version(&self) -> &str {
+        self.version.as_str()
+    }
+
+    fn region(&self) -> &str {
+        self.region.as_str()
+    }
+
+    fn cloud_provider(&self) -> &dyn CloudProvider {
+        self.cloud_provider
+    }
+
+    fn dns_provider(&self) -> &dyn DnsProvider {
+        self.dns_provider
+    }
+
+    fn config_file_store(&self) -> &dyn ObjectStorage {
+        &self.gcs
+    }
+
+    fn is_valid(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_create(&self) -> Result<(), EngineError> {
+        info!("GKE.on_create() called for {}", self.name());
+
+        let listeners_helper = ListenersHelper::new(&self.listeners);
+
+        listeners_helper.deployment_in_progress(ProgressInfo::new(
+            ProgressScope::Infrastructure {
+                execution_id: self.context.execution_id().to_string(),
+            },
+            ProgressLevel::Info,
+            Some(format!(
+                "start to create GKE cluster {} with id {}",
+                self.name(),
+                self.id()
+            )),
+            self.context.execution_id(),
+        ));
+
+        // upgrade cluster instead if required
+        match self.config_file() {
+            Ok(f) => {
+                if let Some(x) = required_upgrade_on_create(is_kubernetes_upgrade_required(
+                    f.0,
+                    &self.version,
+                    self.cloud_provider.credentials_environment_variables(),
+                )) {
+                    return self.upgrade(x);
+                }
+            }
+            Err(_) => {
+                info!("Kubernetes cluster upgrade not required, config file is not found and cluster have certainly never been deployed before");
+            }
+        };
+
+        // the terraform backend bucket has to exist before running terraform init
+        match self.gcs.create_bucket(self.tfstates_bucket_name().as_str()) {
+            Ok(_) => info!(
+                "terraform state bucket {} has been created",
+                self.tfstates_bucket_name()
+            ),
+            Err(_) => warn!("terraform state bucket {} already exists", self.tfstates_bucket_name()),
+        };
+
+        // generate terraform files and copy them into temp dir
+        let temp_dir = self.generate_bootstrap_files(&self.tera_context())?;
+
+        let _ = cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            terraform_init_validate_plan_apply(temp_dir.as_str(), self.context.is_dry_run_deploy()),
+        )?;
+
+        Ok(())
+    }
+
+    fn on_create_error(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_upgrade(&self) -> Result<(), EngineError> {
+        info!("GKE.on_upgrade() called for {}", self.name());
+
+        let kubeconfig = match self.config_file() {
+            Ok(f) => f.0,
+            Err(e) => return Err(e),
+        };
+
+        match is_kubernetes_upgrade_required(
+            kubeconfig,
+            &self.version,
+            self.cloud_provider.credentials_environment_variables(),
+        ) {
+            Ok(x) => self.upgrade(x),
+            Err(e) => {
+                let msg = format!(
+                    "Error detected, upgrade won't occurs, but standard deployment. {:?}",
+                    e.message
+                );
+                error!("{}", &msg);
+                Err(self.engine_error(EngineErrorCause::Internal, msg))
+            }
+        }
+    }
+
+    fn on_upgrade_error(&self) -> Result<(), EngineError> {
+        warn!("GKE.on_upgrade_error() called for {}", self.name());
+        Ok(())
+    }
+
+    fn on_downgrade(&self) -> Result<(), EngineError> {
+        info!("GKE.on_downgrade() called for {}", self.name());
+        Ok(())
+    }
+
+    fn on_downgrade_error(&self) -> Result<(), EngineError> {
+        warn!("GKE.on_downgrade_error() called for {}", self.name());
+        Ok(())
+    }
+
+    fn on_pause(&self) -> Result<(), EngineError> {
+        info!("GKE.on_pause() called for {}", self.name());
+
+        let listeners_helper = ListenersHelper::new(&self.listeners);
+        let send_to_customer = |message: &str| {
+            listeners_helper.pause_in_progress(ProgressInfo::new(
+                ProgressScope::Infrastructure {
+                    execution_id: self.context.execution_id().to_string(),
+                },
+                ProgressLevel::Info,
+                Some(message),
+                self.context.execution_id(),
+            ))
+        };
+        send_to_customer(format!("Preparing GKE {} cluster pause with id {}", self.name(), self.id()).as_str());
+
+        // pause: remove all worker nodes to reduce the bill but keep the control plane to keep all the deployment config, certificates etc...
+        let mut context = self.tera_context();
+        context.insert("gke_paused", &true);
+
+        let temp_dir = self.generate_bootstrap_files(&context)?;
+
+        // pause: only select terraform node pools elements to pause to avoid applying on the whole config
+        let tf_workers_resources = cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            terraform_init_validate_state_list(temp_dir.as_str()),
+        )?
+        .into_iter()
+        .filter(|name| name.starts_with("google_container_node_pool."))
+        .collect::<Vec<_>>();
+
+        if tf_workers_resources.is_empty() {
+            return Err(self.engine_error(
+                EngineErrorCause::Internal,
+                "No worker nodes present, can't Pause the infrastructure. This can happen if there where a manual operations on the workers or the infrastructure is already pause.".to_string(),
+            ));
+        }
+
+        // pause: wait for the engine to have 0 running jobs before pausing and avoid getting unreleased lock (from helm or terraform for example)
+        let _ = kubernetes::wait_for_engine_jobs_to_finish(self)?;
+
+        let mut terraform_args_string = vec!["apply".to_string(), "-auto-approve".to_string()];
+        for x in tf_workers_resources {
+            terraform_args_string.push(format!("-target={}", x));
+        }
+        let terraform_args = terraform_args_string.iter().map(|x| &**x).collect();
+
+        let message = format!("Pausing GKE {} cluster deployment with id {}", self.name(), self.id());
+        info!("{}", &message);
+        send_to_customer(&message);
+
+        match cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            terraform_exec(temp_dir.as_str(), terraform_args),
+        ) {
+            Ok(_) => {
+                let message = format!("Kubernetes cluster {} successfully paused", self.name());
+                info!("{}", &message);
+                send_to_customer(&message);
+                Ok(())
+            }
+            Err(e) => {
+                error!("Error while pausing cluster {} with id {}.", self.name(), self.id());
+                Err(e)
+            }
+        }
+    }
+
+    fn on_pause_error(&self) -> Result<(), EngineError> {
+        warn!("GKE.on_pause_error() called for {}", self.name());
+        Err(self.engine_error(
+            EngineErrorCause::Internal,
+            format!("{} Kubernetes cluster failed to pause", self.name()),
+        ))
+    }
+
+    fn on_delete(&self) -> Result<(), EngineError> {
+        info!("GKE.on_delete() called for {}", self.name());
+
+        let listeners_helper = ListenersHelper::new(&self.listeners);
+        let send_to_customer = |message: &str| {
+            listeners_helper.delete_in_progress(ProgressInfo::new(
+                ProgressScope::Infrastructure {
+                    execution_id: self.context.execution_id().to_string(),
+                },
+                ProgressLevel::Info,
+                Some(message),
+                self.context.execution_id(),
+            ))
+        };
+        send_to_customer(format!("Preparing to delete GKE cluster {} with id {}", self.name(), self.id()).as_str());
+
+        // generate terraform files and copy them into temp dir
+        let temp_dir = self.generate_bootstrap_files(&self.tera_context())?;
+
+        // should apply before destroy to be sure destroy will compute on all resources
+        // don't exit on failure, it can happen if we resume a destroy process
+        let message = format!(
+            "Ensuring everything is up to date before deleting cluster {}/{}",
+            self.name(),
+            self.id()
+        );
+        info!("{}", &message);
+        send_to_customer(&message);
+
+        info!("Running Terraform apply before running a delete");
+        if let Err(e) = cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            terraform_init_validate_plan_apply(temp_dir.as_str(), false),
+        ) {
+            error!("An issue occurred during the apply before destroy of Terraform, it may be expected if you're resuming a destroy: {:?}", e.message);
+        };
+
+        // delete everything deployed on the cluster, not to leave cloud resources behind (e.g. load balancers, volumes)
+        let _ = kubernetes::delete_all_deployments(self, &send_to_customer)?;
+
+        let message = format!("Deleting Kubernetes cluster {}/{}", self.name(), self.id());
+        info!("{}", &message);
+        send_to_customer(&message);
+
+        info!("Running Terraform destroy");
+        let terraform_result =
+            retry::retry(
+                Fibonacci::from_millis(60000).take(3),
+                || match cast_simple_error_to_engine_error(
+                    self.engine_error_scope(),
+                    self.context.execution_id(),
+                    terraform_init_validate_destroy(temp_dir.as_str(), false),
+                ) {
+                    Ok(_) => OperationResult::Ok(()),
+                    Err(e) => OperationResult::Retry(e),
+                },
+            );
+
+        match terraform_result {
+            Ok(_) => {}
+            Err(Operation { error, .. }) => return Err(error),
+            Err(retry::Error::Internal(msg)) => {
+                return Err(self.engine_error(
+                    EngineErrorCause::Internal,
+                    format!(
+                        "Error while deleting cluster {} with id {}: {}",
+                        self.name(),
+                        self.id(),
+                        msg
+                    ),
+                ))
+            }
+        }
+
+        // the kubeconfig bucket is force destroyed by terraform, make sure it does not remain if the state was lost
+        let kubeconfig_bucket_name = format!("qovery-kubeconfigs-{}", self.id());
+        if let Err(e) = self.gcs.delete_bucket(kubeconfig_bucket_name.as_str()) {
+            debug!(
+                "kubeconfig bucket {} is already deleted or can't be deleted: {:?}",
+                kubeconfig_bucket_name, e.message
+            );
+        }
+
+        // nothing is managed by terraform anymore, its state can go
+        if let Err(e) = self.gcs.delete_bucket(self.tfstates_bucket_name().as_str()) {
+            warn!(
+                "terraform state bucket {} can't be deleted: {:?}",
+                self.tfstates_bucket_name(),
+                e.message
+            );
+        }
+
+        let message = format!("Kubernetes cluster {}/{} successfully deleted", self.name(), self.id());
+        info!("{}", &message);
+        send_to_customer(&message);
+
+        Ok(())
+    }
+
+    fn on_delete_error(&self) -> Result<(), EngineError> {
+        warn!("GKE.on_delete_error() called for {}", self.name());
+
+        Ok(())
+    }
+
+    fn deploy_environment(&self, environment: &Environment) -> Result<(), EngineError> {
+        info!("GKE.deploy_environment() called for {}", self.name());
+        kubernetes::deploy_environment(self, environment)
+    }
+
+    fn deploy_environment_error(&self, environment: &Environment) -> Result<(), EngineError> {
+        warn!("GKE.deploy_environment_error() called for {}", self.name());
+        kubernetes::deploy_environment_error(self, environment)
+    }
+
+    fn pause_environment(&self, environment: &Environment) -> Result<(), EngineError> {
+        info!("GKE.pause_environment() called for {}", self.name());
+        kubernetes::pause_environment(self, environment)
+    }
+
+    fn pause_environment_error(&self, _environment: &Environment) -> Result<(), EngineError> {
+        warn!("GKE.pause_environment_error() called for {}", self.name());
+        Ok(())
+    }
+
+    fn delete_environment(&self, environment: &Environment) -> Result<(), EngineError> {
+        info!("GKE.delete_environment() called for {}", self.name());
+        kubernetes::delete_environment(self, environment)
+    }
+
+    fn delete_environment_error(&self, _environment: &Environment) -> Result<(), EngineError> {
+        warn!("GKE.delete_environment_error() called for {}", self.name());
+        Ok(())
+    }
+
+    fn backup_environment(&self, environment: &Environment) -> Result<(), EngineError> {
+        info!("GKE.backup_environment() called for {}", self.name());
+        kubernetes::backup_environment(self, environment)
+    }
+
+    fn restore_environment(&self, environment: &Environment) -> Result<(), EngineError> {
+        info!("GKE.restore_environment() called for {}", self.name());
+        kubernetes::restore_environment(self, environment)
+    }
+
+    fn clone_environment(&self, environment: &Environment) -> Result<(), EngineError> {
+        info!("GKE.clone_environment() called for {}", self.name());
+        kubernetes::clone_environment(self, environment)
+    }
+
+    fn plan_environment(&self, environment: &Environment) -> Result<EnvironmentPlan, EngineError> {
+        info!("GKE.plan_environment() called for {}", self.name());
+        kubernetes::plan_environment(self, environment)
+    }
+}
+
+impl<'a> Listen for GKE<'a> {
+    fn listeners(&self) -> &Listeners {
+        &self.listeners
+    }
+
+    fn add_listener(&mut self, listener: Listener) {
+        self.listeners.push(listener);
+    }
+}
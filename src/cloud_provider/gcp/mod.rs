@@ -1,61 +1,147 @@
-// use std::any::Any;
-//
-// use crate::cloud_provider::{CloudProvider, Kind, TerraformStateCredentials};
-// use crate::error::EngineError;
-// use crate::models::{Context, Listener};
-//
-// pub struct GCP {
-//     context: Context,
-//     id: String,
-//     name: String,
-//     p12_file_content: String,
-// }
-//
-// impl GCP {
-//     pub fn new(context: Context, id: &str, name: &str, p12_file_content: &str) -> Self {
-//         GCP {
-//             context,
-//             id: id.to_string(),
-//             name: name.to_string(),
-//             p12_file_content: p12_file_content.to_string(),
-//         }
-//     }
-// }
-//
-// impl<'x> CloudProvider for GCP {
-//     fn context(&self) -> &Context {
-//         &self.context
-//     }
-//
-//     fn kind(&self) -> Kind {
-//         Kind::GCP
-//     }
-//
-//     fn id(&self) -> &str {
-//         self.id.as_str()
-//     }
-//
-//     fn organization_id(&self) -> &str {
-//         unimplemented!()
-//     }
-//
-//     fn name(&self) -> &str {
-//         self.name.as_str()
-//     }
-//
-//     fn is_valid(&self) -> Result<(), EngineError> {
-//         Ok(())
-//     }
-//
-//     fn add_listener(&mut self, _listener: Listener) {
-//         // TODO
-//     }
-//
-//     fn terraform_state_credentials(&self) -> &TerraformStateCredentials {
-//         unimplemented!()
-//     }
-//
-//     fn as_any(&self) -> &dyn Any {
-//         self
-//     }
-// }
+use std::any::Any;
+
+use crate::cloud_provider::{CloudProvider, Kind, TerraformStateCredentials};
+use crate::constants::{GOOGLE_CLOUD_PROJECT, GOOGLE_CREDENTIALS};
+use crate::error::{cast_simple_error_to_engine_error, EngineError, EngineErrorCause, SimpleError, SimpleErrorKind};
+use crate::models::{Context, Listen, Listener, Listeners};
+
+pub mod application;
+pub mod databases;
+pub mod job;
+pub mod kubernetes;
+pub mod router;
+
+pub struct GCP {
+    context: Context,
+    id: String,
+    organization_id: String,
+    name: String,
+    pub project_id: String,
+    // content of the JSON key of the service account used by Qovery
+    pub credentials_json: String,
+    // HMAC keys of the service account, to use GCS through its S3 compatible API
+    gcs_access_key_id: String,
+    gcs_secret_access_key: String,
+    terraform_state_credentials: TerraformStateCredentials,
+    listeners: Listeners,
+}
+
+impl GCP {
+    pub fn new(
+        context: Context,
+        id: &str,
+        organization_id: &str,
+        project_id: &str,
+        credentials_json: &str,
+        gcs_access_key_id: &str,
+        gcs_secret_access_key: &str,
+        name: &str,
+        terraform_state_credentials: TerraformStateCredentials,
+    ) -> Self {
+        GCP {
+            context,
+            id: id.to_string(),
+            organization_id: organization_id.to_string(),
+            name: name.to_string(),
+            project_id: project_id.to_string(),
+            credentials_json: credentials_json.to_string(),
+            gcs_access_key_id: gcs_access_key_id.to_string(),
+            gcs_secret_access_key: gcs_secret_access_key.to_string(),
+            terraform_state_credentials,
+            listeners: vec![],
+        }
+    }
+
+    /// write the service account key into the workspace, for the tools only accepting a file (terraform backend, gcloud)
+    pub fn credentials_file_path(&self) -> Result<String, EngineError> {
+        cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            write_credentials_file(&self.context, self.id.as_str(), self.credentials_json.as_str()),
+        )
+    }
+}
+
+pub fn write_credentials_file(context: &Context, id: &str, credentials_json: &str) -> Result<String, SimpleError> {
+    let workspace_dir = crate::fs::workspace_directory(
+        context.workspace_root_dir(),
+        context.execution_id(),
+        format!("gcp/{}", id),
+    );
+
+    let file_path = format!("{}/credentials.json", workspace_dir);
+
+    match std::fs::create_dir_all(workspace_dir.as_str())
+        .and_then(|_| std::fs::write(file_path.as_str(), credentials_json))
+    {
+        Ok(_) => Ok(file_path),
+        Err(e) => Err(SimpleError::new(
+            SimpleErrorKind::Other,
+            Some(format!("failed to write GCP credentials file {}: {:?}", file_path, e)),
+        )),
+    }
+}
+
+impl CloudProvider for GCP {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::Gcp
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn organization_id(&self) -> &str {
+        self.organization_id.as_str()
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn is_valid(&self) -> Result<(), EngineError> {
+        match serde_json::from_str::<serde_json::Value>(self.credentials_json.as_str()) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(self.engine_error(
+                EngineErrorCause::User(
+                    "Your GCP account seems to be no longer valid (bad Credentials). \
+                    Please contact your Organization administrator to fix or change the Credentials.",
+                ),
+                format!("failed to read the GCP service account key of {}", self.name_with_id()),
+            )),
+        }
+    }
+
+    fn credentials_environment_variables(&self) -> Vec<(&str, &str)> {
+        vec![
+            (GOOGLE_CLOUD_PROJECT, self.project_id.as_str()),
+            (GOOGLE_CREDENTIALS, self.credentials_json.as_str()),
+        ]
+    }
+
+    fn tera_context_environment_variables(&self) -> Vec<(&str, &str)> {
+        vec![("gcp_project_id", self.project_id.as_str())]
+    }
+
+    fn terraform_state_credentials(&self) -> &TerraformStateCredentials {
+        &self.terraform_state_credentials
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+impl Listen for GCP {
+    fn listeners(&self) -> &Listeners {
+        &self.listeners
+    }
+
+    fn add_listener(&mut self, listener: Listener) {
+        self.listeners.push(listener);
+    }
+}
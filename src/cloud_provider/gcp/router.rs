@@ -0,0 +1,561 @@
+use retry::delay::Fixed;
+use retry::OperationResult;
+use tera::Context as TeraContext;
+
+use crate::cloud_provider::environment::Kind;
+use crate::cloud_provider::models::{BasicAuthUser, CustomDomain, Route};
+use crate::cloud_provider::plan::ServicePlan;
+use crate::cloud_provider::service::{
+    apply_custom_domain_certificate_secrets, check_custom_domain_certificates, check_router_access_control,
+    check_router_routes, default_tera_context, delete_custom_domain_certificate_secrets, delete_stateless_service,
+    get_canary_weight_percent, plan_stateless_service, send_progress_on_long_task, Action, Create, Delete, Helm, Pause,
+    Plan, Service, ServiceType, StatelessService,
+};
+use crate::cloud_provider::utilities::{check_cname_for, sanitize_name};
+use crate::cloud_provider::DeploymentTarget;
+use crate::cmd::helm::Timeout;
+use crate::error::{
+    cast_simple_error_to_engine_error, EngineError, EngineErrorCause, EngineErrorScope, SimpleError, SimpleErrorKind,
+};
+use crate::models::{Context, Listen, Listener, Listeners};
+
+pub struct Router {
+    context: Context,
+    id: String,
+    action: Action,
+    name: String,
+    default_domain: String,
+    custom_domains: Vec<CustomDomain>,
+    routes: Vec<Route>,
+    whitelist_source_ranges: Vec<String>,
+    basic_auth_users: Vec<BasicAuthUser>,
+    listeners: Listeners,
+}
+
+impl Router {
+    pub fn new(
+        context: Context,
+        id: &str,
+        name: &str,
+        action: Action,
+        default_domain: &str,
+        custom_domains: Vec<CustomDomain>,
+        routes: Vec<Route>,
+        whitelist_source_ranges: Vec<String>,
+        basic_auth_users: Vec<BasicAuthUser>,
+        listeners: Listeners,
+    ) -> Self {
+        Router {
+            context,
+            id: id.to_string(),
+            name: name.to_string(),
+            action,
+            default_domain: default_domain.to_string(),
+            custom_domains,
+            routes,
+            whitelist_source_ranges,
+            basic_auth_users,
+            listeners,
+        }
+    }
+}
+
+impl Service for Router {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn service_type(&self) -> ServiceType {
+        ServiceType::Router
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn sanitized_name(&self) -> String {
+        sanitize_name("router", self.name())
+    }
+
+    fn version(&self) -> &str {
+        "1.0"
+    }
+
+    fn action(&self) -> &Action {
+        &self.action
+    }
+
+    fn private_port(&self) -> Option<u16> {
+        None
+    }
+
+    fn start_timeout(&self) -> Timeout<u32> {
+        Timeout::Default
+    }
+
+    fn total_cpus(&self) -> String {
+        "1".to_string()
+    }
+
+    fn cpu_burst(&self) -> String {
+        self.total_cpus()
+    }
+
+    fn total_ram_in_mib(&self) -> u32 {
+        1
+    }
+
+    fn total_instances(&self) -> u16 {
+        1
+    }
+
+    fn tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError> {
+        let (kubernetes, environment) = match target {
+            DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+            DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+        };
+
+        check_router_access_control(self, &self.whitelist_source_ranges, &self.basic_auth_users)?;
+        check_router_routes(self, &self.routes)?;
+
+        let mut context = default_tera_context(self, kubernetes, environment);
+        context.insert("gke_cluster_id", kubernetes.id());
+
+        let applications = environment
+            .stateless_services
+            .iter()
+            .filter(|x| x.service_type() == ServiceType::Application)
+            .collect::<Vec<_>>();
+
+        let custom_domain_data_templates = self
+            .custom_domains
+            .iter()
+            .map(|cd| cd.to_custom_domain_data_template())
+            .collect::<Vec<_>>();
+
+        let route_data_templates = self
+            .routes
+            .iter()
+            .map(|r| {
+                match applications
+                    .iter()
+                    .find(|app| app.name() == r.application_name.as_str())
+                {
+                    Some(application) => match application.private_port() {
+                        Some(private_port) => Some(r.to_route_data_template(
+                            application.sanitized_name().to_string(),
+                            private_port,
+                            get_canary_weight_percent(kubernetes, environment, application.as_ref()),
+                        )),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            })
+            .filter(|x| x.is_some())
+            .map(|x| x.unwrap())
+            .collect::<Vec<_>>();
+
+        // autoscaler
+        context.insert("nginx_enable_horizontal_autoscaler", "false");
+        context.insert("nginx_minimum_replicas", "1");
+        context.insert("nginx_maximum_replicas", "10");
+        // resources
+        context.insert("nginx_requests_cpu", "200m");
+        context.insert("nginx_requests_memory", "128Mi");
+        context.insert("nginx_limit_cpu", "200m");
+        context.insert("nginx_limit_memory", "128Mi");
+        match environment.kind {
+            Kind::Production => {
+                context.insert("nginx_enable_horizontal_autoscaler", "true");
+                context.insert("nginx_minimum_replicas", "2");
+                context.insert("nginx_requests_cpu", "250m");
+                context.insert("nginx_requests_memory", "384Mi");
+                context.insert("nginx_limit_cpu", "1");
+                context.insert("nginx_limit_memory", "384Mi");
+            }
+            _ => {}
+        };
+
+        let kubernetes_config_file_path = kubernetes.config_file_path();
+
+        match kubernetes_config_file_path {
+            Ok(kubernetes_config_file_path_string) => {
+                // Default domain
+                let external_ingress_hostname_default = crate::cmd::kubectl::do_kubectl_exec_get_external_ingress_ip(
+                    kubernetes_config_file_path_string.as_str(),
+                    "nginx-ingress",
+                    "app=nginx-ingress,component=controller",
+                    kubernetes.cloud_provider().credentials_environment_variables(),
+                );
+
+                match external_ingress_hostname_default {
+                    Ok(external_ingress_hostname_default) => match external_ingress_hostname_default {
+                        Some(hostname) => context.insert("external_ingress_hostname_default", hostname.as_str()),
+                        None => {
+                            return Err(self.engine_error(
+                                EngineErrorCause::Internal,
+                                "Error while trying to get Load Balancer IP from Kubernetes cluster".into(),
+                            ));
+                        }
+                    },
+                    _ => {
+                        error!("can't fetch external ingress ip");
+                    }
+                }
+
+                // Custom domain
+                if !self.custom_domains.is_empty() {
+                    let external_ingress_ip_selector =
+                        format!("app=nginx-ingress,component=controller,app_id={}", self.id());
+
+                    let deployed_ingress = match crate::cmd::kubectl::do_kubectl_exec_get_external_ingress_ip(
+                        kubernetes_config_file_path_string.as_str(),
+                        environment.namespace(),
+                        external_ingress_ip_selector.as_str(),
+                        kubernetes.cloud_provider().credentials_environment_variables(),
+                    ) {
+                        Ok(x) => x.is_some(),
+                        _ => false,
+                    };
+
+                    if deployed_ingress {
+                        // the IP of the GCP load balancer is directly exposed on the service, once provisioned
+                        let gcp_load_balancer_ip = retry::retry(Fixed::from_millis(5000).take(40), || {
+                            match crate::cmd::kubectl::do_kubectl_exec_get_external_ingress_ip(
+                                kubernetes_config_file_path_string.as_str(),
+                                environment.namespace(),
+                                external_ingress_ip_selector.as_str(),
+                                kubernetes.cloud_provider().credentials_environment_variables(),
+                            ) {
+                                Ok(Some(ip)) if !ip.is_empty() => {
+                                    info!("Got the IP {}", &ip);
+                                    OperationResult::Ok(ip)
+                                }
+                                _ => {
+                                    info!("Can't get Load Balancer IP, load balancer may be not ready yet or not yet deployed");
+                                    OperationResult::Retry(SimpleError::new(
+                                        SimpleErrorKind::Other,
+                                        Some("Can't get Load Balancer IP, load balancer may be not ready yet or not yet deployed"),
+                                    ))
+                                }
+                            }
+                        });
+
+                        match gcp_load_balancer_ip {
+                            Err(_) => {
+                                return Err(self.engine_error(
+                                    EngineErrorCause::Internal,
+                                    "Wasn't able to get load balancer info, stopping now as ingress rendering will fail".into(),
+                                ));
+                            }
+                            Ok(ip) => context.insert("gcp_lb_ingress_ip", &ip),
+                        }
+                    };
+                }
+            }
+            Err(_) => error!("can't fetch kubernetes config file - what's wrong? This must never happened"),
+        }
+
+        let router_default_domain_hash = crate::crypto::to_sha1_truncate_16(self.default_domain.as_str());
+
+        let tls_domain = format!("*.{}", kubernetes.dns_provider().domain());
+        context.insert("router_tls_domain", tls_domain.as_str());
+        context.insert("router_default_domain", self.default_domain.as_str());
+        context.insert("router_default_domain_hash", router_default_domain_hash.as_str());
+        context.insert("custom_domains", &custom_domain_data_templates);
+        context.insert("routes", &route_data_templates);
+        context.insert("whitelist_source_ranges", &self.whitelist_source_ranges);
+        // content of the htpasswd file of the basic auth secret, one line per user
+        context.insert(
+            "basic_auth_htpasswd",
+            &self
+                .basic_auth_users
+                .iter()
+                .map(|user| user.to_htpasswd(self.id()))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        );
+        context.insert("spec_acme_email", "tls@qovery.com");
+        context.insert("metadata_annotations_cert_manager_cluster_issuer", "letsencrypt-qovery");
+
+        let lets_encrypt_url = match self.context.is_test_cluster() {
+            true => "https://acme-staging-v02.api.letsencrypt.org/directory",
+            false => "https://acme-v02.api.letsencrypt.org/directory",
+        };
+        context.insert("spec_acme_server", lets_encrypt_url);
+
+        Ok(context)
+    }
+
+    fn selector(&self) -> String {
+        "app=nginx-ingress".to_string()
+    }
+
+    fn engine_error_scope(&self) -> EngineErrorScope {
+        EngineErrorScope::Router(self.id().to_string(), self.name().to_string())
+    }
+}
+
+impl crate::cloud_provider::service::Router for Router {
+    fn domains(&self) -> Vec<&str> {
+        let mut _domains = vec![self.default_domain.as_str()];
+
+        for domain in &self.custom_domains {
+            _domains.push(domain.domain.as_str());
+        }
+
+        _domains
+    }
+
+    fn routes_to(&self, application_name: &str) -> bool {
+        self.routes
+            .iter()
+            .any(|route| route.application_name == application_name)
+    }
+}
+
+impl Helm for Router {
+    fn helm_release_name(&self) -> String {
+        crate::string::cut(format!("router-{}", self.id()), 50)
+    }
+
+    fn helm_chart_dir(&self) -> String {
+        format!("{}/common/charts/nginx-ingress", self.context().lib_root_dir())
+    }
+
+    fn helm_chart_values_dir(&self) -> String {
+        format!("{}/gcp/chart_values/nginx-ingress", self.context.lib_root_dir())
+    }
+
+    fn helm_chart_external_name_service_dir(&self) -> String {
+        String::new()
+    }
+}
+
+impl Listen for Router {
+    fn listeners(&self) -> &Listeners {
+        &self.listeners
+    }
+
+    fn add_listener(&mut self, listener: Listener) {
+        self.listeners.push(listener);
+    }
+}
+
+impl StatelessService for Router {
+    fn as_router(&self) -> Option<&dyn crate::cloud_provider::service::Router> {
+        Some(self)
+    }
+}
+
+impl Create for Router {
+    fn on_create(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.router.on_create() called for {}", self.name());
+        let (kubernetes, environment) = match target {
+            DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+            DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+        };
+
+        let workspace_dir = self.workspace_directory();
+        let helm_release_name = self.helm_release_name();
+
+        let kubernetes_config_file_path = kubernetes.config_file_path()?;
+
+        let _ = check_custom_domain_certificates(target, self, &self.custom_domains)?;
+
+        // respect order - getting the context here and not before is mandatory
+        // the nginx-ingress must be available to get the external dns target if necessary
+        let mut context = self.tera_context(target)?;
+
+        // custom domain
+        if !self.custom_domains.is_empty() {
+            // custom domains? create an NGINX ingress
+            info!("setup NGINX ingress for custom domains");
+
+            let into_dir = crate::fs::workspace_directory(
+                self.context.workspace_root_dir(),
+                self.context.execution_id(),
+                "routers/nginx-ingress",
+            );
+
+            let _ = cast_simple_error_to_engine_error(
+                self.engine_error_scope(),
+                self.context.execution_id(),
+                crate::template::generate_and_copy_all_files_into_dir(
+                    self.helm_chart_values_dir(),
+                    into_dir.as_str(),
+                    &context,
+                ),
+            )?;
+
+            let _ = cast_simple_error_to_engine_error(
+                self.engine_error_scope(),
+                self.context.execution_id(),
+                crate::template::copy_non_template_files(self.helm_chart_dir(), into_dir.as_str()),
+            )?;
+
+            // do exec helm upgrade and return the last deployment status
+            let helm_history_row = cast_simple_error_to_engine_error(
+                self.engine_error_scope(),
+                self.context.execution_id(),
+                crate::cmd::helm::helm_exec_with_upgrade_history_with_override(
+                    kubernetes_config_file_path.as_str(),
+                    environment.namespace(),
+                    format!("custom-{}", helm_release_name).as_str(),
+                    into_dir.as_str(),
+                    format!("{}/nginx-ingress.yaml", into_dir.as_str()).as_str(),
+                    kubernetes.cloud_provider().credentials_environment_variables(),
+                ),
+            )?;
+
+            // check deployment status
+            if helm_history_row.is_none() || !helm_history_row.unwrap().is_successfully_deployed() {
+                return Err(self.engine_error(EngineErrorCause::Internal, "Router has failed to be deployed".into()));
+            }
+
+            // waiting for the load balancer, it should be deploy to get fqdn
+            let _ = retry::retry(Fixed::from_millis(3000).take(60), || {
+                let external_ingress_ip_custom = crate::cmd::kubectl::do_kubectl_exec_get_external_ingress_ip(
+                    kubernetes_config_file_path.as_str(),
+                    environment.namespace(),
+                    format!(
+                        "{},component=controller,release=custom-{}",
+                        self.selector(),
+                        helm_release_name
+                    )
+                    .as_str(),
+                    kubernetes.cloud_provider().credentials_environment_variables(),
+                );
+
+                match external_ingress_ip_custom {
+                    Ok(external_ingress_ip_custom) => OperationResult::Ok(external_ingress_ip_custom),
+                    Err(err) => {
+                        error!("Waiting GCP LoadBalancer endpoint to be available to be able to configure TLS");
+                        OperationResult::Retry(err)
+                    }
+                }
+            });
+        }
+
+        // re-run context to get get lb ip address to use it then in the ingress
+        context = self.tera_context(target)?;
+
+        let from_dir = format!("{}/gcp/charts/q-ingress-tls", self.context.lib_root_dir());
+        let _ = cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            crate::template::generate_and_copy_all_files_into_dir(from_dir.as_str(), workspace_dir.as_str(), &context),
+        )?;
+
+        let _ = apply_custom_domain_certificate_secrets(target, self, &self.custom_domains)?;
+
+        // do exec helm upgrade and return the last deployment status
+        let helm_history_row = cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            crate::cmd::helm::helm_exec_with_upgrade_history(
+                kubernetes_config_file_path.as_str(),
+                environment.namespace(),
+                helm_release_name.as_str(),
+                workspace_dir.as_str(),
+                Timeout::Default,
+                kubernetes.cloud_provider().credentials_environment_variables(),
+            ),
+        )?;
+
+        if helm_history_row.is_none() || !helm_history_row.unwrap().is_successfully_deployed() {
+            return Err(self.engine_error(EngineErrorCause::Internal, "Router has failed to be deployed".into()));
+        }
+
+        Ok(())
+    }
+
+    fn on_create_check(&self) -> Result<(), EngineError> {
+        use crate::cloud_provider::service::Router;
+
+        // check non custom domains
+        self.check_domains()?;
+
+        // Wait/Check that custom domain is a CNAME targeting qovery
+        for domain_to_check in self.custom_domains.iter() {
+            match check_cname_for(
+                self.progress_scope(),
+                self.listeners(),
+                &domain_to_check.domain,
+                self.context.execution_id(),
+            ) {
+                Ok(cname) if cname.trim_end_matches('.') == domain_to_check.target_domain.trim_end_matches('.') => {
+                    continue;
+                }
+                Ok(err) | Err(err) => {
+                    warn!(
+                        "Invalid CNAME for {}. Might not be an issue if user is using a CDN: {}",
+                        domain_to_check.domain, err
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn on_create_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.router.on_create_error() called for {}", self.name());
+
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Create, || {
+            delete_stateless_service(target, self, true)
+        })
+    }
+}
+
+impl Pause for Router {
+    fn on_pause(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.router.on_pause() called for {}, doing nothing", self.name());
+        Ok(())
+    }
+
+    fn on_pause_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_pause_error(&self, _target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.router.on_pause_error() called for {}, doing nothing", self.name());
+        Ok(())
+    }
+}
+
+impl Delete for Router {
+    fn on_delete(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        info!("GCP.router.on_delete() called for {}", self.name());
+
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Delete, || {
+            delete_stateless_service(target, self, false)
+                .and_then(|_| delete_custom_domain_certificate_secrets(target, self, &[]))
+        })
+    }
+
+    fn on_delete_check(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_delete_error(&self, target: &DeploymentTarget) -> Result<(), EngineError> {
+        warn!("GCP.router.on_delete_error() called for {}", self.name());
+
+        send_progress_on_long_task(self, crate::cloud_provider::service::Action::Delete, || {
+            delete_stateless_service(target, self, true)
+        })
+    }
+}
+
+impl Plan for Router {
+    fn on_plan(&self, target: &DeploymentTarget) -> Result<ServicePlan, EngineError> {
+        info!("GCP.router.on_plan() called for {}", self.name());
+
+        plan_stateless_service(target, self, None)
+    }
+}
@@ -626,4 +626,25 @@ mod tests {
         let final_succeed = get_latest_successful_deployment(&results).unwrap();
         assert_eq!(results[1].updated, final_succeed.updated);
     }
+
+    #[test]
+    fn test_last_succeeded_deployment_from_latest_revision() {
+        // helm_exec_history returns the rows from the latest revision to the oldest one
+        let payload = r#"
+        [
+            {"revision": 3, "updated": "2021-06-17T12:36:08+02:00", "status": "failed", "chart": "q-application-0.1.0", "app_version": "0.1"},
+            {"revision": 2, "updated": "2021-06-17T12:34:08+02:00", "status": "deployed", "chart": "q-application-0.1.0", "app_version": "0.1"},
+            {"revision": 1, "updated": "2021-06-17T08:37:37+02:00", "status": "superseded", "chart": "q-application-0.1.0", "app_version": "0.1"}
+        ]
+        "#;
+
+        let results = serde_json::from_str::<Vec<HelmHistoryRow>>(payload).unwrap();
+        assert_eq!(get_latest_successful_deployment(&results).unwrap().revision, 2);
+
+        let failed_only = serde_json::from_str::<Vec<HelmHistoryRow>>(
+            r#"[{"revision": 1, "updated": "2021-06-17T08:37:37+02:00", "status": "failed", "chart": "q-application-0.1.0", "app_version": "0.1"}]"#,
+        )
+        .unwrap();
+        assert!(get_latest_successful_deployment(&failed_only).is_err());
+    }
 }
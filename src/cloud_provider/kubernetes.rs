@@ -1,23 +1,27 @@
 use std::any::Any;
+use std::cmp::Ordering;
 use std::fs::File;
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
 use std::thread;
 
-use retry::delay::Fibonacci;
+use retry::delay::{Fibonacci, Fixed};
 use retry::Error::Operation;
 use retry::OperationResult;
 use serde::{Deserialize, Serialize};
 
-use crate::cloud_provider::environment::Environment;
+use crate::cloud_provider::environment::{Environment, PodResources};
+use crate::cloud_provider::plan::EnvironmentPlan;
 use crate::cloud_provider::service::CheckAction;
 use crate::cloud_provider::utilities::{get_version_number, VersionsNumber};
 use crate::cloud_provider::{service, CloudProvider, DeploymentTarget};
 use crate::cmd::kubectl;
 use crate::cmd::kubectl::{
-    kubectl_delete_objects_in_all_namespaces, kubectl_exec_count_all_objects, kubectl_exec_get_node,
-    kubectl_exec_version,
+    kubectl_delete_objects_in_all_namespaces, kubectl_exec_api_custom_metrics, kubectl_exec_count_all_objects,
+    kubectl_exec_get_all_namespaces, kubectl_exec_get_node, kubectl_exec_get_scheduled_pods, kubectl_exec_version,
 };
+use crate::cmd::structs::{HelmChart, KubernetesPod, KubernetesPodContainer, KubernetesPodSpec};
+use crate::deletion_utilities::{get_firsts_namespaces_to_delete, get_qovery_managed_namespaces};
 use crate::dns_provider::DnsProvider;
 use crate::error::SimpleErrorKind::Other;
 use crate::error::{
@@ -69,45 +73,30 @@ pub trait Kubernetes: Listen {
         let (path, _) = self.config_file()?;
         Ok(path)
     }
-    fn resources(&self, _environment: &Environment) -> Result<Resources, EngineError> {
+    /// bucket (from the config file store) where self-hosted database backups are uploaded
+    fn database_backups_bucket_name(&self) -> String {
+        format!("qovery-database-backups-{}", self.id())
+    }
+    /// free resources of the cluster to deploy the given environment. The requests of the pods
+    /// already running in the environment namespace are considered as free, since those pods are
+    /// replaced on redeploy.
+    fn resources(&self, environment: &Environment) -> Result<Resources, EngineError> {
         let kubernetes_config_file_path = self.config_file_path()?;
+        let envs = self.cloud_provider().credentials_environment_variables();
 
         let nodes = cast_simple_error_to_engine_error(
             self.engine_error_scope(),
             self.context().execution_id(),
-            crate::cmd::kubectl::kubectl_exec_get_node(
-                kubernetes_config_file_path,
-                self.cloud_provider().credentials_environment_variables(),
-            ),
+            kubectl_exec_get_node(kubernetes_config_file_path.as_str(), envs.clone()),
         )?;
 
-        let mut resources = Resources {
-            free_cpu: 0.0,
-            max_cpu: 0.0,
-            free_ram_in_mib: 0,
-            max_ram_in_mib: 0,
-            free_pods: 0,
-            max_pods: 0,
-            running_nodes: 0,
-        };
-
-        for node in nodes.items {
-            resources.free_cpu += cpu_string_to_float(node.status.allocatable.cpu);
-            resources.max_cpu += cpu_string_to_float(node.status.capacity.cpu);
-            resources.free_ram_in_mib += any_to_mi(node.status.allocatable.memory);
-            resources.max_ram_in_mib += any_to_mi(node.status.capacity.memory);
-            resources.free_pods = match node.status.allocatable.pods.parse::<u16>() {
-                Ok(v) => v,
-                _ => 0,
-            };
-            resources.max_pods = match node.status.capacity.pods.parse::<u16>() {
-                Ok(v) => v,
-                _ => 0,
-            };
-            resources.running_nodes += 1;
-        }
+        let pods = cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context().execution_id(),
+            kubectl_exec_get_scheduled_pods(kubernetes_config_file_path.as_str(), envs),
+        )?;
 
-        Ok(resources)
+        Ok(compute_resources(nodes.items, pods.items, environment.namespace()))
     }
     fn on_create(&self) -> Result<(), EngineError>;
     fn on_create_error(&self) -> Result<(), EngineError>;
@@ -125,6 +114,10 @@ pub trait Kubernetes: Listen {
     fn pause_environment_error(&self, environment: &Environment) -> Result<(), EngineError>;
     fn delete_environment(&self, environment: &Environment) -> Result<(), EngineError>;
     fn delete_environment_error(&self, environment: &Environment) -> Result<(), EngineError>;
+    fn backup_environment(&self, environment: &Environment) -> Result<(), EngineError>;
+    fn restore_environment(&self, environment: &Environment) -> Result<(), EngineError>;
+    fn clone_environment(&self, environment: &Environment) -> Result<(), EngineError>;
+    fn plan_environment(&self, environment: &Environment) -> Result<EnvironmentPlan, EngineError>;
     fn engine_error_scope(&self) -> EngineErrorScope {
         EngineErrorScope::Kubernetes(self.id().to_string(), self.name().to_string())
     }
@@ -148,6 +141,7 @@ pub trait KubernetesNode {
 pub enum Kind {
     Eks,
     Doks,
+    Gke,
 }
 
 #[derive(Debug)]
@@ -159,6 +153,136 @@ pub struct Resources {
     pub free_pods: u16,
     pub max_pods: u16,
     pub running_nodes: u16,
+    pub nodes: Vec<NodeResources>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeResources {
+    pub name: String,
+    pub free_cpu: f32,
+    pub free_ram_in_mib: u32,
+    pub free_pods: u16,
+}
+
+/// compute the free resources of each schedulable node: what is allocatable on the node minus
+/// what is requested by the pods scheduled on it. Pods of the `replaced_namespace` are ignored.
+fn compute_resources(
+    nodes: Vec<crate::cmd::structs::KubernetesNode>,
+    pods: Vec<KubernetesPod>,
+    replaced_namespace: &str,
+) -> Resources {
+    let mut resources = Resources {
+        free_cpu: 0.0,
+        max_cpu: 0.0,
+        free_ram_in_mib: 0,
+        max_ram_in_mib: 0,
+        free_pods: 0,
+        max_pods: 0,
+        running_nodes: 0,
+        nodes: vec![],
+    };
+
+    for node in nodes {
+        // cordoned nodes (e.g. being drained during an upgrade) won't receive any new pod
+        if node.spec.as_ref().map(|spec| spec.unschedulable).unwrap_or(false) {
+            continue;
+        }
+
+        let node_name = node.metadata.name;
+        let scheduled_pods = pods.iter().filter(|pod| {
+            pod.metadata.namespace.as_deref() != Some(replaced_namespace)
+                && pod.spec.as_ref().and_then(|spec| spec.node_name.as_deref()) == Some(node_name.as_str())
+        });
+
+        let mut node_resources = NodeResources {
+            name: node_name.clone(),
+            free_cpu: cpu_string_to_float(node.status.allocatable.cpu),
+            free_ram_in_mib: any_to_mi(node.status.allocatable.memory),
+            free_pods: node.status.allocatable.pods.parse::<u16>().unwrap_or(0),
+        };
+
+        for pod in scheduled_pods {
+            let requests = pod_requested_resources(pod.spec.as_ref().unwrap());
+            node_resources.free_cpu = (node_resources.free_cpu - requests.cpu).max(0.0);
+            node_resources.free_ram_in_mib = node_resources.free_ram_in_mib.saturating_sub(requests.ram_in_mib);
+            node_resources.free_pods = node_resources.free_pods.saturating_sub(1);
+        }
+
+        resources.free_cpu += node_resources.free_cpu;
+        resources.max_cpu += cpu_string_to_float(node.status.capacity.cpu);
+        resources.free_ram_in_mib += node_resources.free_ram_in_mib;
+        resources.max_ram_in_mib += any_to_mi(node.status.capacity.memory);
+        resources.free_pods += node_resources.free_pods;
+        resources.max_pods += node.status.capacity.pods.parse::<u16>().unwrap_or(0);
+        resources.running_nodes += 1;
+        resources.nodes.push(node_resources);
+    }
+
+    resources
+}
+
+/// resources requested by a pod: init containers run one after the other before the containers,
+/// so the pod requests the highest value between the biggest init container and the sum of its containers
+fn pod_requested_resources(spec: &KubernetesPodSpec) -> PodResources {
+    let container_requests = |container: &KubernetesPodContainer| match &container.resources.requests {
+        Some(requests) => PodResources {
+            cpu: requests.cpu.as_ref().map(cpu_string_to_float).unwrap_or(0.0),
+            ram_in_mib: requests.memory.as_ref().map(any_to_mi).unwrap_or(0),
+        },
+        None => PodResources {
+            cpu: 0.0,
+            ram_in_mib: 0,
+        },
+    };
+
+    let mut requests = PodResources {
+        cpu: 0.0,
+        ram_in_mib: 0,
+    };
+
+    for container in &spec.containers {
+        let container = container_requests(container);
+        requests.cpu += container.cpu;
+        requests.ram_in_mib += container.ram_in_mib;
+    }
+
+    for container in &spec.init_containers {
+        let init_container = container_requests(container);
+        requests.cpu = requests.cpu.max(init_container.cpu);
+        requests.ram_in_mib = requests.ram_in_mib.max(init_container.ram_in_mib);
+    }
+
+    requests
+}
+
+/// place the pods on the nodes the way the scheduler would roughly do it: biggest pods first,
+/// each one on the first node with enough free resources (first fit decreasing).
+/// Returns the first pod which does not fit on any node.
+fn find_unschedulable_pod(nodes: &[NodeResources], pods: &[PodResources]) -> Option<PodResources> {
+    let mut nodes = nodes.to_vec();
+    let mut pods = pods.to_vec();
+    pods.sort_by(|a, b| {
+        b.ram_in_mib
+            .cmp(&a.ram_in_mib)
+            .then(b.cpu.partial_cmp(&a.cpu).unwrap_or(Ordering::Equal))
+    });
+
+    for pod in pods {
+        let node = nodes
+            .iter_mut()
+            .find(|node| node.free_pods > 0 && node.free_cpu >= pod.cpu && node.free_ram_in_mib >= pod.ram_in_mib);
+
+        match node {
+            Some(node) => {
+                node.free_cpu -= pod.cpu;
+                node.free_ram_in_mib -= pod.ram_in_mib;
+                node.free_pods -= 1;
+            }
+            None => return Some(pod),
+        }
+    }
+
+    None
 }
 
 /// common function to deploy a complete environment through Kubernetes and the different
@@ -177,6 +301,8 @@ pub fn deploy_environment(kubernetes: &dyn Kubernetes, environment: &Environment
         },
         // FIXME: We don't have any managed service on DO for now
         Kind::Doks => DeploymentTarget::SelfHosted(kubernetes, environment),
+        // FIXME: We don't have any managed service on GCP for now
+        Kind::Gke => DeploymentTarget::SelfHosted(kubernetes, environment),
     };
 
     // do not deploy if there is not enough resources
@@ -476,6 +602,227 @@ pub fn delete_environment(kubernetes: &dyn Kubernetes, environment: &Environment
     Ok(())
 }
 
+/// common kubernetes function to backup all the databases and the application storage of an environment
+pub fn backup_environment(kubernetes: &dyn Kubernetes, environment: &Environment) -> Result<(), EngineError> {
+    let listeners_helper = ListenersHelper::new(kubernetes.listeners());
+    let stateful_deployment_target = stateful_deployment_target(kubernetes, environment);
+
+    for service in &environment.stateful_services {
+        let result = service::check_kubernetes_service_error(
+            service.on_backup(&stateful_deployment_target),
+            kubernetes,
+            service,
+            &stateful_deployment_target,
+            &listeners_helper,
+            "backup",
+            CheckAction::Deploy,
+        );
+
+        if let Err(err) = result {
+            // clean up what has been started, the error is the one returned to the caller
+            let _ = service.on_backup_error(&stateful_deployment_target);
+            return Err(err);
+        }
+
+        let _ = service::check_kubernetes_service_error(
+            service.on_backup_check(),
+            kubernetes,
+            service,
+            &stateful_deployment_target,
+            &listeners_helper,
+            "check backup",
+            CheckAction::Deploy,
+        )?;
+    }
+
+    // stateless services are deployed on kubernetes, that's why we choose the deployment target SelfHosted.
+    let stateless_deployment_target = DeploymentTarget::SelfHosted(kubernetes, environment);
+
+    for service in &environment.stateless_services {
+        let backup = match service.as_backup() {
+            Some(backup) => backup,
+            None => continue,
+        };
+
+        let result = service::check_kubernetes_service_error(
+            backup.on_backup(&stateless_deployment_target),
+            kubernetes,
+            service,
+            &stateless_deployment_target,
+            &listeners_helper,
+            "backup",
+            CheckAction::Deploy,
+        );
+
+        if let Err(err) = result {
+            let _ = backup.on_backup_error(&stateless_deployment_target);
+            return Err(err);
+        }
+
+        let _ = service::check_kubernetes_service_error(
+            backup.on_backup_check(),
+            kubernetes,
+            service,
+            &stateless_deployment_target,
+            &listeners_helper,
+            "check backup",
+            CheckAction::Deploy,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// common kubernetes function to restore the databases and the application storage of an environment which have a restore source
+pub fn restore_environment(kubernetes: &dyn Kubernetes, environment: &Environment) -> Result<(), EngineError> {
+    let listeners_helper = ListenersHelper::new(kubernetes.listeners());
+    let stateful_deployment_target = stateful_deployment_target(kubernetes, environment);
+
+    for service in &environment.stateful_services {
+        let result = service::check_kubernetes_service_error(
+            service.on_restore(&stateful_deployment_target),
+            kubernetes,
+            service,
+            &stateful_deployment_target,
+            &listeners_helper,
+            "restore",
+            CheckAction::Deploy,
+        );
+
+        if let Err(err) = result {
+            let _ = service.on_restore_error(&stateful_deployment_target);
+            return Err(err);
+        }
+
+        let _ = service::check_kubernetes_service_error(
+            service.on_restore_check(),
+            kubernetes,
+            service,
+            &stateful_deployment_target,
+            &listeners_helper,
+            "check restore",
+            CheckAction::Deploy,
+        )?;
+    }
+
+    // stateless services are deployed on kubernetes, that's why we choose the deployment target SelfHosted.
+    let stateless_deployment_target = DeploymentTarget::SelfHosted(kubernetes, environment);
+
+    for service in &environment.stateless_services {
+        let backup = match service.as_backup() {
+            Some(backup) => backup,
+            None => continue,
+        };
+
+        let result = service::check_kubernetes_service_error(
+            backup.on_restore(&stateless_deployment_target),
+            kubernetes,
+            service,
+            &stateless_deployment_target,
+            &listeners_helper,
+            "restore",
+            CheckAction::Deploy,
+        );
+
+        if let Err(err) = result {
+            let _ = backup.on_restore_error(&stateless_deployment_target);
+            return Err(err);
+        }
+
+        let _ = service::check_kubernetes_service_error(
+            backup.on_restore_check(),
+            kubernetes,
+            service,
+            &stateless_deployment_target,
+            &listeners_helper,
+            "check restore",
+            CheckAction::Deploy,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// common kubernetes function to create the databases of an environment from the ones of the environment it is cloned from
+pub fn clone_environment(kubernetes: &dyn Kubernetes, environment: &Environment) -> Result<(), EngineError> {
+    let listeners_helper = ListenersHelper::new(kubernetes.listeners());
+    let stateful_deployment_target = stateful_deployment_target(kubernetes, environment);
+
+    for service in &environment.stateful_services {
+        let result = service::check_kubernetes_service_error(
+            service.on_clone(&stateful_deployment_target),
+            kubernetes,
+            service,
+            &stateful_deployment_target,
+            &listeners_helper,
+            "clone",
+            CheckAction::Deploy,
+        );
+
+        if let Err(err) = result {
+            let _ = service.on_clone_error(&stateful_deployment_target);
+            return Err(err);
+        }
+
+        let _ = service::check_kubernetes_service_error(
+            service.on_clone_check(),
+            kubernetes,
+            service,
+            &stateful_deployment_target,
+            &listeners_helper,
+            "check clone",
+            CheckAction::Deploy,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// common function to plan the changes of a complete environment, service by service,
+/// without deploying anything
+pub fn plan_environment(
+    kubernetes: &dyn Kubernetes,
+    environment: &Environment,
+) -> Result<EnvironmentPlan, EngineError> {
+    let stateful_deployment_target = stateful_deployment_target(kubernetes, environment);
+    let stateless_deployment_target = DeploymentTarget::SelfHosted(kubernetes, environment);
+
+    let mut services = Vec::with_capacity(environment.stateful_services.len() + environment.stateless_services.len());
+
+    for service in &environment.stateful_services {
+        services.push(service.on_plan(&stateful_deployment_target)?);
+    }
+
+    for service in &environment.stateless_services {
+        services.push(service.on_plan(&stateless_deployment_target)?);
+    }
+
+    Ok(EnvironmentPlan {
+        environment_id: environment.id.clone(),
+        services,
+    })
+}
+
+fn stateful_deployment_target<'a>(
+    kubernetes: &'a dyn Kubernetes,
+    environment: &'a Environment,
+) -> DeploymentTarget<'a> {
+    match kubernetes.kind() {
+        Kind::Eks => match environment.kind {
+            crate::cloud_provider::environment::Kind::Production => {
+                DeploymentTarget::ManagedServices(kubernetes, environment)
+            }
+            crate::cloud_provider::environment::Kind::Development => {
+                DeploymentTarget::SelfHosted(kubernetes, environment)
+            }
+        },
+        // FIXME: We don't have any managed service on DO for now
+        Kind::Doks => DeploymentTarget::SelfHosted(kubernetes, environment),
+        // FIXME: We don't have any managed service on GCP for now
+        Kind::Gke => DeploymentTarget::SelfHosted(kubernetes, environment),
+    }
+}
+
 /// check that there is enough CPU and RAM, and pods resources
 /// before starting to deploy stateful and stateless services
 pub fn check_kubernetes_has_enough_resources_to_deploy_environment(
@@ -541,6 +888,262 @@ pub fn check_kubernetes_has_enough_resources_to_deploy_environment(
         return Err(kubernetes.engine_error(cause, message));
     }
 
+    // the cluster may have enough resources in total, but too fragmented across the nodes to run every pod
+    if let Some(pod) = find_unschedulable_pod(&resources.nodes, &environment.required_pods()) {
+        let message = format!(
+            "There is no node on the Kubernetes cluster '{}' with enough free resources to run a pod \
+                requesting {} CPU and {}mib RAM. {} CPU and {}mib RAM are available but spread over {} nodes. \
+                Consider to upgrade your nodes configuration.",
+            kubernetes.name(),
+            pod.cpu,
+            pod.ram_in_mib,
+            resources.free_cpu,
+            resources.free_ram_in_mib,
+            resources.running_nodes,
+        );
+
+        return Err(kubernetes.engine_error(cause, message));
+    }
+
+    Ok(())
+}
+
+/// wait (up to 1h) for the engine running on the cluster to have no running job,
+/// to avoid getting unreleased locks (from helm or terraform for example) when removing the worker nodes
+pub fn wait_for_engine_jobs_to_finish(kubernetes: &dyn Kubernetes) -> Result<(), EngineError> {
+    let kubernetes_config_file_path = kubernetes.config_file_path()?;
+
+    let metric_name = "taskmanager_nb_running_tasks";
+    let wait_engine_job_finish = retry::retry(Fixed::from_millis(60000).take(60), || {
+        return match kubectl_exec_api_custom_metrics(
+            &kubernetes_config_file_path,
+            kubernetes.cloud_provider().credentials_environment_variables(),
+            "qovery",
+            None,
+            metric_name,
+        ) {
+            Ok(metrics) => {
+                let mut current_engine_jobs = 0;
+
+                for metric in metrics.items {
+                    match metric.value.parse::<i32>() {
+                        Ok(job_count) if job_count > 0 => current_engine_jobs += 1,
+                        Err(e) => {
+                            error!("error while looking at the API metric value {}. {:?}", metric_name, e);
+                            return OperationResult::Retry(SimpleError {
+                                kind: SimpleErrorKind::Other,
+                                message: Some(e.to_string()),
+                            });
+                        }
+                        _ => {}
+                    }
+                }
+
+                if current_engine_jobs == 0 {
+                    OperationResult::Ok(())
+                } else {
+                    OperationResult::Retry(SimpleError {
+                        kind: SimpleErrorKind::Other,
+                        message: Some(
+                            "can't pause the infrastructure now, Engine jobs are currently running, retrying later..."
+                                .to_string(),
+                        ),
+                    })
+                }
+            }
+            Err(e) => {
+                error!("error while looking at the API metric value {}. {:?}", metric_name, e);
+                OperationResult::Retry(e)
+            }
+        };
+    });
+
+    match wait_engine_job_finish {
+        Ok(_) => info!("no current running jobs on the Engine, infrastructure pause is allowed to start"),
+        Err(Operation { error, .. }) => {
+            return Err(EngineError {
+                cause: EngineErrorCause::Internal,
+                scope: EngineErrorScope::Engine,
+                execution_id: kubernetes.context().execution_id().to_string(),
+                message: error.message,
+            })
+        }
+        Err(retry::Error::Internal(msg)) => {
+            return Err(EngineError::new(
+                EngineErrorCause::Internal,
+                EngineErrorScope::Engine,
+                kubernetes.context().execution_id(),
+                Some(msg),
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+/// delete everything deployed on the cluster: the non-Qovery namespaces first, then the Qovery managed charts
+/// and namespaces, and finally all the remaining charts
+pub fn delete_all_deployments(kubernetes: &dyn Kubernetes, send_to_customer: &dyn Fn(&str)) -> Result<(), EngineError> {
+    let kubernetes_config_file_path = kubernetes.config_file_path()?;
+
+    let all_namespaces = kubectl_exec_get_all_namespaces(
+        &kubernetes_config_file_path,
+        kubernetes.cloud_provider().credentials_environment_variables(),
+    );
+
+    // should make the diff between all namespaces and qovery managed namespaces
+    let message = format!(
+        "Deleting all non-Qovery deployed applications and dependencies for cluster {}/{}",
+        kubernetes.name(),
+        kubernetes.id()
+    );
+    info!("{}", &message);
+    send_to_customer(&message);
+
+    match all_namespaces {
+        Ok(namespace_vec) => {
+            let namespaces_as_str = namespace_vec.iter().map(std::ops::Deref::deref).collect();
+            let namespaces_to_delete = get_firsts_namespaces_to_delete(namespaces_as_str);
+
+            info!("Deleting non Qovery namespaces");
+            for namespace_to_delete in namespaces_to_delete.iter() {
+                info!("Starting namespace {} deletion process", namespace_to_delete);
+                let deletion = crate::cmd::kubectl::kubectl_exec_delete_namespace(
+                    &kubernetes_config_file_path,
+                    namespace_to_delete,
+                    kubernetes.cloud_provider().credentials_environment_variables(),
+                );
+
+                match deletion {
+                    Ok(_) => info!("Namespace {} is deleted", namespace_to_delete),
+                    Err(e) => {
+                        if e.message.is_some() && e.message.unwrap().contains("not found") {
+                            {}
+                        } else {
+                            error!("Can't delete the namespace {}", namespace_to_delete);
+                        }
+                    }
+                }
+            }
+        }
+
+        Err(e) => error!(
+            "Error while getting all namespaces for Kubernetes cluster {}: error {:?}",
+            kubernetes.name_with_id(),
+            e.message
+        ),
+    }
+
+    let message = format!(
+        "Deleting all Qovery deployed elements and associated dependencies for cluster {}/{}",
+        kubernetes.name(),
+        kubernetes.id()
+    );
+    info!("{}", &message);
+    send_to_customer(&message);
+
+    // delete custom metrics api to avoid stale namespaces on deletion
+    let _ = crate::cmd::helm::helm_uninstall_list(
+        &kubernetes_config_file_path,
+        vec![HelmChart {
+            name: "metrics-server".to_string(),
+            namespace: "kube-system".to_string(),
+        }],
+        kubernetes.cloud_provider().credentials_environment_variables(),
+    );
+
+    // required to avoid namespace stuck on deletion
+    match uninstall_cert_manager(
+        &kubernetes_config_file_path,
+        kubernetes.cloud_provider().credentials_environment_variables(),
+    ) {
+        Ok(_) => {}
+        Err(e) => {
+            return Err(EngineError::new(
+                EngineErrorCause::Internal,
+                kubernetes.engine_error_scope(),
+                kubernetes.context().execution_id(),
+                e.message,
+            ))
+        }
+    };
+
+    info!("Deleting Qovery managed helm charts");
+    let qovery_namespaces = get_qovery_managed_namespaces();
+    for qovery_namespace in qovery_namespaces.iter() {
+        info!(
+            "Starting Qovery managed charts deletion process in {} namespace",
+            qovery_namespace
+        );
+        let charts_to_delete = crate::cmd::helm::helm_list(
+            &kubernetes_config_file_path,
+            kubernetes.cloud_provider().credentials_environment_variables(),
+            Some(qovery_namespace),
+        );
+        match charts_to_delete {
+            Ok(charts) => {
+                for chart in charts {
+                    info!("Deleting chart {} in {} namespace", chart.name, chart.namespace);
+                    match crate::cmd::helm::helm_exec_uninstall(
+                        &kubernetes_config_file_path,
+                        &chart.namespace,
+                        &chart.name,
+                        kubernetes.cloud_provider().credentials_environment_variables(),
+                    ) {
+                        Ok(_) => info!("chart {} deleted", chart.name),
+                        Err(e) => error!("{:?}", e),
+                    }
+                }
+            }
+            Err(e) => {
+                if e.message.is_some() && e.message.unwrap().contains("not found") {
+                    {}
+                } else {
+                    error!("Can't delete the namespace {}", qovery_namespace);
+                }
+            }
+        }
+    }
+
+    info!("Deleting Qovery managed Namespaces");
+    for qovery_namespace in qovery_namespaces.iter() {
+        info!("Starting namespace {} deletion process", qovery_namespace);
+        let deletion = crate::cmd::kubectl::kubectl_exec_delete_namespace(
+            &kubernetes_config_file_path,
+            qovery_namespace,
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        );
+        match deletion {
+            Ok(_) => info!("Namespace {} is fully deleted", qovery_namespace),
+            Err(e) => {
+                if e.message.is_some() && e.message.unwrap().contains("not found") {
+                    {}
+                } else {
+                    error!("Can't delete the namespace {}", qovery_namespace);
+                }
+            }
+        }
+    }
+
+    info!("Delete all remaining deployed helm applications");
+    match crate::cmd::helm::helm_list(
+        &kubernetes_config_file_path,
+        kubernetes.cloud_provider().credentials_environment_variables(),
+        None,
+    ) {
+        Ok(helm_charts) => {
+            for chart in helm_charts {
+                info!("Deleting chart {} in progress...", chart.name);
+                let _ = crate::cmd::helm::helm_uninstall_list(
+                    &kubernetes_config_file_path,
+                    vec![chart],
+                    kubernetes.cloud_provider().credentials_environment_variables(),
+                );
+            }
+        }
+        Err(_) => error!("Unable to get helm list"),
+    }
+
     Ok(())
 }
 
@@ -671,6 +1274,29 @@ where
     check_kubernetes_upgrade_status(requested_version, deployed_masters_version, deployed_workers_version)
 }
 
+/// Returns the upgrade to run instead of the standard cluster deployment, if any.
+/// The standard deployment goes on when the deployed versions can't be determined.
+pub fn required_upgrade_on_create(
+    upgrade_status: Result<KubernetesUpgradeStatus, SimpleError>,
+) -> Option<KubernetesUpgradeStatus> {
+    match upgrade_status {
+        Ok(x) => {
+            if x.required_upgrade_on.is_some() {
+                return Some(x);
+            }
+            info!("Kubernetes cluster upgrade not required");
+            None
+        }
+        Err(e) => {
+            error!(
+                "Error detected, upgrade won't occurs, but standard deployment. {:?}",
+                e.message
+            );
+            None
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub enum KubernetesNodesType {
     Masters,
@@ -866,11 +1492,14 @@ pub fn compare_kubernetes_cluster_versions_for_upgrade(
 
 #[cfg(test)]
 mod tests {
+    use crate::cloud_provider::environment::PodResources;
     use crate::cloud_provider::kubernetes::{
-        check_kubernetes_upgrade_status, compare_kubernetes_cluster_versions_for_upgrade, KubernetesNodesType,
+        check_kubernetes_upgrade_status, compare_kubernetes_cluster_versions_for_upgrade, find_unschedulable_pod,
+        required_upgrade_on_create, KubernetesNodesType, NodeResources,
     };
     use crate::cloud_provider::utilities::{get_version_number, VersionsNumber};
     use crate::cmd::structs::{KubernetesList, KubernetesNode, KubernetesVersion};
+    use crate::error::{SimpleError, SimpleErrorKind};
 
     #[test]
     pub fn check_kubernetes_upgrade_method() {
@@ -925,6 +1554,36 @@ mod tests {
         assert_eq!(result.older_workers_version_detected, false); // not true because we're in an upgrade process
     }
 
+    #[test]
+    pub fn check_required_upgrade_on_create() {
+        let version_1_17 = VersionsNumber {
+            major: "1".to_string(),
+            minor: Some("17".to_string()),
+            patch: None,
+        };
+        let version_1_18 = VersionsNumber {
+            major: "1".to_string(),
+            minor: Some("18".to_string()),
+            patch: None,
+        };
+
+        // the cluster is upgraded instead of being deployed when the requested version is newer
+        let status = check_kubernetes_upgrade_status("1.18", version_1_17.clone(), vec![version_1_17.clone()]);
+        let upgrade = required_upgrade_on_create(status).unwrap();
+        assert_eq!(upgrade.required_upgrade_on.unwrap(), KubernetesNodesType::Masters);
+
+        let status = check_kubernetes_upgrade_status("1.18", version_1_18.clone(), vec![version_1_17.clone()]);
+        let upgrade = required_upgrade_on_create(status).unwrap();
+        assert_eq!(upgrade.required_upgrade_on.unwrap(), KubernetesNodesType::Workers);
+
+        // standard deployment when up to date or when the deployed versions are unknown
+        let status = check_kubernetes_upgrade_status("1.18", version_1_18.clone(), vec![version_1_18.clone()]);
+        assert!(required_upgrade_on_create(status).is_none());
+        assert!(
+            required_upgrade_on_create(Err(SimpleError::new(SimpleErrorKind::Other, Some("unreachable")))).is_none()
+        );
+    }
+
     #[allow(dead_code)]
     pub fn print_kubernetes_version(provider_version: &VersionsNumber, provider: &VersionsNumber) {
         println!(
@@ -1420,4 +2079,39 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    pub fn check_pods_fit_on_nodes() {
+        let node = |name: &str, free_cpu: f32, free_ram_in_mib: u32, free_pods: u16| NodeResources {
+            name: name.to_string(),
+            free_cpu,
+            free_ram_in_mib,
+            free_pods,
+        };
+        let pod = |cpu: f32, ram_in_mib: u32| PodResources { cpu, ram_in_mib };
+
+        let nodes = vec![node("node-1", 1.0, 1024, 10), node("node-2", 2.0, 2048, 10)];
+
+        // enough resources in total and on each node
+        assert_eq!(
+            find_unschedulable_pod(&nodes, &[pod(0.5, 512), pod(1.5, 1536), pod(0.5, 512)]),
+            None
+        );
+
+        // enough resources in total, but no node is big enough for the biggest pod
+        assert_eq!(
+            find_unschedulable_pod(&nodes, &[pod(0.5, 512), pod(2.5, 1024)]),
+            Some(pod(2.5, 1024))
+        );
+
+        // placing the smallest pod first on node-2 would leave no room for the biggest one
+        assert_eq!(find_unschedulable_pod(&nodes, &[pod(1.0, 1024), pod(2.0, 2048)]), None);
+
+        // not enough pods slots
+        let nodes = vec![node("node-1", 4.0, 4096, 1)];
+        assert_eq!(
+            find_unschedulable_pod(&nodes, &[pod(0.1, 128), pod(0.1, 128)]),
+            Some(pod(0.1, 128))
+        );
+    }
 }
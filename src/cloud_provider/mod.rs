@@ -15,6 +15,7 @@ pub mod helm;
 pub mod kubernetes;
 pub mod metrics;
 pub mod models;
+pub mod plan;
 pub mod qovery;
 pub mod service;
 pub mod utilities;
@@ -53,6 +54,7 @@ pub trait CloudProvider: Listen {
 pub enum Kind {
     Aws,
     Do,
+    Gcp,
 }
 
 impl Kind {
@@ -60,6 +62,7 @@ impl Kind {
         match self {
             Kind::Aws => "AWS",
             Kind::Do => "Digital Ocean",
+            Kind::Gcp => "GCP",
         }
     }
 }
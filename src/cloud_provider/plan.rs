@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+
+/// what would be changed on an environment by committing a transaction, service by service
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct EnvironmentPlan {
+    pub environment_id: String,
+    pub services: Vec<ServicePlan>,
+}
+
+impl EnvironmentPlan {
+    pub fn has_changes(&self) -> bool {
+        self.services.iter().any(|service| service.has_changes())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct ServicePlan {
+    pub id: String,
+    pub name: String,
+    pub service_type: String,
+    pub resources: Vec<ResourceChange>,
+    pub image: Option<ImageChange>,
+    // raw output of `helm diff` or `terraform plan`, to be reviewed as is
+    pub diff: Vec<String>,
+}
+
+impl ServicePlan {
+    pub fn has_changes(&self) -> bool {
+        !self.resources.is_empty() || self.image.is_some()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct ResourceChange {
+    pub kind: ResourceChangeKind,
+    // kind and name of a Kubernetes object (e.g. Deployment/app-name) or a Terraform resource address
+    pub resource: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ResourceChangeKind {
+    Created,
+    Changed,
+    // destroyed then created again, the data it holds is lost
+    Replaced,
+    Deleted,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Eq, PartialEq)]
+pub struct ImageChange {
+    // none if the service is not deployed yet
+    pub from: Option<String>,
+    pub to: String,
+}
+
+/// read the resource changes from a `helm diff upgrade --no-color` output,
+/// where each changed object has a header like `namespace, name, Kind (group) has changed:`
+pub fn resource_changes_from_helm_diff(lines: &[String]) -> Vec<ResourceChange> {
+    lines
+        .iter()
+        .filter_map(|line| {
+            let (header, kind) = if let Some(header) = line.strip_suffix(" has been added:") {
+                (header, ResourceChangeKind::Created)
+            } else if let Some(header) = line.strip_suffix(" has changed:") {
+                (header, ResourceChangeKind::Changed)
+            } else if let Some(header) = line.strip_suffix(" has been removed:") {
+                (header, ResourceChangeKind::Deleted)
+            } else {
+                return None;
+            };
+
+            let mut fields = header.splitn(3, ", ");
+            let _namespace = fields.next()?;
+            let name = fields.next()?;
+            // drop the api group: "Deployment (apps)" becomes "Deployment"
+            let object_kind = fields.next()?.split(" (").next()?;
+
+            Some(ResourceChange {
+                kind,
+                resource: format!("{}/{}", object_kind, name),
+            })
+        })
+        .collect()
+}
+
+/// read the resource changes from a `terraform plan -no-color` output,
+/// where each changed resource has a comment like `# aws_db_instance.postgresql_instance will be created`
+pub fn resource_changes_from_terraform_plan(lines: &[String]) -> Vec<ResourceChange> {
+    lines
+        .iter()
+        .filter_map(|line| {
+            let comment = line.trim().strip_prefix("# ")?;
+
+            let (resource, kind) = if let Some(resource) = comment.strip_suffix(" will be created") {
+                (resource, ResourceChangeKind::Created)
+            } else if let Some(resource) = comment.strip_suffix(" will be updated in-place") {
+                (resource, ResourceChangeKind::Changed)
+            } else if let Some(resource) = comment.strip_suffix(" must be replaced") {
+                (resource, ResourceChangeKind::Replaced)
+            } else if let Some(resource) = comment.strip_suffix(" will be destroyed") {
+                (resource, ResourceChangeKind::Deleted)
+            } else {
+                return None;
+            };
+
+            Some(ResourceChange {
+                kind,
+                resource: resource.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cloud_provider::plan::{
+        resource_changes_from_helm_diff, resource_changes_from_terraform_plan, ResourceChange, ResourceChangeKind,
+    };
+
+    fn lines(output: &str) -> Vec<String> {
+        output.lines().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_resource_changes_from_helm_diff() {
+        let output = r#"z0bd8ba4e-z4f3cc0c5, app-z8c2a4a5c, Deployment (apps) has changed:
+  # Source: q-application/templates/deployment.j2.yaml
+  apiVersion: apps/v1
+  kind: Deployment
+  metadata:
+    name: app-z8c2a4a5c
+  spec:
+-   replicas: 1
++   replicas: 2
+z0bd8ba4e-z4f3cc0c5, app-z8c2a4a5c, Service (v1) has been added:
++ # Source: q-application/templates/service.j2.yaml
+z0bd8ba4e-z4f3cc0c5, app-z8c2a4a5c-pdb, PodDisruptionBudget (policy) has been removed:
+- # Source: q-application/templates/pdb.j2.yaml"#;
+
+        assert_eq!(
+            resource_changes_from_helm_diff(&lines(output)),
+            vec![
+                ResourceChange {
+                    kind: ResourceChangeKind::Changed,
+                    resource: "Deployment/app-z8c2a4a5c".to_string(),
+                },
+                ResourceChange {
+                    kind: ResourceChangeKind::Created,
+                    resource: "Service/app-z8c2a4a5c".to_string(),
+                },
+                ResourceChange {
+                    kind: ResourceChangeKind::Deleted,
+                    resource: "PodDisruptionBudget/app-z8c2a4a5c-pdb".to_string(),
+                },
+            ]
+        );
+        assert!(resource_changes_from_helm_diff(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_resource_changes_from_terraform_plan() {
+        let output = r#"Terraform will perform the following actions:
+
+  # aws_db_instance.postgresql_instance must be replaced
+-/+ resource "aws_db_instance" "postgresql_instance" {
+      ~ engine_version = "11.8" -> "12.4" # forces replacement
+    }
+
+  # aws_security_group_rule.postgresql_remote_access will be updated in-place
+  ~ resource "aws_security_group_rule" "postgresql_remote_access" {
+    }
+
+  # aws_db_parameter_group.postgresql_parameter_group will be created
+  + resource "aws_db_parameter_group" "postgresql_parameter_group" {
+    }
+
+  # aws_db_subnet_group.postgresql will be destroyed
+  - resource "aws_db_subnet_group" "postgresql" {
+    }
+
+Plan: 2 to add, 1 to change, 2 to destroy."#;
+
+        assert_eq!(
+            resource_changes_from_terraform_plan(&lines(output)),
+            vec![
+                ResourceChange {
+                    kind: ResourceChangeKind::Replaced,
+                    resource: "aws_db_instance.postgresql_instance".to_string(),
+                },
+                ResourceChange {
+                    kind: ResourceChangeKind::Changed,
+                    resource: "aws_security_group_rule.postgresql_remote_access".to_string(),
+                },
+                ResourceChange {
+                    kind: ResourceChangeKind::Created,
+                    resource: "aws_db_parameter_group.postgresql_parameter_group".to_string(),
+                },
+                ResourceChange {
+                    kind: ResourceChangeKind::Deleted,
+                    resource: "aws_db_subnet_group.postgresql".to_string(),
+                },
+            ]
+        );
+    }
+}
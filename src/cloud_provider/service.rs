@@ -1,24 +1,39 @@
+use std::cmp::Ordering;
 use std::net::TcpStream;
 use std::sync::mpsc;
 use std::sync::mpsc::TryRecvError;
 use std::thread;
 use std::time::Duration;
 
+use chrono::{DateTime, Utc};
+use retry::delay::Fibonacci;
+use retry::OperationResult;
 use tera::Context as TeraContext;
 
 use crate::build_platform::Image;
 use crate::cloud_provider::environment::Environment;
 use crate::cloud_provider::kubernetes::Kubernetes;
-use crate::cloud_provider::utilities::check_domain_for;
+use crate::cloud_provider::models::{
+    Autoscaling, BasicAuthUser, CustomDomain, CustomDomainCertificate, JobSchedule, PublicAccess, PublicPortProtocol,
+    RolloutStrategy, Route, Storage,
+};
+use crate::cloud_provider::plan::{
+    resource_changes_from_helm_diff, resource_changes_from_terraform_plan, ImageChange, ResourceChange,
+    ResourceChangeKind, ServicePlan,
+};
+use crate::cloud_provider::utilities::{check_domain_for, compare_database_major_versions, is_valid_cidr};
 use crate::cloud_provider::DeploymentTarget;
 use crate::cmd::helm::Timeout;
 use crate::cmd::kubectl::ScalingKind::Statefulset;
 use crate::cmd::kubectl::{kubectl_exec_delete_secret, kubectl_exec_scale_replicas_by_selector, ScalingKind};
 use crate::cmd::structs::LabelsContent;
-use crate::error::{cast_simple_error_to_engine_error, StringError};
+use crate::error::{cast_simple_error_to_engine_error, SimpleError, SimpleErrorKind, StringError};
 use crate::error::{EngineError, EngineErrorCause, EngineErrorScope};
 use crate::models::ProgressLevel::Info;
-use crate::models::{Context, Listen, Listeners, ListenersHelper, ProgressInfo, ProgressLevel, ProgressScope};
+use crate::models::{
+    Context, DatabaseKind, Listen, Listeners, ListenersHelper, ProgressInfo, ProgressLevel, ProgressScope,
+};
+use crate::object_storage::ObjectAccess;
 
 pub trait Service {
     fn context(&self) -> &Context;
@@ -33,6 +48,7 @@ pub trait Service {
         let dir_root = match self.service_type() {
             ServiceType::Application => "applications",
             ServiceType::ExternalService => "external-services",
+            ServiceType::Job => "jobs",
             ServiceType::Database(_) => "databases",
             ServiceType::Router => "routers",
         };
@@ -51,6 +67,14 @@ pub trait Service {
     fn cpu_burst(&self) -> String;
     fn total_ram_in_mib(&self) -> u32;
     fn total_instances(&self) -> u16;
+    /// instances the service can be scaled up to, same as total_instances without autoscaling
+    fn max_instances(&self) -> u16 {
+        self.total_instances()
+    }
+    /// how a new version of the service replaces the running one
+    fn rollout_strategy(&self) -> RolloutStrategy {
+        RolloutStrategy::RollingUpdate
+    }
     fn tera_context(&self, target: &DeploymentTarget) -> Result<TeraContext, EngineError>;
     // used to retrieve logs by using Kubernetes labels (selector)
     fn selector(&self) -> String;
@@ -101,13 +125,24 @@ pub trait Service {
         match self.service_type() {
             ServiceType::Application => ProgressScope::Application { id },
             ServiceType::ExternalService => ProgressScope::ExternalService { id },
+            ServiceType::Job => ProgressScope::Job { id },
             ServiceType::Database(_) => ProgressScope::Database { id },
             ServiceType::Router => ProgressScope::Router { id },
         }
     }
 }
 
-pub trait StatelessService: Service + Create + Pause + Delete {
+pub trait StatelessService: Service + Create + Pause + Delete + Plan {
+    // backed up and restored along with the databases of the environment, e.g. the storage of an application
+    fn as_backup(&self) -> Option<&dyn Backup> {
+        None
+    }
+
+    // redeployed along with the applications it routes to, e.g. during a canary release
+    fn as_router(&self) -> Option<&dyn Router> {
+        None
+    }
+
     fn exec_action(&self, deployment_target: &DeploymentTarget) -> Result<(), EngineError> {
         match self.action() {
             crate::cloud_provider::service::Action::Create => self.on_create(deployment_target),
@@ -118,10 +153,23 @@ pub trait StatelessService: Service + Create + Pause + Delete {
     }
 }
 
-pub trait StatefulService: Service + Create + Pause + Delete + Backup + Clone + Upgrade + Downgrade {
+pub trait StatefulService: Service + Create + Pause + Delete + Backup + Clone + Upgrade + Downgrade + Plan {
     fn exec_action(&self, deployment_target: &DeploymentTarget) -> Result<(), EngineError> {
         match self.action() {
-            crate::cloud_provider::service::Action::Create => self.on_create(deployment_target),
+            crate::cloud_provider::service::Action::Create => {
+                // a major version change must be migrated before the regular deployment
+                if let Err(err) = self.on_upgrade(deployment_target) {
+                    let _ = self.on_upgrade_error(deployment_target);
+                    return Err(err);
+                }
+
+                if let Err(err) = self.on_downgrade(deployment_target) {
+                    let _ = self.on_downgrade_error(deployment_target);
+                    return Err(err);
+                }
+
+                self.on_create(deployment_target)
+            }
             crate::cloud_provider::service::Action::Delete => self.on_delete(deployment_target),
             crate::cloud_provider::service::Action::Pause => self.on_pause(deployment_target),
             crate::cloud_provider::service::Action::Nothing => Ok(()),
@@ -136,8 +184,15 @@ pub trait Application: StatelessService {
 
 pub trait ExternalService: StatelessService {}
 
+pub trait Job: StatelessService {
+    fn schedule(&self) -> &JobSchedule;
+    // active deadline of a run of the job
+    fn max_duration_in_seconds(&self) -> u32;
+}
+
 pub trait Router: StatelessService + Listen {
     fn domains(&self) -> Vec<&str>;
+    fn routes_to(&self, application_name: &str) -> bool;
     fn check_domains(&self) -> Result<(), EngineError> {
         check_domain_for(
             ListenersHelper::new(self.listeners()),
@@ -206,6 +261,11 @@ pub trait Downgrade {
     fn on_downgrade_error(&self, target: &DeploymentTarget) -> Result<(), EngineError>;
 }
 
+/// what the service action would change, without changing anything
+pub trait Plan {
+    fn on_plan(&self, target: &DeploymentTarget) -> Result<ServicePlan, EngineError>;
+}
+
 pub trait Terraform {
     fn terraform_common_resource_dir_path(&self) -> String;
     fn terraform_resource_dir_path(&self) -> String;
@@ -234,6 +294,19 @@ pub struct DatabaseOptions {
     pub port: u16,
     pub disk_size_in_gib: u32,
     pub database_disk_type: String,
+    pub restore_from: Option<DatabaseRestoreSource>,
+    // a private database is only reachable from inside the cluster, its Kubernetes service stays a ClusterIP
+    pub public_access: Option<PublicAccess>,
+}
+
+#[derive(Clone, Eq, PartialEq)]
+pub enum DatabaseRestoreSource {
+    // a backup done by `Backup::on_backup`, possibly from another database
+    Backup { database_id: String, backup_name: String },
+    // a snapshot of a managed database (RDS, DocumentDB or ElastiCache)
+    Snapshot { snapshot_id: String },
+    // the state of a managed RDS database at a given time
+    PointInTime { restore_time: DateTime<Utc> },
 }
 
 #[derive(Eq, PartialEq)]
@@ -248,6 +321,7 @@ pub enum DatabaseType<'a> {
 pub enum ServiceType<'a> {
     Application,
     ExternalService,
+    Job,
     Database(DatabaseType<'a>),
     Router,
 }
@@ -257,6 +331,7 @@ impl<'a> ServiceType<'a> {
         match self {
             ServiceType::Application => "Application",
             ServiceType::ExternalService => "ExternalService",
+            ServiceType::Job => "Job",
             ServiceType::Database(db_type) => match db_type {
                 DatabaseType::PostgreSQL(_) => "PostgreSQL database",
                 DatabaseType::MongoDB(_) => "MongoDB database",
@@ -328,30 +403,52 @@ pub fn deploy_user_stateless_service<T>(target: &DeploymentTarget, service: &T)
 where
     T: Service + Helm,
 {
-    deploy_stateless_service(
-        target,
-        service,
-        service.engine_error(
-            EngineErrorCause::User(
-                "Your application has failed to start. \
-                Ensure you can run it without issues with `qovery run` and check its logs from the web interface or the CLI with `qovery log`. \
-                This issue often occurs due to ports misconfiguration. Make sure you exposed the correct port (using EXPOSE statement in Dockerfile or via Qovery configuration).",
-            ),
-            format!(
-                "{} {} has failed to start ⤬",
-                service.service_type().name(),
-                service.name_with_id()
-            ),
+    deploy_stateless_service(target, service, user_service_start_error(service))
+}
+
+fn user_service_start_error<T>(service: &T) -> EngineError
+where
+    T: Service + ?Sized,
+{
+    service.engine_error(
+        EngineErrorCause::User(
+            "Your application has failed to start. \
+            Ensure you can run it without issues with `qovery run` and check its logs from the web interface or the CLI with `qovery log`. \
+            This issue often occurs due to ports misconfiguration. Make sure you exposed the correct port (using EXPOSE statement in Dockerfile or via Qovery configuration).",
+        ),
+        format!(
+            "{} {} has failed to start ⤬",
+            service.service_type().name(),
+            service.name_with_id()
         ),
     )
 }
 
-/// deploy a stateless service (app, router, database...) on Kubernetes
-pub fn deploy_stateless_service<T>(
-    target: &DeploymentTarget,
-    service: &T,
-    thrown_error: EngineError,
-) -> Result<(), EngineError>
+const BLUE_GREEN_SLOTS: [&str; 2] = ["blue", "green"];
+const CANARY_SLOT: &str = "canary";
+// interval between two checks of the canary pods during the analysis
+const CANARY_ANALYSIS_INTERVAL_IN_SECONDS: u64 = 10;
+
+/// deploy an application created by the user according to its rollout strategy
+pub fn deploy_user_application<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+where
+    T: Service + Helm,
+{
+    match service.rollout_strategy() {
+        RolloutStrategy::RollingUpdate => {
+            let _ = deploy_user_stateless_service(target, service)?;
+            uninstall_blue_green_releases(target, service)
+        }
+        RolloutStrategy::BlueGreen => deploy_blue_green_application(target, service),
+        RolloutStrategy::Canary {
+            analysis_duration_in_seconds,
+            ..
+        } => deploy_canary_application(target, service, analysis_duration_in_seconds),
+    }
+}
+
+/// delete an application created by the user, with the releases left by its rollout strategy
+pub fn delete_user_application<T>(target: &DeploymentTarget, service: &T, is_error: bool) -> Result<(), EngineError>
 where
     T: Service + Helm,
 {
@@ -360,79 +457,195 @@ where
         DeploymentTarget::SelfHosted(k, env) => (*k, *env),
     };
 
-    let workspace_dir = service.workspace_directory();
-    let tera_context = service.tera_context(target)?;
+    let _ = delete_stateless_service(target, service, is_error)?;
 
-    let _ = cast_simple_error_to_engine_error(
-        service.engine_error_scope(),
-        service.context().execution_id(),
-        crate::template::generate_and_copy_all_files_into_dir(
-            service.helm_chart_dir(),
-            workspace_dir.as_str(),
-            &tera_context,
-        ),
-    )?;
+    // the rollout strategy may have changed since these releases were deployed
+    for slot in BLUE_GREEN_SLOTS.iter().chain([CANARY_SLOT].iter()) {
+        let _ = uninstall_helm_release_if_exists(
+            kubernetes,
+            environment,
+            service,
+            rollout_release_name(service, slot).as_str(),
+        )?;
+    }
 
-    let helm_release_name = service.helm_release_name();
-    let kubernetes_config_file_path = kubernetes.config_file_path()?;
+    Ok(())
+}
 
-    // define labels to add to namespace
-    let namespace_labels = service.context().resource_expiration_in_seconds().map(|_| {
-        vec![
-            (LabelsContent {
-                name: "ttl".to_string(),
-                value: format! {"{}", service.context().resource_expiration_in_seconds().unwrap()},
-            }),
-        ]
-    });
+/// the main release holds the workload again when switching from the blue/green strategy, once it is deployed
+/// the slots can be removed
+fn uninstall_blue_green_releases<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+where
+    T: Service + Helm,
+{
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
 
-    // create a namespace with labels if do not exists
-    let _ = cast_simple_error_to_engine_error(
-        service.engine_error_scope(),
-        service.context().execution_id(),
-        crate::cmd::kubectl::kubectl_exec_create_namespace(
-            kubernetes_config_file_path.as_str(),
-            environment.namespace(),
-            namespace_labels,
-            kubernetes.cloud_provider().credentials_environment_variables(),
-        ),
-    )?;
+    for slot in BLUE_GREEN_SLOTS.iter() {
+        let _ = uninstall_helm_release_if_exists(
+            kubernetes,
+            environment,
+            service,
+            rollout_release_name(service, slot).as_str(),
+        )?;
+    }
 
-    // do exec helm upgrade and return the last deployment status
-    let helm_history_row = cast_simple_error_to_engine_error(
-        service.engine_error_scope(),
-        service.context().execution_id(),
-        crate::cmd::helm::helm_exec_with_upgrade_history(
-            kubernetes_config_file_path.as_str(),
-            environment.namespace(),
-            helm_release_name.as_str(),
-            workspace_dir.as_str(),
-            service.start_timeout(),
-            kubernetes.cloud_provider().credentials_environment_variables(),
-        ),
-    )?;
+    Ok(())
+}
 
-    // check deployment status
-    if helm_history_row.is_none() || !helm_history_row.unwrap().is_successfully_deployed() {
-        return Err(thrown_error);
+/// the canary ingresses are rendered by the routers, which are deployed after the applications:
+/// the routers of the application are deployed beforehand so that the canary receives its share of the traffic
+/// during the analysis, and once again when the canary is removed so that they stop routing to it
+fn deploy_canary_ingresses<T>(
+    target: &DeploymentTarget,
+    environment: &Environment,
+    service: &T,
+) -> Result<(), EngineError>
+where
+    T: Service + ?Sized,
+{
+    for router in environment
+        .stateless_services
+        .iter()
+        .filter_map(|stateless_service| stateless_service.as_router())
+        .filter(|router| *router.action() == Action::Create && router.routes_to(service.name()))
+    {
+        let _ = router.exec_action(target)?;
     }
 
-    let _ = cast_simple_error_to_engine_error(
+    Ok(())
+}
+
+/// share of the traffic of the routes to an application which goes to its canary,
+/// only while the canary release of the application is deployed
+pub fn get_canary_weight_percent<T>(
+    kubernetes: &dyn Kubernetes,
+    environment: &Environment,
+    application: &T,
+) -> Option<u8>
+where
+    T: Service + ?Sized,
+{
+    let weight_percent = application.rollout_strategy().canary_weight_percent()?;
+    let canary_service_name = format!("{}-{}", application.sanitized_name(), CANARY_SLOT);
+
+    match crate::cmd::kubectl::kubectl_exec_get_service_selector(
+        kubernetes.config_file_path().ok()?.as_str(),
+        environment.namespace(),
+        canary_service_name.as_str(),
+        kubernetes.cloud_provider().credentials_environment_variables(),
+    ) {
+        Ok(Some(_)) => Some(weight_percent),
+        Ok(None) => None,
+        Err(err) => {
+            warn!(
+                "can't find whether the canary of {} is deployed, it is not routed to: {:?}",
+                application.name_with_id(),
+                err
+            );
+            None
+        }
+    }
+}
+
+/// name of the release deployed next to the main release of an application during a rollout
+fn rollout_release_name<T>(service: &T, slot: &str) -> String
+where
+    T: Helm + ?Sized,
+{
+    // helm release names are limited to 53 characters, the slot must not be cut
+    format!("{}-{}", crate::string::cut(service.helm_release_name(), 43), slot)
+}
+
+/// the new version is deployed in the slot which does not receive the traffic, the application service
+/// is switched to it once its pods are ready, then the previous slot is removed.
+/// The running version keeps receiving the traffic if the new one fails to start.
+fn deploy_blue_green_application<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+where
+    T: Service + Helm,
+{
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+
+    let selector = cast_simple_error_to_engine_error(
         service.engine_error_scope(),
         service.context().execution_id(),
-        crate::cmd::kubectl::kubectl_exec_is_pod_ready_with_retry(
-            kubernetes_config_file_path.as_str(),
+        crate::cmd::kubectl::kubectl_exec_get_service_selector(
+            kubernetes.config_file_path()?.as_str(),
             environment.namespace(),
-            service.selector().as_str(),
+            service.sanitized_name().as_str(),
             kubernetes.cloud_provider().credentials_environment_variables(),
         ),
     )?;
 
-    Ok(())
+    // no slot is active on the first deployment, or when switching from the rolling update strategy
+    let active_slot = BLUE_GREEN_SLOTS.iter().find(|slot| {
+        selector.as_ref().and_then(|selector| selector.get("app"))
+            == Some(&format!("{}-{}", service.sanitized_name(), slot))
+    });
+
+    let next_slot = match active_slot {
+        Some(&"blue") => "green",
+        _ => "blue",
+    };
+
+    let next_resource_name = format!("{}-{}", service.sanitized_name(), next_slot);
+    let next_release_name = rollout_release_name(service, next_slot);
+
+    info!(
+        "deploying {} {} into the {} slot",
+        service.service_type().name(),
+        service.name_with_id(),
+        next_slot
+    );
+
+    let next_slot_result = deploy_application_release(target, service, next_release_name.as_str(), |context| {
+        context.insert("resource_name", next_resource_name.as_str());
+        context.insert("is_service", &false);
+    })
+    .and_then(|_| wait_for_application_pods(kubernetes, environment, service, next_resource_name.as_str()));
+
+    if let Err(err) = next_slot_result {
+        warn!(
+            "{} {} has failed to start in the {} slot, the traffic is not switched",
+            service.service_type().name(),
+            service.name_with_id(),
+            next_slot
+        );
+
+        let _ = uninstall_helm_release_if_exists(kubernetes, environment, service, next_release_name.as_str());
+        return Err(err);
+    }
+
+    // the main release only holds the service once the application runs in the slots
+    let _ = deploy_application_release(target, service, service.helm_release_name().as_str(), |context| {
+        context.insert("is_workload", &false);
+        context.insert("service_selector_app", next_resource_name.as_str());
+    })?;
+
+    match active_slot {
+        Some(active_slot) => uninstall_helm_release_if_exists(
+            kubernetes,
+            environment,
+            service,
+            rollout_release_name(service, active_slot).as_str(),
+        ),
+        None => Ok(()),
+    }
 }
 
-/// do specific operations on a stateless service deployment error
-pub fn deploy_stateless_service_error<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+/// the new version is deployed as a canary, next to the running one, and receives a share of the traffic
+/// through the canary ingress of the routers. It is promoted if its pods stay healthy during the analysis,
+/// otherwise it is aborted and the running version keeps the whole traffic.
+fn deploy_canary_application<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    analysis_duration_in_seconds: u32,
+) -> Result<(), EngineError>
 where
     T: Service + Helm,
 {
@@ -441,358 +654,2435 @@ where
         DeploymentTarget::SelfHosted(k, env) => (*k, *env),
     };
 
-    let kubernetes_config_file_path = kubernetes.config_file_path()?;
-    let helm_release_name = service.helm_release_name();
-
     let history_rows = cast_simple_error_to_engine_error(
         service.engine_error_scope(),
         service.context().execution_id(),
         crate::cmd::helm::helm_exec_history(
-            kubernetes_config_file_path.as_str(),
+            kubernetes.config_file_path()?.as_str(),
             environment.namespace(),
-            helm_release_name.as_str(),
+            service.helm_release_name().as_str(),
             &kubernetes.cloud_provider().credentials_environment_variables(),
         ),
     )?;
 
-    if history_rows.len() == 1 {
-        cast_simple_error_to_engine_error(
+    // there is no running version to compare the canary with on the first deployment
+    if !history_rows.iter().any(|row| row.is_successfully_deployed()) {
+        return deploy_user_stateless_service(target, service);
+    }
+
+    let canary_resource_name = format!("{}-{}", service.sanitized_name(), CANARY_SLOT);
+    let canary_release_name = rollout_release_name(service, CANARY_SLOT);
+
+    info!(
+        "deploying {} {} as a canary for {} seconds",
+        service.service_type().name(),
+        service.name_with_id(),
+        analysis_duration_in_seconds
+    );
+
+    let canary_result = deploy_application_release(target, service, canary_release_name.as_str(), |context| {
+        context.insert("resource_name", canary_resource_name.as_str());
+        context.insert("is_service", &false);
+        context.insert("is_canary_service", &true);
+        // a single canary instance is enough to receive its share of the traffic
+        context.insert("total_instances", &1);
+        context.insert("autoscaling", &None::<String>);
+    })
+    .and_then(|_| wait_for_application_pods(kubernetes, environment, service, canary_resource_name.as_str()))
+    .and_then(|_| deploy_canary_ingresses(target, environment, service))
+    .and_then(|_| {
+        analyse_canary_pods(
+            kubernetes,
+            environment,
+            service,
+            canary_resource_name.as_str(),
+            analysis_duration_in_seconds,
+        )
+    });
+
+    let result = match canary_result {
+        Ok(_) => {
+            info!(
+                "promoting the canary of {} {}",
+                service.service_type().name(),
+                service.name_with_id()
+            );
+            deploy_user_stateless_service(target, service).and_then(|_| uninstall_blue_green_releases(target, service))
+        }
+        Err(err) => {
+            warn!(
+                "aborting the canary of {} {}",
+                service.service_type().name(),
+                service.name_with_id()
+            );
+            Err(err)
+        }
+    };
+
+    // promoted or aborted, the whole traffic goes back to the main release
+    let cleanup_results = vec![
+        uninstall_helm_release_if_exists(kubernetes, environment, service, canary_release_name.as_str()),
+        deploy_canary_ingresses(target, environment, service),
+    ];
+
+    let mut cleanup_error = None;
+    for cleanup_result in cleanup_results {
+        if let Err(err) = cleanup_result {
+            error!(
+                "error while removing the canary of {} {}: {:?}",
+                service.service_type().name(),
+                service.name_with_id(),
+                err.message
+            );
+            cleanup_error = cleanup_error.or(Some(err));
+        }
+    }
+
+    // the outcome of the canary analysis matters more than its cleanup
+    let _ = result?;
+
+    match cleanup_error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+/// the canary is healthy as long as all its containers are ready and none of them has restarted
+fn analyse_canary_pods<T>(
+    kubernetes: &dyn Kubernetes,
+    environment: &Environment,
+    service: &T,
+    canary_resource_name: &str,
+    analysis_duration_in_seconds: u32,
+) -> Result<(), EngineError>
+where
+    T: Service + ?Sized,
+{
+    let selector = format!("app={}", canary_resource_name);
+    let started_at = Utc::now();
+
+    while Utc::now().signed_duration_since(started_at).num_seconds() < analysis_duration_in_seconds as i64 {
+        let pods = cast_simple_error_to_engine_error(
             service.engine_error_scope(),
             service.context().execution_id(),
-            crate::cmd::helm::helm_exec_uninstall(
-                kubernetes_config_file_path.as_str(),
+            crate::cmd::kubectl::kubectl_exec_get_pod(
+                kubernetes.config_file_path()?.as_str(),
                 environment.namespace(),
-                helm_release_name.as_str(),
+                selector.as_str(),
                 kubernetes.cloud_provider().credentials_environment_variables(),
             ),
         )?;
+
+        let is_healthy = !pods.items.is_empty()
+            && pods.items.iter().all(|pod| match &pod.status.container_statuses {
+                Some(statuses) => statuses.iter().all(|status| status.ready && status.restart_count == 0),
+                None => false,
+            });
+
+        if !is_healthy {
+            return Err(service.engine_error(
+                EngineErrorCause::User(
+                    "The new version of your application has been unhealthy during its canary analysis, it has not been promoted. \
+                    Check its logs from the web interface or the CLI with `qovery log`.",
+                ),
+                format!(
+                    "canary of {} {} is unhealthy ⤬",
+                    service.service_type().name(),
+                    service.name_with_id()
+                ),
+            ));
+        }
+
+        thread::sleep(Duration::from_secs(CANARY_ANALYSIS_INTERVAL_IN_SECONDS));
     }
 
     Ok(())
 }
 
-pub fn scale_down_database(
+/// render the chart of the application, with `customize_context` applied on its tera context, and deploy it
+/// as `release_name`
+fn deploy_application_release<T, F>(
     target: &DeploymentTarget,
-    service: &impl Database,
-    replicas_count: usize,
-) -> Result<(), EngineError> {
+    service: &T,
+    release_name: &str,
+    customize_context: F,
+) -> Result<(), EngineError>
+where
+    T: Service + Helm,
+    F: Fn(&mut TeraContext),
+{
     let (kubernetes, environment) = match target {
-        DeploymentTarget::ManagedServices(_, _) => {
-            info!("Doing nothing for pause database as it is a managed service");
-            return Ok(());
-        }
+        DeploymentTarget::ManagedServices(k, env) => (*k, *env),
         DeploymentTarget::SelfHosted(k, env) => (*k, *env),
     };
 
-    let scaledown_ret = kubectl_exec_scale_replicas_by_selector(
-        kubernetes.config_file_path()?,
-        kubernetes.cloud_provider().credentials_environment_variables(),
-        environment.namespace(),
-        Statefulset,
-        format!("databaseId={}", service.id()).as_str(),
-        replicas_count as u32,
-    );
+    let workspace_dir = format!("{}/{}", service.workspace_directory(), release_name);
+    let mut tera_context = service.tera_context(target)?;
+    customize_context(&mut tera_context);
 
-    cast_simple_error_to_engine_error(
+    let _ = cast_simple_error_to_engine_error(
         service.engine_error_scope(),
         service.context().execution_id(),
-        scaledown_ret,
-    )
+        crate::template::generate_and_copy_all_files_into_dir(
+            service.helm_chart_dir(),
+            workspace_dir.as_str(),
+            &tera_context,
+        ),
+    )?;
+
+    let _ = create_service_namespace(kubernetes, environment, service)?;
+
+    let helm_history_row = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::helm::helm_exec_with_upgrade_history(
+            kubernetes.config_file_path()?.as_str(),
+            environment.namespace(),
+            release_name,
+            workspace_dir.as_str(),
+            service.start_timeout(),
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )?;
+
+    match helm_history_row {
+        Some(row) if row.is_successfully_deployed() => Ok(()),
+        _ => Err(user_service_start_error(service)),
+    }
 }
 
-pub fn scale_down_application(
-    target: &DeploymentTarget,
-    service: &impl StatelessService,
-    replicas_count: usize,
-    scaling_kind: ScalingKind,
-) -> Result<(), EngineError> {
-    let (kubernetes, environment) = match target {
-        DeploymentTarget::ManagedServices(_, _) => {
-            return Err(EngineError {
-                cause: EngineErrorCause::Internal,
-                scope: EngineErrorScope::Engine,
-                execution_id: service.context().execution_id().to_string(),
-                message: Some(format!("Cannot scale down managed service: {}", service.name_with_id())),
-            })
-        }
-        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
-    };
-    let scaledown_ret = kubectl_exec_scale_replicas_by_selector(
-        kubernetes.config_file_path()?,
-        kubernetes.cloud_provider().credentials_environment_variables(),
+fn wait_for_application_pods<T>(
+    kubernetes: &dyn Kubernetes,
+    environment: &Environment,
+    service: &T,
+    resource_name: &str,
+) -> Result<(), EngineError>
+where
+    T: Service + ?Sized,
+{
+    match crate::cmd::kubectl::kubectl_exec_is_pod_ready_with_retry(
+        kubernetes.config_file_path()?.as_str(),
         environment.namespace(),
-        scaling_kind,
-        format!("appId={}", service.id()).as_str(),
-        replicas_count as u32,
-    );
+        format!("app={}", resource_name).as_str(),
+        kubernetes.cloud_provider().credentials_environment_variables(),
+    ) {
+        Ok(Some(true)) => Ok(()),
+        _ => Err(user_service_start_error(service)),
+    }
+}
+
+fn uninstall_helm_release_if_exists<T>(
+    kubernetes: &dyn Kubernetes,
+    environment: &Environment,
+    service: &T,
+    release_name: &str,
+) -> Result<(), EngineError>
+where
+    T: Service + ?Sized,
+{
+    let kubernetes_config_file_path = kubernetes.config_file_path()?;
+
+    let history_rows = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::helm::helm_exec_history(
+            kubernetes_config_file_path.as_str(),
+            environment.namespace(),
+            release_name,
+            &kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )?;
+
+    if history_rows.is_empty() {
+        return Ok(());
+    }
 
     cast_simple_error_to_engine_error(
         service.engine_error_scope(),
         service.context().execution_id(),
-        scaledown_ret,
+        crate::cmd::helm::helm_exec_uninstall(
+            kubernetes_config_file_path.as_str(),
+            environment.namespace(),
+            release_name,
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
     )
 }
 
-pub fn delete_stateless_service<T>(target: &DeploymentTarget, service: &T, is_error: bool) -> Result<(), EngineError>
+/// the load balancer of the public ports takes a while to be provisioned, its address is reported once known
+pub fn report_public_access_address<T, F>(
+    target: &DeploymentTarget,
+    service: &T,
+    public_access: &PublicAccess,
+    get_external_address: F,
+) -> Result<(), EngineError>
 where
-    T: Service + Helm,
+    T: Service + Listen + ?Sized,
+    F: Fn(&str, &str, &str, Vec<(&str, &str)>) -> Result<Option<String>, SimpleError>,
 {
     let (kubernetes, environment) = match target {
         DeploymentTarget::ManagedServices(k, env) => (*k, *env),
         DeploymentTarget::SelfHosted(k, env) => (*k, *env),
     };
 
-    let helm_release_name = service.helm_release_name();
-
-    if is_error {
-        let _ = get_stateless_resource_information(kubernetes, environment, service.selector().as_str())?;
+    if public_access.ports.is_empty() {
+        return Ok(());
     }
 
-    // clean the resource
-    let _ = do_stateless_service_cleanup(kubernetes, environment, helm_release_name.as_str())?;
+    let kubernetes_config_file_path = kubernetes.config_file_path()?;
+    let selector = match service.service_type() {
+        ServiceType::Database(_) => format!("databaseId={},publicAccess=true", service.id()),
+        _ => format!("appId={},publicAccess=true", service.id()),
+    };
+
+    let tcp_ports = public_access
+        .ports
+        .iter()
+        .filter(|p| p.protocol == PublicPortProtocol::Tcp)
+        .map(|p| format!("{}/tcp", p.port))
+        .collect::<Vec<_>>();
+    let udp_ports = public_access
+        .ports
+        .iter()
+        .filter(|p| p.protocol == PublicPortProtocol::Udp)
+        .map(|p| format!("{}/udp", p.port))
+        .collect::<Vec<_>>();
+
+    // a load balancer can't mix protocols: when both are exposed, each one has its own load balancer
+    // and the DNS record only points to the TCP one
+    let load_balancers = match (tcp_ports.is_empty(), udp_ports.is_empty()) {
+        (false, false) => vec![
+            (format!("{},publicProtocol=tcp", selector), tcp_ports, true),
+            (format!("{},publicProtocol=udp", selector), udp_ports, false),
+        ],
+        (false, true) => vec![(selector, tcp_ports, true)],
+        _ => vec![(selector, udp_ports, true)],
+    };
+
+    let listeners_helper = ListenersHelper::new(service.listeners());
+
+    for (selector, ports, has_dns_record) in load_balancers {
+        let address = retry::retry(Fibonacci::from_millis(3000).take(10), || {
+            match get_external_address(
+                kubernetes_config_file_path.as_str(),
+                environment.namespace(),
+                selector.as_str(),
+                kubernetes.cloud_provider().credentials_environment_variables(),
+            ) {
+                Ok(Some(address)) => OperationResult::Ok(address),
+                Ok(None) => OperationResult::Retry(SimpleError::new(
+                    SimpleErrorKind::Other,
+                    Some("the load balancer has no address yet"),
+                )),
+                Err(err) => OperationResult::Retry(err),
+            }
+        });
+
+        let ports = ports.join(", ");
+
+        match address {
+            Ok(address) => {
+                let message = match has_dns_record {
+                    true => format!(
+                        "{} '{}' is publicly reachable on {} ({}) through ports {}",
+                        service.service_type().name(),
+                        service.name_with_id(),
+                        public_access.fqdn,
+                        address,
+                        ports
+                    ),
+                    false => format!(
+                        "{} '{}' is publicly reachable on {} through ports {}, {} only points to its TCP ports",
+                        service.service_type().name(),
+                        service.name_with_id(),
+                        address,
+                        ports,
+                        public_access.fqdn
+                    ),
+                };
+
+                info!("{}", message.as_str());
+
+                listeners_helper.deployment_in_progress(ProgressInfo::new(
+                    service.progress_scope(),
+                    ProgressLevel::Info,
+                    Some(message),
+                    service.context().execution_id(),
+                ));
+            }
+            Err(_) => {
+                // the load balancer may still come up later, it's not worth failing the deployment
+                let message = format!(
+                    "{} '{}' is deployed but the load balancer of its public ports {} has no address yet",
+                    service.service_type().name(),
+                    service.name_with_id(),
+                    ports
+                );
+
+                warn!("{}", message.as_str());
+
+                listeners_helper.deployment_in_progress(ProgressInfo::new(
+                    service.progress_scope(),
+                    ProgressLevel::Warn,
+                    Some(message),
+                    service.context().execution_id(),
+                ));
+            }
+        }
+    }
 
     Ok(())
 }
 
-pub fn deploy_stateful_service<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+/// deploy a stateless service (app, router, database...) on Kubernetes
+pub fn deploy_stateless_service<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    thrown_error: EngineError,
+) -> Result<(), EngineError>
 where
-    T: StatefulService + Helm + Terraform,
+    T: Service + Helm,
 {
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+
     let workspace_dir = service.workspace_directory();
+    let tera_context = service.tera_context(target)?;
 
-    match target {
-        DeploymentTarget::ManagedServices(kubernetes, _) => {
-            // use terraform
-            info!(
-                "deploy {} with name {} on {}",
-                service.service_type().name(),
-                service.name_with_id(),
-                kubernetes.cloud_provider().kind().name()
-            );
+    let _ = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::template::generate_and_copy_all_files_into_dir(
+            service.helm_chart_dir(),
+            workspace_dir.as_str(),
+            &tera_context,
+        ),
+    )?;
 
-            let context = service.tera_context(target)?;
+    let helm_release_name = service.helm_release_name();
+    let kubernetes_config_file_path = kubernetes.config_file_path()?;
 
-            let _ = cast_simple_error_to_engine_error(
-                service.engine_error_scope(),
-                service.context().execution_id(),
-                crate::template::generate_and_copy_all_files_into_dir(
-                    service.terraform_common_resource_dir_path(),
-                    &workspace_dir,
-                    &context,
-                ),
-            )?;
+    let _ = create_service_namespace(kubernetes, environment, service)?;
 
-            let _ = cast_simple_error_to_engine_error(
-                service.engine_error_scope(),
-                service.context().execution_id(),
-                crate::template::generate_and_copy_all_files_into_dir(
-                    service.terraform_resource_dir_path(),
-                    workspace_dir.as_str(),
-                    &context,
-                ),
-            )?;
+    // do exec helm upgrade and return the last deployment status
+    let helm_history_row = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::helm::helm_exec_with_upgrade_history(
+            kubernetes_config_file_path.as_str(),
+            environment.namespace(),
+            helm_release_name.as_str(),
+            workspace_dir.as_str(),
+            service.start_timeout(),
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )?;
 
-            let _ = cast_simple_error_to_engine_error(
-                service.engine_error_scope(),
-                service.context().execution_id(),
-                crate::template::generate_and_copy_all_files_into_dir(
-                    service.helm_chart_external_name_service_dir(),
-                    format!("{}/{}", workspace_dir, "external-name-svc"),
-                    &context,
-                ),
-            )?;
+    // check deployment status
+    if helm_history_row.is_none() || !helm_history_row.unwrap().is_successfully_deployed() {
+        return Err(thrown_error);
+    }
 
-            let _ = cast_simple_error_to_engine_error(
-                service.engine_error_scope(),
-                service.context().execution_id(),
-                crate::cmd::terraform::terraform_init_validate_plan_apply(
-                    workspace_dir.as_str(),
-                    service.context().is_dry_run_deploy(),
-                ),
-            )?;
-        }
-        DeploymentTarget::SelfHosted(kubernetes, environment) => {
-            // use helm
-            info!(
-                "deploy {} with name {} on {:?} Kubernetes cluster id {}",
-                service.service_type().name(),
-                service.name_with_id(),
-                kubernetes.cloud_provider().kind().name(),
-                kubernetes.id()
-            );
+    let _ = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::kubectl::kubectl_exec_is_pod_ready_with_retry(
+            kubernetes_config_file_path.as_str(),
+            environment.namespace(),
+            service.selector().as_str(),
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )?;
 
-            let context = service.tera_context(target)?;
-            let kubernetes_config_file_path = kubernetes.config_file_path()?;
+    Ok(())
+}
 
-            // default chart
-            let _ = cast_simple_error_to_engine_error(
-                service.engine_error_scope(),
-                service.context().execution_id(),
-                crate::template::generate_and_copy_all_files_into_dir(
+/// create the namespace of the environment if it does not exist, with a ttl label on ephemeral environments
+fn create_service_namespace<T>(
+    kubernetes: &dyn Kubernetes,
+    environment: &Environment,
+    service: &T,
+) -> Result<(), EngineError>
+where
+    T: Service + ?Sized,
+{
+    // define labels to add to namespace
+    let namespace_labels = service.context().resource_expiration_in_seconds().map(|_| {
+        vec![
+            (LabelsContent {
+                name: "ttl".to_string(),
+                value: format! {"{}", service.context().resource_expiration_in_seconds().unwrap()},
+            }),
+        ]
+    });
+
+    // create a namespace with labels if do not exists
+    cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::kubectl::kubectl_exec_create_namespace(
+            kubernetes.config_file_path()?.as_str(),
+            environment.namespace(),
+            namespace_labels,
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )
+}
+
+/// deploy a job created by the user with the q-job chart.
+/// One-shot and pre-deploy hook jobs are run and awaited until their completion, cron jobs are only scheduled
+pub fn deploy_user_job<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+where
+    T: Job + Helm,
+{
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+
+    let _ = upgrade_job_release(target, service, false)?;
+
+    if service.schedule().cron_schedule().is_some() {
+        return Ok(());
+    }
+
+    match crate::cmd::kubectl::kubectl_exec_wait_for_job_completion(
+        kubernetes.config_file_path()?.as_str(),
+        environment.namespace(),
+        service.sanitized_name().as_str(),
+        service.max_duration_in_seconds(),
+        kubernetes.cloud_provider().credentials_environment_variables(),
+    ) {
+        Ok(true) => Ok(()),
+        _ => Err(service.engine_error(
+            EngineErrorCause::User(
+                "Your job has failed or has not completed in time. \
+                Ensure you can run it without issues with `qovery run` and check its logs from the web interface or the CLI with `qovery log`.",
+            ),
+            format!(
+                "{} {} has not succeeded ⤬",
+                service.service_type().name(),
+                service.name_with_id()
+            ),
+        )),
+    }
+}
+
+/// suspend a cron job, one-shot and pre-deploy hook jobs have nothing left running once done
+pub fn pause_job<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+where
+    T: Job + Helm,
+{
+    match service.schedule().cron_schedule() {
+        Some(_) => upgrade_job_release(target, service, true),
+        None => Ok(()),
+    }
+}
+
+fn upgrade_job_release<T>(target: &DeploymentTarget, service: &T, is_suspended: bool) -> Result<(), EngineError>
+where
+    T: Job + Helm,
+{
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+
+    let workspace_dir = service.workspace_directory();
+    let mut tera_context = service.tera_context(target)?;
+    tera_context.insert("job_suspended", &is_suspended);
+
+    let _ = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::template::generate_and_copy_all_files_into_dir(
+            service.helm_chart_dir(),
+            workspace_dir.as_str(),
+            &tera_context,
+        ),
+    )?;
+
+    let helm_release_name = service.helm_release_name();
+    let kubernetes_config_file_path = kubernetes.config_file_path()?;
+
+    let _ = create_service_namespace(kubernetes, environment, service)?;
+
+    // the pod template of a Job can't be updated, the previous run is removed to start a new one
+    if service.schedule().cron_schedule().is_none() {
+        let _ = do_stateless_service_cleanup(kubernetes, environment, helm_release_name.as_str())?;
+    }
+
+    let helm_history_row = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::helm::helm_exec_with_upgrade_history(
+            kubernetes_config_file_path.as_str(),
+            environment.namespace(),
+            helm_release_name.as_str(),
+            workspace_dir.as_str(),
+            service.start_timeout(),
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )?;
+
+    match helm_history_row {
+        Some(row) if row.is_successfully_deployed() => Ok(()),
+        _ => Err(service.engine_error(
+            EngineErrorCause::Internal,
+            format!(
+                "{} {} release has not been deployed",
+                service.service_type().name(),
+                service.name_with_id()
+            ),
+        )),
+    }
+}
+
+/// react to a failed deployment of a stateless service: its release is rolled back to its last deployed
+/// revision, or uninstalled if it has never been deployed. Releases of the other services are not touched.
+pub fn deploy_stateless_service_error<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+where
+    T: Service + Helm + Listen,
+{
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+
+    let kubernetes_config_file_path = kubernetes.config_file_path()?;
+    let helm_release_name = service.helm_release_name();
+
+    // rows are sorted from the latest revision to the oldest one
+    let history_rows = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::helm::helm_exec_history(
+            kubernetes_config_file_path.as_str(),
+            environment.namespace(),
+            helm_release_name.as_str(),
+            &kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )?;
+
+    let failed_revision = match history_rows.first() {
+        // the service has not been deployed or its deployment has succeeded, there is nothing to revert
+        None => return Ok(()),
+        Some(row) if row.is_successfully_deployed() => return Ok(()),
+        Some(row) => row.revision,
+    };
+
+    if history_rows.len() == 1 {
+        return cast_simple_error_to_engine_error(
+            service.engine_error_scope(),
+            service.context().execution_id(),
+            crate::cmd::helm::helm_exec_uninstall(
+                kubernetes_config_file_path.as_str(),
+                environment.namespace(),
+                helm_release_name.as_str(),
+                kubernetes.cloud_provider().credentials_environment_variables(),
+            ),
+        );
+    }
+
+    let listeners_helper = ListenersHelper::new(service.listeners());
+
+    let rollback_revision = match crate::cloud_provider::helm::get_latest_successful_deployment(&history_rows) {
+        Ok(row) => row.revision,
+        Err(_) => {
+            let message = format!(
+                "{} {} revision {} has failed and there is no previously deployed revision to roll back to",
+                service.service_type().name(),
+                service.name_with_id(),
+                failed_revision
+            );
+
+            warn!("{}", message.as_str());
+
+            listeners_helper.deployment_error(ProgressInfo::new(
+                service.progress_scope(),
+                ProgressLevel::Warn,
+                Some(message),
+                service.context().execution_id(),
+            ));
+
+            return Ok(());
+        }
+    };
+
+    info!(
+        "rolling back {} {} from revision {} to revision {}",
+        service.service_type().name(),
+        service.name_with_id(),
+        failed_revision,
+        rollback_revision
+    );
+
+    let rollback_result = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::helm::helm_exec_rollback(
+            kubernetes_config_file_path.as_str(),
+            environment.namespace(),
+            helm_release_name.as_str(),
+            rollback_revision,
+            service.start_timeout(),
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    );
+
+    let (level, message) = match &rollback_result {
+        Ok(_) => (
+            ProgressLevel::Warn,
+            format!(
+                "{} {} revision {} has failed, it has been rolled back to revision {}",
+                service.service_type().name(),
+                service.name_with_id(),
+                failed_revision,
+                rollback_revision
+            ),
+        ),
+        Err(_) => (
+            ProgressLevel::Error,
+            format!(
+                "{} {} revision {} has failed and can't be rolled back to revision {}",
+                service.service_type().name(),
+                service.name_with_id(),
+                failed_revision,
+                rollback_revision
+            ),
+        ),
+    };
+
+    listeners_helper.deployment_error(ProgressInfo::new(
+        service.progress_scope(),
+        level,
+        Some(message),
+        service.context().execution_id(),
+    ));
+
+    rollback_result
+}
+
+pub fn scale_down_database(
+    target: &DeploymentTarget,
+    service: &impl Database,
+    replicas_count: usize,
+) -> Result<(), EngineError> {
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(_, _) => {
+            info!("Doing nothing for pause database as it is a managed service");
+            return Ok(());
+        }
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+
+    let scaledown_ret = kubectl_exec_scale_replicas_by_selector(
+        kubernetes.config_file_path()?,
+        kubernetes.cloud_provider().credentials_environment_variables(),
+        environment.namespace(),
+        Statefulset,
+        format!("databaseId={}", service.id()).as_str(),
+        replicas_count as u32,
+    );
+
+    cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        scaledown_ret,
+    )
+}
+
+pub fn scale_down_application(
+    target: &DeploymentTarget,
+    service: &impl StatelessService,
+    replicas_count: usize,
+    scaling_kind: ScalingKind,
+) -> Result<(), EngineError> {
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(_, _) => {
+            return Err(EngineError {
+                cause: EngineErrorCause::Internal,
+                scope: EngineErrorScope::Engine,
+                execution_id: service.context().execution_id().to_string(),
+                message: Some(format!("Cannot scale down managed service: {}", service.name_with_id())),
+            })
+        }
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+    let scaledown_ret = kubectl_exec_scale_replicas_by_selector(
+        kubernetes.config_file_path()?,
+        kubernetes.cloud_provider().credentials_environment_variables(),
+        environment.namespace(),
+        scaling_kind,
+        format!("appId={}", service.id()).as_str(),
+        replicas_count as u32,
+    );
+
+    cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        scaledown_ret,
+    )
+}
+
+fn volume_snapshots_cronjob_name<T>(service: &T) -> String
+where
+    T: Service + ?Sized,
+{
+    format!("{}-volume-snapshots", service.sanitized_name())
+}
+
+/// take a snapshot of every volume of an application now, by running its volume snapshots cronjob once
+pub fn snapshot_application_storage<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+where
+    T: Service + ?Sized,
+{
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+
+    let kubernetes_config_file_path = kubernetes.config_file_path()?;
+    let job_name = crate::string::cut(
+        format!("volume-snapshots-{}-{}", service.id(), Utc::now().timestamp()),
+        63,
+    );
+
+    let _ = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::kubectl::kubectl_exec_create_job_from_cronjob(
+            kubernetes_config_file_path.as_str(),
+            environment.namespace(),
+            volume_snapshots_cronjob_name(service).as_str(),
+            job_name.as_str(),
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )?;
+
+    match crate::cmd::kubectl::kubectl_exec_is_job_ready_with_retry(
+        kubernetes_config_file_path.as_str(),
+        environment.namespace(),
+        job_name.as_str(),
+        kubernetes.cloud_provider().credentials_environment_variables(),
+    ) {
+        Ok(Some(true)) => Ok(()),
+        _ => Err(service.engine_error(
+            EngineErrorCause::Internal,
+            format!(
+                "the volumes of {} have failed to be snapshotted",
+                service.name_with_id()
+            ),
+        )),
+    }
+}
+
+/// a volume is only created from a snapshot along with its claim: the application is uninstalled, the claims of
+/// the restored storage are replaced by ones created from their snapshot, then the application is deployed again
+pub fn restore_application_storage<T, S>(
+    target: &DeploymentTarget,
+    service: &T,
+    storage: &[Storage<S>],
+    restore_chart_dir: &str,
+) -> Result<(), EngineError>
+where
+    T: Service + Helm,
+{
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+
+    let restored_storage = storage
+        .iter()
+        .filter(|s| s.restore_from_snapshot.is_some())
+        .collect::<Vec<_>>();
+
+    if restored_storage.is_empty() {
+        info!("there is no storage to restore for {}", service.name_with_id());
+        return Ok(());
+    }
+
+    let kubernetes_config_file_path = kubernetes.config_file_path()?;
+
+    for s in &restored_storage {
+        let snapshot_name = s.restore_from_snapshot.as_ref().unwrap();
+
+        let snapshot = cast_simple_error_to_engine_error(
+            service.engine_error_scope(),
+            service.context().execution_id(),
+            crate::cmd::kubectl::kubectl_exec_get_volume_snapshot(
+                kubernetes_config_file_path.as_str(),
+                environment.namespace(),
+                snapshot_name.as_str(),
+                kubernetes.cloud_provider().credentials_environment_variables(),
+            ),
+        )?;
+
+        let message = match snapshot {
+            None => format!("snapshot {} of storage {} doesn't exist", snapshot_name, s.name),
+            Some(snapshot)
+                if !snapshot
+                    .status
+                    .as_ref()
+                    .map(|status| status.ready_to_use)
+                    .unwrap_or(false) =>
+            {
+                format!("snapshot {} of storage {} is not ready to use", snapshot_name, s.name)
+            }
+            Some(_) => continue,
+        };
+
+        return Err(service.engine_error(EngineErrorCause::User("the snapshot can't be restored"), message));
+    }
+
+    // the volume claim templates of the statefulset can't be updated, it is deleted along with the release
+    let _ = uninstall_helm_release_if_exists(kubernetes, environment, service, service.helm_release_name().as_str())?;
+
+    for s in &restored_storage {
+        let _ = cast_simple_error_to_engine_error(
+            service.engine_error_scope(),
+            service.context().execution_id(),
+            crate::cmd::kubectl::kubectl_exec_delete_pvc_by_selector(
+                kubernetes_config_file_path.as_str(),
+                environment.namespace(),
+                format!("appId={},diskId={}", service.id(), s.id).as_str(),
+                kubernetes.cloud_provider().credentials_environment_variables(),
+            ),
+        )?;
+    }
+
+    let workspace_dir = format!("{}/volume-restore", service.workspace_directory());
+    let mut context = service.tera_context(target)?;
+    context.insert("restored_instances", &service.max_instances());
+
+    let _ = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::template::generate_and_copy_all_files_into_dir(restore_chart_dir, workspace_dir.as_str(), &context),
+    )?;
+
+    // not part of the release, the claims must outlive it
+    let _ = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::kubectl::kubectl_exec_apply(
+            kubernetes_config_file_path.as_str(),
+            format!("{}/templates", workspace_dir).as_str(),
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )?;
+
+    deploy_user_application(target, service)
+}
+
+/// replicas to render along with the horizontal pod autoscaler, helm would otherwise reset the workload
+/// to a single replica on each upgrade (including when the autoscaling is enabled on a running application)
+pub fn get_autoscaling_replicas<T>(
+    kubernetes: &dyn Kubernetes,
+    environment: &Environment,
+    service: &T,
+    autoscaling: &Autoscaling,
+) -> Result<u16, EngineError>
+where
+    T: Service + ?Sized,
+{
+    if !autoscaling.is_valid() {
+        return Err(service.engine_error(
+            EngineErrorCause::User(
+                "The minimum number of instances must be at least 1 and can't exceed the maximum number of instances",
+            ),
+            format!(
+                "invalid autoscaling bounds (min {}, max {}) for {}",
+                autoscaling.min_instances,
+                autoscaling.max_instances,
+                service.name_with_id()
+            ),
+        ));
+    }
+
+    let current_replicas = match crate::cmd::kubectl::kubectl_exec_get_workloads(
+        kubernetes.config_file_path()?.as_str(),
+        environment.namespace(),
+        format!("appId={}", service.id()).as_str(),
+        kubernetes.cloud_provider().credentials_environment_variables(),
+    ) {
+        Ok(workloads) => workloads.items.iter().filter_map(|w| w.spec.replicas).max(),
+        Err(err) => {
+            warn!(
+                "can't get the current replicas of {}, the minimum will be deployed: {:?}",
+                service.name_with_id(),
+                err.message
+            );
+            None
+        }
+    };
+
+    Ok(autoscaling.replicas(current_replicas))
+}
+
+/// refuse the routes whose options can't be honored by the ingress controller
+pub fn check_router_routes<T>(service: &T, routes: &[Route]) -> Result<(), EngineError>
+where
+    T: Service + ?Sized,
+{
+    for route in routes {
+        if let Err(message) = route.check() {
+            return Err(service.engine_error(
+                EngineErrorCause::User(message),
+                format!(
+                    "invalid route {} to {} for router {}",
+                    route.path,
+                    route.application_name,
+                    service.name_with_id()
+                ),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// refuse the source ranges and basic auth users of a router which can't be enforced,
+/// the router must never be exposed without the access control requested for it
+pub fn check_router_access_control<T>(
+    service: &T,
+    whitelist_source_ranges: &[String],
+    basic_auth_users: &[BasicAuthUser],
+) -> Result<(), EngineError>
+where
+    T: Service + ?Sized,
+{
+    if let Some(cidr) = whitelist_source_ranges
+        .iter()
+        .find(|cidr| !is_valid_cidr(cidr.as_str()))
+    {
+        return Err(service.engine_error(
+            EngineErrorCause::User("The source ranges allowed to reach the router must be valid CIDR blocks"),
+            format!("invalid source range {} for router {}", cidr, service.name_with_id()),
+        ));
+    }
+
+    if let Some(user) = basic_auth_users.iter().find(|user| !user.is_valid()) {
+        return Err(service.engine_error(
+            EngineErrorCause::User("Basic auth usernames can't be empty nor contain a colon"),
+            format!(
+                "invalid basic auth username {} for router {}",
+                user.username,
+                service.name_with_id()
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// check the certificates brought by the user for custom domains are not expired, cover their domain and match
+/// their private key, before the router uses them
+pub fn check_custom_domain_certificates<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    custom_domains: &[CustomDomain],
+) -> Result<(), EngineError>
+where
+    T: Service + ?Sized,
+{
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+
+    // kept out of the chart directory, helm would embed the private keys into the release
+    let certificates_dir = crate::fs::workspace_directory(
+        service.context().workspace_root_dir(),
+        service.context().execution_id(),
+        format!("certificates/{}", service.id()),
+    );
+
+    let result = custom_domains.iter().try_for_each(|custom_domain| {
+        check_custom_domain_certificate(
+            kubernetes,
+            environment,
+            service,
+            custom_domain,
+            certificates_dir.as_str(),
+        )
+    });
+
+    // the private keys don't outlive their validation
+    let _ = std::fs::remove_dir_all(&certificates_dir);
+
+    result
+}
+
+fn check_custom_domain_certificate<T>(
+    kubernetes: &dyn Kubernetes,
+    environment: &Environment,
+    service: &T,
+    custom_domain: &CustomDomain,
+    certificates_dir: &str,
+) -> Result<(), EngineError>
+where
+    T: Service + ?Sized,
+{
+    let (certificate, private_key) = match &custom_domain.certificate {
+        None => return Ok(()),
+        Some(CustomDomainCertificate::Pem {
+            certificate,
+            private_key,
+        }) => (certificate.clone().into_bytes(), private_key.clone().into_bytes()),
+        Some(CustomDomainCertificate::ExistingSecret { secret_name }) => {
+            let secret_data = cast_simple_error_to_engine_error(
+                service.engine_error_scope(),
+                service.context().execution_id(),
+                crate::cmd::kubectl::kubectl_exec_get_secret_data(
+                    kubernetes.config_file_path()?.as_str(),
+                    environment.namespace(),
+                    secret_name.as_str(),
+                    kubernetes.cloud_provider().credentials_environment_variables(),
+                ),
+            )?;
+
+            match secret_data
+                .as_ref()
+                .and_then(|d| Some((d.get("tls.crt")?, d.get("tls.key")?)))
+            {
+                Some((certificate, private_key)) => (certificate.clone(), private_key.clone()),
+                None => {
+                    return Err(service.engine_error(
+                        EngineErrorCause::User(
+                            "The TLS secret of your custom domain must exist in the namespace of the environment \
+                            and hold the tls.crt and tls.key keys.",
+                        ),
+                        format!(
+                            "secret {} of custom domain {} doesn't exist or isn't a TLS secret",
+                            secret_name, custom_domain.domain
+                        ),
+                    ))
+                }
+            }
+        }
+    };
+
+    let domain_hash = crate::crypto::to_sha1_truncate_16(custom_domain.domain.as_str());
+    let certificate_path = format!("{}/{}.crt", certificates_dir, domain_hash);
+    let private_key_path = format!("{}/{}.key", certificates_dir, domain_hash);
+
+    let _ = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        std::fs::write(certificate_path.as_str(), certificate)
+            .and_then(|_| std::fs::write(private_key_path.as_str(), private_key))
+            .map_err(SimpleError::from),
+    )?;
+
+    let invalid_certificate_error = |message: String| {
+        service.engine_error(
+            EngineErrorCause::User(
+                "The certificate of your custom domain is invalid. It must be PEM encoded, not expired, \
+                cover the custom domain in its subject alternative names and match its private key.",
+            ),
+            message,
+        )
+    };
+
+    let certificate_info = crate::cmd::openssl::openssl_exec_x509_info(certificate_path.as_str()).map_err(|err| {
+        invalid_certificate_error(format!(
+            "can't read the certificate of custom domain {}: {:?}",
+            custom_domain.domain, err.message
+        ))
+    })?;
+
+    if certificate_info.is_expired() {
+        return Err(invalid_certificate_error(format!(
+            "certificate of custom domain {} has expired on {}",
+            custom_domain.domain, certificate_info.not_after
+        )));
+    }
+
+    if !certificate_info.covers_domain(custom_domain.domain.as_str()) {
+        return Err(invalid_certificate_error(format!(
+            "certificate of custom domain {} only covers {}",
+            custom_domain.domain,
+            certificate_info.dns_names.join(", ")
+        )));
+    }
+
+    let certificate_public_key = crate::cmd::openssl::openssl_exec_x509_public_key(certificate_path.as_str());
+    let private_key_public_key = crate::cmd::openssl::openssl_exec_private_key_public_key(private_key_path.as_str());
+
+    match (certificate_public_key, private_key_public_key) {
+        (Ok(certificate_public_key), Ok(private_key_public_key))
+            if certificate_public_key == private_key_public_key => {}
+        _ => {
+            return Err(invalid_certificate_error(format!(
+                "certificate of custom domain {} doesn't match its private key",
+                custom_domain.domain
+            )))
+        }
+    }
+
+    info!(
+        "certificate of custom domain {} is valid until {}",
+        custom_domain.domain, certificate_info.not_after
+    );
+
+    Ok(())
+}
+
+/// apply the TLS secrets of the certificates brought by the user, and delete the ones of the custom domains the router
+/// no longer has. they are applied with kubectl from a temporary directory, out of the chart of the router: helm would
+/// keep the private keys in the release, and the rendered chart is kept with the workspace of the execution
+pub fn apply_custom_domain_certificate_secrets<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    custom_domains: &[CustomDomain],
+) -> Result<(), EngineError>
+where
+    T: Service + ?Sized,
+{
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+
+    let kubernetes_config_file_path = kubernetes.config_file_path()?;
+    let manifests_dir = crate::fs::workspace_directory(
+        service.context().workspace_root_dir(),
+        service.context().execution_id(),
+        format!("certificate-secrets/{}", service.id()),
+    );
+
+    let manifests = custom_domains
+        .iter()
+        .filter_map(|custom_domain| {
+            custom_domain
+                .to_tls_secret_manifest(environment.namespace(), environment.owner_id.as_str(), service.id())
+                .map(|manifest| (custom_domain, manifest))
+        })
+        .collect::<Vec<_>>();
+
+    let result = manifests.iter().try_for_each(|(custom_domain, manifest)| {
+        std::fs::write(
+            format!(
+                "{}/custom-domain-certificate-secret-{}.yaml",
+                manifests_dir,
+                crate::crypto::to_sha1_truncate_16(custom_domain.domain.as_str())
+            ),
+            manifest,
+        )
+        .map_err(SimpleError::from)
+    });
+
+    let result = match result {
+        Ok(_) if !manifests.is_empty() => crate::cmd::kubectl::kubectl_exec_apply(
+            kubernetes_config_file_path.as_str(),
+            manifests_dir.as_str(),
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+        result => result,
+    };
+
+    // the private keys don't outlive their application
+    let _ = std::fs::remove_dir_all(&manifests_dir);
+
+    let _ = cast_simple_error_to_engine_error(service.engine_error_scope(), service.context().execution_id(), result)?;
+
+    let kept_custom_domains = manifests
+        .iter()
+        .map(|(custom_domain, _)| *custom_domain)
+        .collect::<Vec<_>>();
+
+    delete_custom_domain_certificate_secrets(target, service, kept_custom_domains.as_slice())
+}
+
+/// delete the TLS secrets of the certificates brought by the user for the router, except the ones of the given
+/// custom domains
+pub fn delete_custom_domain_certificate_secrets<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    kept_custom_domains: &[&CustomDomain],
+) -> Result<(), EngineError>
+where
+    T: Service + ?Sized,
+{
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+
+    let mut selector = format!("routerId={}", service.id());
+    if !kept_custom_domains.is_empty() {
+        let kept_domain_hashes = kept_custom_domains
+            .iter()
+            .map(|custom_domain| crate::crypto::to_sha1_truncate_16(custom_domain.domain.as_str()))
+            .collect::<Vec<_>>();
+        selector.push_str(format!(",fqdnId notin ({})", kept_domain_hashes.join(",")).as_str());
+    }
+
+    cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::kubectl::kubectl_exec_delete_by_selector(
+            kubernetes.config_file_path()?.as_str(),
+            environment.namespace(),
+            "secret",
+            selector.as_str(),
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )
+}
+
+pub fn delete_stateless_service<T>(target: &DeploymentTarget, service: &T, is_error: bool) -> Result<(), EngineError>
+where
+    T: Service + Helm,
+{
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+
+    let helm_release_name = service.helm_release_name();
+
+    if is_error {
+        let _ = get_stateless_resource_information(kubernetes, environment, service.selector().as_str())?;
+    }
+
+    // clean the resource
+    let _ = do_stateless_service_cleanup(kubernetes, environment, helm_release_name.as_str())?;
+
+    Ok(())
+}
+
+pub fn deploy_stateful_service<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+where
+    T: StatefulService + Helm + Terraform,
+{
+    let workspace_dir = service.workspace_directory();
+
+    match target {
+        DeploymentTarget::ManagedServices(kubernetes, _) => {
+            // use terraform
+            info!(
+                "deploy {} with name {} on {}",
+                service.service_type().name(),
+                service.name_with_id(),
+                kubernetes.cloud_provider().kind().name()
+            );
+
+            let context = service.tera_context(target)?;
+
+            let _ = cast_simple_error_to_engine_error(
+                service.engine_error_scope(),
+                service.context().execution_id(),
+                crate::template::generate_and_copy_all_files_into_dir(
+                    service.terraform_common_resource_dir_path(),
+                    &workspace_dir,
+                    &context,
+                ),
+            )?;
+
+            let _ = cast_simple_error_to_engine_error(
+                service.engine_error_scope(),
+                service.context().execution_id(),
+                crate::template::generate_and_copy_all_files_into_dir(
+                    service.terraform_resource_dir_path(),
+                    workspace_dir.as_str(),
+                    &context,
+                ),
+            )?;
+
+            let _ = cast_simple_error_to_engine_error(
+                service.engine_error_scope(),
+                service.context().execution_id(),
+                crate::template::generate_and_copy_all_files_into_dir(
+                    service.helm_chart_external_name_service_dir(),
+                    format!("{}/{}", workspace_dir, "external-name-svc"),
+                    &context,
+                ),
+            )?;
+
+            let _ = cast_simple_error_to_engine_error(
+                service.engine_error_scope(),
+                service.context().execution_id(),
+                crate::cmd::terraform::terraform_init_validate_plan_apply(
+                    workspace_dir.as_str(),
+                    service.context().is_dry_run_deploy(),
+                ),
+            )?;
+        }
+        DeploymentTarget::SelfHosted(kubernetes, environment) => {
+            // use helm
+            info!(
+                "deploy {} with name {} on {:?} Kubernetes cluster id {}",
+                service.service_type().name(),
+                service.name_with_id(),
+                kubernetes.cloud_provider().kind().name(),
+                kubernetes.id()
+            );
+
+            let context = service.tera_context(target)?;
+            let kubernetes_config_file_path = kubernetes.config_file_path()?;
+
+            // default chart
+            let _ = cast_simple_error_to_engine_error(
+                service.engine_error_scope(),
+                service.context().execution_id(),
+                crate::template::generate_and_copy_all_files_into_dir(
+                    service.helm_chart_dir(),
+                    workspace_dir.as_str(),
+                    &context,
+                ),
+            )?;
+
+            // overwrite with our chart values
+            let _ = cast_simple_error_to_engine_error(
+                service.engine_error_scope(),
+                service.context().execution_id(),
+                crate::template::generate_and_copy_all_files_into_dir(
+                    service.helm_chart_values_dir(),
+                    workspace_dir.as_str(),
+                    &context,
+                ),
+            )?;
+
+            // define labels to add to namespace
+            let namespace_labels = service.context().resource_expiration_in_seconds().map(|_| {
+                vec![
+                    (LabelsContent {
+                        name: "ttl".into(),
+                        value: format!("{}", service.context().resource_expiration_in_seconds().unwrap()),
+                    }),
+                ]
+            });
+
+            // create a namespace with labels if it does not exist
+            let _ = cast_simple_error_to_engine_error(
+                service.engine_error_scope(),
+                service.context().execution_id(),
+                crate::cmd::kubectl::kubectl_exec_create_namespace(
+                    kubernetes_config_file_path.as_str(),
+                    environment.namespace(),
+                    namespace_labels,
+                    kubernetes.cloud_provider().credentials_environment_variables(),
+                ),
+            )?;
+
+            // do exec helm upgrade and return the last deployment status
+            let helm_history_row = cast_simple_error_to_engine_error(
+                service.engine_error_scope(),
+                service.context().execution_id(),
+                crate::cmd::helm::helm_exec_with_upgrade_history(
+                    kubernetes_config_file_path.as_str(),
+                    environment.namespace(),
+                    service.helm_release_name().as_str(),
+                    workspace_dir.as_str(),
+                    service.start_timeout(),
+                    kubernetes.cloud_provider().credentials_environment_variables(),
+                ),
+            )?;
+
+            // check deployment status
+            if helm_history_row.is_none() || !helm_history_row.unwrap().is_successfully_deployed() {
+                return Err(service.engine_error(
+                    EngineErrorCause::Internal,
+                    format!(
+                        "{} service fails to be deployed (before start)",
+                        service.service_type().name()
+                    ),
+                ));
+            }
+
+            // check app status
+            match crate::cmd::kubectl::kubectl_exec_is_pod_ready_with_retry(
+                kubernetes_config_file_path.as_str(),
+                environment.namespace(),
+                service.selector().as_str(),
+                kubernetes.cloud_provider().credentials_environment_variables(),
+            ) {
+                Ok(Some(true)) => {}
+                _ => {
+                    return Err(service.engine_error(
+                        EngineErrorCause::Internal,
+                        format!(
+                            "{} database {} failed to start after several retries",
+                            service.service_type().name(),
+                            service.name_with_id()
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// plan a stateless service (app, router, external service) action with helm diff.
+/// `image` is the container image the service is deployed with, if any
+pub fn plan_stateless_service<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    image: Option<String>,
+) -> Result<ServicePlan, EngineError>
+where
+    T: Service + Helm,
+{
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(k, env) => (*k, *env),
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+
+    let (resources, diff) = match service.action() {
+        Action::Create => {
+            let workspace_dir = service.workspace_directory();
+            let tera_context = service.tera_context(target)?;
+
+            let _ = cast_simple_error_to_engine_error(
+                service.engine_error_scope(),
+                service.context().execution_id(),
+                crate::template::generate_and_copy_all_files_into_dir(
                     service.helm_chart_dir(),
                     workspace_dir.as_str(),
-                    &context,
+                    &tera_context,
+                ),
+            )?;
+
+            plan_helm_release_upgrade(kubernetes, environment, service, workspace_dir.as_str())?
+        }
+        Action::Delete => (plan_helm_release_deletion(kubernetes, environment, service)?, vec![]),
+        Action::Pause | Action::Nothing => (vec![], vec![]),
+    };
+
+    let image = match (service.action(), image) {
+        (Action::Create, Some(image)) => {
+            let deployed_image = get_service_deployed_image(kubernetes, environment, service)?;
+
+            match deployed_image {
+                Some(deployed_image) if image_tag(deployed_image.as_str()) == image_tag(image.as_str()) => None,
+                deployed_image => Some(ImageChange {
+                    from: deployed_image,
+                    to: image,
+                }),
+            }
+        }
+        _ => None,
+    };
+
+    Ok(ServicePlan {
+        id: service.id().to_string(),
+        name: service.name().to_string(),
+        service_type: service.service_type().name().to_string(),
+        resources,
+        image,
+        diff,
+    })
+}
+
+/// plan a stateful service (database) action with terraform plan for managed databases
+/// and helm diff for self-hosted ones
+pub fn plan_stateful_service<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    requested_version: &str,
+) -> Result<ServicePlan, EngineError>
+where
+    T: StatefulService + Helm + Terraform,
+{
+    let workspace_dir = service.workspace_directory();
+    let mut image = None;
+
+    let (resources, diff) = match (target, service.action()) {
+        (DeploymentTarget::ManagedServices(_, _), Action::Create)
+        | (DeploymentTarget::ManagedServices(_, _), Action::Delete) => {
+            let context = service.tera_context(target)?;
+
+            for (from_dir, to_dir) in [
+                (service.terraform_common_resource_dir_path(), workspace_dir.clone()),
+                (service.terraform_resource_dir_path(), workspace_dir.clone()),
+                (
+                    service.helm_chart_external_name_service_dir(),
+                    format!("{}/{}", workspace_dir, "external-name-svc"),
+                ),
+            ] {
+                let _ = cast_simple_error_to_engine_error(
+                    service.engine_error_scope(),
+                    service.context().execution_id(),
+                    crate::template::generate_and_copy_all_files_into_dir(from_dir, to_dir, &context),
+                )?;
+            }
+
+            let diff = cast_simple_error_to_engine_error(
+                service.engine_error_scope(),
+                service.context().execution_id(),
+                crate::cmd::terraform::terraform_init_validate_plan(
+                    workspace_dir.as_str(),
+                    *service.action() == Action::Delete,
+                ),
+            )?;
+
+            (resource_changes_from_terraform_plan(&diff), diff)
+        }
+        (DeploymentTarget::SelfHosted(kubernetes, environment), Action::Create) => {
+            let context = service.tera_context(target)?;
+
+            // default chart, overwritten with our chart values
+            for from_dir in [service.helm_chart_dir(), service.helm_chart_values_dir()] {
+                let _ = cast_simple_error_to_engine_error(
+                    service.engine_error_scope(),
+                    service.context().execution_id(),
+                    crate::template::generate_and_copy_all_files_into_dir(from_dir, workspace_dir.as_str(), &context),
+                )?;
+            }
+
+            // a version change means a new image for the database container
+            if let Some(deployed_image) = get_service_deployed_image(*kubernetes, *environment, service)? {
+                if image_tag(deployed_image.as_str()) != requested_version {
+                    let repository = deployed_image.rsplitn(2, ':').last().unwrap_or_default();
+
+                    image = Some(ImageChange {
+                        to: format!("{}:{}", repository, requested_version),
+                        from: Some(deployed_image),
+                    });
+                }
+            }
+
+            plan_helm_release_upgrade(*kubernetes, *environment, service, workspace_dir.as_str())?
+        }
+        (DeploymentTarget::SelfHosted(kubernetes, environment), Action::Delete) => {
+            (plan_helm_release_deletion(*kubernetes, *environment, service)?, vec![])
+        }
+        _ => (vec![], vec![]),
+    };
+
+    Ok(ServicePlan {
+        id: service.id().to_string(),
+        name: service.name().to_string(),
+        service_type: service.service_type().name().to_string(),
+        resources,
+        image,
+        diff,
+    })
+}
+
+fn plan_helm_release_upgrade<T>(
+    kubernetes: &dyn Kubernetes,
+    environment: &Environment,
+    service: &T,
+    chart_root_dir: &str,
+) -> Result<(Vec<ResourceChange>, Vec<String>), EngineError>
+where
+    T: Service + Helm,
+{
+    let diff = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::helm::helm_exec_diff(
+            kubernetes.config_file_path()?.as_str(),
+            environment.namespace(),
+            service.helm_release_name().as_str(),
+            chart_root_dir,
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )?;
+
+    Ok((resource_changes_from_helm_diff(&diff), diff))
+}
+
+/// deleting a service uninstalls its helm release, if it has been deployed
+fn plan_helm_release_deletion<T>(
+    kubernetes: &dyn Kubernetes,
+    environment: &Environment,
+    service: &T,
+) -> Result<Vec<ResourceChange>, EngineError>
+where
+    T: Service + Helm,
+{
+    let releases = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::helm::helm_list(
+            kubernetes.config_file_path()?.as_str(),
+            kubernetes.cloud_provider().credentials_environment_variables(),
+            Some(environment.namespace()),
+        ),
+    )?;
+
+    let helm_release_name = service.helm_release_name();
+
+    Ok(releases
+        .into_iter()
+        .filter(|release| release.name == helm_release_name)
+        .map(|release| ResourceChange {
+            kind: ResourceChangeKind::Deleted,
+            resource: format!("release/{}", release.name),
+        })
+        .collect())
+}
+
+fn image_tag(image: &str) -> &str {
+    image.rsplit(':').next().unwrap_or_default()
+}
+
+pub fn delete_stateful_service<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+where
+    T: StatefulService + Helm + Terraform,
+{
+    match target {
+        DeploymentTarget::ManagedServices(kubernetes, environment) => {
+            let workspace_dir = service.workspace_directory();
+            let tera_context = service.tera_context(target)?;
+
+            let _ = cast_simple_error_to_engine_error(
+                service.engine_error_scope(),
+                service.context().execution_id(),
+                crate::template::generate_and_copy_all_files_into_dir(
+                    service.terraform_common_resource_dir_path(),
+                    workspace_dir.as_str(),
+                    &tera_context,
+                ),
+            )?;
+
+            let _ = cast_simple_error_to_engine_error(
+                service.engine_error_scope(),
+                service.context().execution_id(),
+                crate::template::generate_and_copy_all_files_into_dir(
+                    service.terraform_resource_dir_path(),
+                    workspace_dir.as_str(),
+                    &tera_context,
+                ),
+            )?;
+
+            let _ = cast_simple_error_to_engine_error(
+                service.engine_error_scope(),
+                service.context().execution_id(),
+                crate::template::generate_and_copy_all_files_into_dir(
+                    service.helm_chart_external_name_service_dir(),
+                    format!("{}/{}", workspace_dir, "external-name-svc"),
+                    &tera_context,
                 ),
             )?;
 
-            // overwrite with our chart values
-            let _ = cast_simple_error_to_engine_error(
-                service.engine_error_scope(),
-                service.context().execution_id(),
-                crate::template::generate_and_copy_all_files_into_dir(
-                    service.helm_chart_values_dir(),
-                    workspace_dir.as_str(),
-                    &context,
-                ),
-            )?;
+            let _ = cast_simple_error_to_engine_error(
+                service.engine_error_scope(),
+                service.context().execution_id(),
+                crate::template::generate_and_copy_all_files_into_dir(
+                    service.helm_chart_external_name_service_dir(),
+                    workspace_dir.as_str(),
+                    &tera_context,
+                ),
+            )?;
+
+            match crate::cmd::terraform::terraform_init_validate_destroy(workspace_dir.as_str(), true) {
+                Ok(_) => {
+                    info!("deleting secret containing tfstates");
+                    let _ = delete_terraform_tfstate_secret(
+                        *kubernetes,
+                        environment.namespace(),
+                        &get_tfstate_name(service),
+                    );
+                }
+                Err(e) => {
+                    let message = format!("{:?}", e);
+                    error!("{}", message);
+
+                    return Err(service.engine_error(EngineErrorCause::Internal, message));
+                }
+            }
+        }
+        DeploymentTarget::SelfHosted(kubernetes, environment) => {
+            let helm_release_name = service.helm_release_name();
+
+            // clean the resource
+            let _ = do_stateless_service_cleanup(*kubernetes, *environment, helm_release_name.as_str())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// dump a self-hosted database into the database backups bucket of the cluster.
+/// the backup is named after the execution id.
+pub fn backup_stateful_service<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+where
+    T: StatefulService + Helm,
+{
+    match target {
+        DeploymentTarget::ManagedServices(_, _) => Err(service.engine_error(
+            EngineErrorCause::User("Backups are only available for databases running on Kubernetes for now"),
+            format!(
+                "can't backup {} {}, it is a managed service",
+                service.service_type().name(),
+                service.name_with_id()
+            ),
+        )),
+        DeploymentTarget::SelfHosted(kubernetes, environment) => run_database_backup_job(
+            target,
+            *kubernetes,
+            *environment,
+            service,
+            DatabaseBackupAction::Backup,
+            database_backup_object_key(service.id(), service.context().execution_id()).as_str(),
+        ),
+    }
+}
+
+/// restore a self-hosted database from a backup stored into the database backups bucket of the cluster.
+/// nothing is done if there is no restore source.
+pub fn restore_stateful_service<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    restore_from: Option<&DatabaseRestoreSource>,
+) -> Result<(), EngineError>
+where
+    T: StatefulService + Helm,
+{
+    let (database_id, backup_name) = match restore_from {
+        Some(DatabaseRestoreSource::Backup {
+            database_id,
+            backup_name,
+        }) => (database_id, backup_name),
+        Some(_) => {
+            return Err(service.engine_error(
+                EngineErrorCause::User("Snapshots and point in time restores are only available for managed databases"),
+                format!(
+                    "can't restore {} {} from a snapshot, it is not a managed service",
+                    service.service_type().name(),
+                    service.name_with_id()
+                ),
+            ))
+        }
+        None => {
+            info!("nothing to restore for {}", service.name_with_id());
+            return Ok(());
+        }
+    };
+
+    match target {
+        DeploymentTarget::ManagedServices(_, _) => Err(service.engine_error(
+            EngineErrorCause::User("Restores are only available for databases running on Kubernetes for now"),
+            format!(
+                "can't restore {} {}, it is a managed service",
+                service.service_type().name(),
+                service.name_with_id()
+            ),
+        )),
+        DeploymentTarget::SelfHosted(kubernetes, environment) => run_database_backup_job(
+            target,
+            *kubernetes,
+            *environment,
+            service,
+            DatabaseBackupAction::Restore,
+            database_backup_object_key(database_id, backup_name).as_str(),
+        ),
+    }
+}
+
+/// seed a self-hosted database, on its first deployment, with the data of the database having the same name
+/// in the environment it is cloned from. the database is deployed to be restored.
+pub fn clone_stateful_service<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+where
+    T: StatefulService + Helm + Terraform,
+{
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(_, _) => {
+            return Err(service.engine_error(
+                EngineErrorCause::Internal,
+                format!("{} is a managed service", service.name_with_id()),
+            ))
+        }
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+
+    let source_namespace = match environment.clone_from_namespace() {
+        Some(namespace) => namespace,
+        None => return Ok(()),
+    };
+
+    let kubernetes_config_file_path = kubernetes.config_file_path()?;
+    let is_running_in = |namespace: &str| {
+        cast_simple_error_to_engine_error(
+            service.engine_error_scope(),
+            service.context().execution_id(),
+            crate::cmd::kubectl::kubectl_exec_get_pod(
+                kubernetes_config_file_path.as_str(),
+                namespace,
+                service.selector().as_str(),
+                kubernetes.cloud_provider().credentials_environment_variables(),
+            ),
+        )
+        .map(|pods| !pods.items.is_empty())
+    };
+
+    if is_running_in(environment.namespace())? {
+        info!("{} already exists, it is not cloned again", service.name_with_id());
+        return Ok(());
+    }
+
+    if !is_running_in(source_namespace.as_str())? {
+        info!(
+            "there is no {} named {} in namespace {}, nothing to clone",
+            service.service_type().name(),
+            service.name(),
+            source_namespace
+        );
+        return Ok(());
+    }
+
+    let object_key = database_backup_object_key(
+        service.id(),
+        format!("clone-{}", service.context().execution_id()).as_str(),
+    );
+
+    let _ = run_database_backup_job(
+        target,
+        kubernetes,
+        environment,
+        service,
+        DatabaseBackupAction::Clone,
+        object_key.as_str(),
+    )?;
+
+    let _ = deploy_stateful_service(target, service)?;
+
+    run_database_backup_job(
+        target,
+        kubernetes,
+        environment,
+        service,
+        DatabaseBackupAction::Restore,
+        object_key.as_str(),
+    )
+}
+
+/// migrate a self-hosted database to a newer major version: the data is dumped, the database is recreated
+/// with the requested version, then the dump is restored. other version changes are regular deployments.
+pub fn upgrade_stateful_service<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    requested_version: &str,
+) -> Result<(), EngineError>
+where
+    T: StatefulService + Helm + Terraform,
+{
+    migrate_stateful_service_major_version(target, service, requested_version, Ordering::Less)
+}
+
+/// migrate a self-hosted database to an older major version, the same way as an upgrade.
+/// only MongoDB can restore a dump taken from a newer major version, other downgrades are refused.
+pub fn downgrade_stateful_service<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    requested_version: &str,
+) -> Result<(), EngineError>
+where
+    T: StatefulService + Helm + Terraform,
+{
+    migrate_stateful_service_major_version(target, service, requested_version, Ordering::Greater)
+}
+
+fn migrate_stateful_service_major_version<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    requested_version: &str,
+    // how the deployed version compares to the requested one
+    migration: Ordering,
+) -> Result<(), EngineError>
+where
+    T: StatefulService + Helm + Terraform,
+{
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(_, _) => {
+            return Err(service.engine_error(
+                EngineErrorCause::Internal,
+                format!("{} is a managed service", service.name_with_id()),
+            ))
+        }
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
+
+    let database_kind = match service.service_type() {
+        ServiceType::Database(DatabaseType::PostgreSQL(_)) => DatabaseKind::Postgresql,
+        ServiceType::Database(DatabaseType::MySQL(_)) => DatabaseKind::Mysql,
+        ServiceType::Database(DatabaseType::MongoDB(_)) => DatabaseKind::Mongodb,
+        ServiceType::Database(DatabaseType::Redis(_)) => DatabaseKind::Redis,
+        _ => {
+            return Err(service.engine_error(
+                EngineErrorCause::Internal,
+                format!("{} is not a database", service.name_with_id()),
+            ))
+        }
+    };
+
+    let deployed_version = match get_stateful_service_deployed_version(kubernetes, environment.namespace(), service)? {
+        Some(version) => version,
+        None => return Ok(()),
+    };
+
+    match compare_database_major_versions(&database_kind, deployed_version.as_str(), requested_version) {
+        Ok(ordering) if ordering == migration => {}
+        Ok(_) => return Ok(()),
+        Err(err) => return Err(service.engine_error(EngineErrorCause::Internal, err)),
+    }
+
+    if migration == Ordering::Greater && database_kind != DatabaseKind::Mongodb {
+        return Err(service.engine_error(
+            EngineErrorCause::User(
+                "Downgrading the major version of a database is only possible with MongoDB, \
+                restore a backup taken with the requested version instead",
+            ),
+            format!(
+                "can't downgrade {} {} from version {} to {}",
+                service.service_type().name(),
+                service.name_with_id(),
+                deployed_version,
+                requested_version
+            ),
+        ));
+    }
+
+    // the database is only deleted and restored by a real deployment
+    if service.context().is_dry_run_deploy() {
+        info!(
+            "{} {} would be migrated from version {} to {}",
+            service.service_type().name(),
+            service.name_with_id(),
+            deployed_version,
+            requested_version
+        );
+        return Ok(());
+    }
+
+    let listeners_helper = ListenersHelper::new(kubernetes.listeners());
+
+    listeners_helper.deployment_in_progress(ProgressInfo::new(
+        service.progress_scope(),
+        ProgressLevel::Info,
+        Some(format!(
+            "{} '{}' is migrated from version {} to {}, it is unavailable until its data is restored",
+            service.service_type().name(),
+            service.name_with_id(),
+            deployed_version,
+            requested_version
+        )),
+        service.context().execution_id(),
+    ));
+
+    // the dump is kept in the backups bucket, in case the restore fails
+    let object_key = database_backup_object_key(
+        service.id(),
+        format!("migration-{}-{}", deployed_version, service.context().execution_id()).as_str(),
+    );
+
+    let _ = run_database_backup_job(
+        target,
+        kubernetes,
+        environment,
+        service,
+        DatabaseBackupAction::Backup,
+        object_key.as_str(),
+    )?;
+
+    let _ = delete_stateful_service_and_volumes(kubernetes, environment, service)?;
+
+    let result = deploy_stateful_service(target, service).and_then(|_| {
+        run_database_backup_job(
+            target,
+            kubernetes,
+            environment,
+            service,
+            DatabaseBackupAction::Restore,
+            object_key.as_str(),
+        )
+    });
+
+    // the previous database is gone, its data is only left in the dump
+    if let Err(err) = result {
+        let message = format!(
+            "{} '{}' has been deleted but its data can't be restored into version {}, \
+            its data is kept in the dump {} of the database backups bucket: {:?}",
+            service.service_type().name(),
+            service.name_with_id(),
+            requested_version,
+            object_key,
+            err.message
+        );
+
+        listeners_helper.error(ProgressInfo::new(
+            service.progress_scope(),
+            ProgressLevel::Error,
+            Some(message.clone()),
+            service.context().execution_id(),
+        ));
+
+        return Err(service.engine_error(
+            EngineErrorCause::User(
+                "Your database has been deleted to be migrated but its data can't be restored. \
+                It is kept in a dump of the database backups bucket, named in the deployment logs: restore it from this backup",
+            ),
+            message,
+        ));
+    }
+
+    Ok(())
+}
+
+/// version of the image the self-hosted database is running in the namespace, if it is deployed
+fn get_stateful_service_deployed_version<T>(
+    kubernetes: &dyn Kubernetes,
+    namespace: &str,
+    service: &T,
+) -> Result<Option<String>, EngineError>
+where
+    T: StatefulService,
+{
+    let image = get_service_image_in_namespace(kubernetes, namespace, service)?;
+
+    Ok(image.map(|image| image_tag(image.as_str()).to_string()))
+}
+
+/// image of the main container of the service, if it is deployed
+pub fn get_service_deployed_image<T>(
+    kubernetes: &dyn Kubernetes,
+    environment: &Environment,
+    service: &T,
+) -> Result<Option<String>, EngineError>
+where
+    T: Service + ?Sized,
+{
+    get_service_image_in_namespace(kubernetes, environment.namespace(), service)
+}
+
+fn get_service_image_in_namespace<T>(
+    kubernetes: &dyn Kubernetes,
+    namespace: &str,
+    service: &T,
+) -> Result<Option<String>, EngineError>
+where
+    T: Service + ?Sized,
+{
+    let pods = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::kubectl::kubectl_exec_get_pod(
+            kubernetes.config_file_path()?.as_str(),
+            namespace,
+            service.selector().as_str(),
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )?;
+
+    // the main container comes first, before the sidecars (e.g. database metrics)
+    Ok(pods
+        .items
+        .iter()
+        .filter_map(|pod| pod.spec.as_ref())
+        .filter_map(|spec| spec.containers.first())
+        .map(|container| container.image.clone())
+        .next())
+}
 
-            // define labels to add to namespace
-            let namespace_labels = service.context().resource_expiration_in_seconds().map(|_| {
-                vec![
-                    (LabelsContent {
-                        name: "ttl".into(),
-                        value: format!("{}", service.context().resource_expiration_in_seconds().unwrap()),
-                    }),
-                ]
-            });
+/// uninstall the chart of a self-hosted database and delete its volumes, which helm keeps
+fn delete_stateful_service_and_volumes<T>(
+    kubernetes: &dyn Kubernetes,
+    environment: &Environment,
+    service: &T,
+) -> Result<(), EngineError>
+where
+    T: StatefulService + Helm,
+{
+    let kubernetes_config_file_path = kubernetes.config_file_path()?;
 
-            // create a namespace with labels if it does not exist
-            let _ = cast_simple_error_to_engine_error(
-                service.engine_error_scope(),
-                service.context().execution_id(),
-                crate::cmd::kubectl::kubectl_exec_create_namespace(
-                    kubernetes_config_file_path.as_str(),
-                    environment.namespace(),
-                    namespace_labels,
-                    kubernetes.cloud_provider().credentials_environment_variables(),
-                ),
-            )?;
+    let pods = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::kubectl::kubectl_exec_get_pod(
+            kubernetes_config_file_path.as_str(),
+            environment.namespace(),
+            service.selector().as_str(),
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )?;
 
-            // do exec helm upgrade and return the last deployment status
-            let helm_history_row = cast_simple_error_to_engine_error(
-                service.engine_error_scope(),
-                service.context().execution_id(),
-                crate::cmd::helm::helm_exec_with_upgrade_history(
-                    kubernetes_config_file_path.as_str(),
-                    environment.namespace(),
-                    service.helm_release_name().as_str(),
-                    workspace_dir.as_str(),
-                    service.start_timeout(),
-                    kubernetes.cloud_provider().credentials_environment_variables(),
-                ),
-            )?;
+    let claim_names = pods
+        .items
+        .iter()
+        .filter_map(|pod| pod.spec.as_ref())
+        .flat_map(|spec| spec.volumes.iter())
+        .filter_map(|volume| volume.persistent_volume_claim.as_ref())
+        .map(|claim| claim.claim_name.clone())
+        .collect::<Vec<_>>();
 
-            // check deployment status
-            if helm_history_row.is_none() || !helm_history_row.unwrap().is_successfully_deployed() {
-                return Err(service.engine_error(
-                    EngineErrorCause::Internal,
-                    format!(
-                        "{} service fails to be deployed (before start)",
-                        service.service_type().name()
-                    ),
-                ));
-            }
+    let _ = do_stateless_service_cleanup(kubernetes, environment, service.helm_release_name().as_str())?;
 
-            // check app status
-            match crate::cmd::kubectl::kubectl_exec_is_pod_ready_with_retry(
+    for claim_name in claim_names {
+        let _ = cast_simple_error_to_engine_error(
+            service.engine_error_scope(),
+            service.context().execution_id(),
+            crate::cmd::kubectl::kubectl_exec_delete_pvc(
                 kubernetes_config_file_path.as_str(),
                 environment.namespace(),
-                service.selector().as_str(),
+                claim_name.as_str(),
                 kubernetes.cloud_provider().credentials_environment_variables(),
-            ) {
-                Ok(Some(true)) => {}
-                _ => {
-                    return Err(service.engine_error(
-                        EngineErrorCause::Internal,
-                        format!(
-                            "{} database {} failed to start after several retries",
-                            service.service_type().name(),
-                            service.name_with_id()
-                        ),
-                    ));
-                }
-            }
-        }
+            ),
+        )?;
     }
 
     Ok(())
 }
 
-pub fn delete_stateful_service<T>(target: &DeploymentTarget, service: &T) -> Result<(), EngineError>
+/// remove the backup (or restore) job left behind by a failure
+pub fn delete_database_backup_job<T>(
+    target: &DeploymentTarget,
+    service: &T,
+    action: DatabaseBackupAction,
+) -> Result<(), EngineError>
 where
-    T: StatefulService + Helm + Terraform,
+    T: StatefulService + Helm,
 {
-    match target {
-        DeploymentTarget::ManagedServices(kubernetes, environment) => {
-            let workspace_dir = service.workspace_directory();
-            let tera_context = service.tera_context(target)?;
+    let (kubernetes, environment) = match target {
+        DeploymentTarget::ManagedServices(_, _) => return Ok(()),
+        DeploymentTarget::SelfHosted(k, env) => (*k, *env),
+    };
 
-            let _ = cast_simple_error_to_engine_error(
-                service.engine_error_scope(),
-                service.context().execution_id(),
-                crate::template::generate_and_copy_all_files_into_dir(
-                    service.terraform_common_resource_dir_path(),
-                    workspace_dir.as_str(),
-                    &tera_context,
-                ),
-            )?;
+    let namespace = match action.namespace(environment) {
+        Some(namespace) => namespace,
+        None => return Ok(()),
+    };
 
-            let _ = cast_simple_error_to_engine_error(
-                service.engine_error_scope(),
-                service.context().execution_id(),
-                crate::template::generate_and_copy_all_files_into_dir(
-                    service.terraform_resource_dir_path(),
-                    workspace_dir.as_str(),
-                    &tera_context,
-                ),
-            )?;
+    cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::helm::helm_exec_uninstall(
+            kubernetes.config_file_path()?.as_str(),
+            namespace.as_str(),
+            database_backup_job_name(service, &action).as_str(),
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )
+}
 
-            let _ = cast_simple_error_to_engine_error(
-                service.engine_error_scope(),
-                service.context().execution_id(),
-                crate::template::generate_and_copy_all_files_into_dir(
-                    service.helm_chart_external_name_service_dir(),
-                    format!("{}/{}", workspace_dir, "external-name-svc"),
-                    &tera_context,
-                ),
-            )?;
+pub fn database_backup_object_key(database_id: &str, backup_name: &str) -> String {
+    format!("{}/{}.dump", database_id, backup_name)
+}
 
-            let _ = cast_simple_error_to_engine_error(
-                service.engine_error_scope(),
-                service.context().execution_id(),
-                crate::template::generate_and_copy_all_files_into_dir(
-                    service.helm_chart_external_name_service_dir(),
-                    workspace_dir.as_str(),
-                    &tera_context,
-                ),
-            )?;
+pub enum DatabaseBackupAction {
+    Backup,
+    Restore,
+    // dump of the database with the same name in the environment being cloned
+    Clone,
+}
 
-            match crate::cmd::terraform::terraform_init_validate_destroy(workspace_dir.as_str(), true) {
-                Ok(_) => {
-                    info!("deleting secret containing tfstates");
-                    let _ = delete_terraform_tfstate_secret(
-                        *kubernetes,
-                        environment.namespace(),
-                        &get_tfstate_name(service),
-                    );
-                }
-                Err(e) => {
-                    let message = format!("{:?}", e);
-                    error!("{}", message);
+impl DatabaseBackupAction {
+    fn name(&self) -> &str {
+        match self {
+            DatabaseBackupAction::Backup => "backup",
+            DatabaseBackupAction::Restore => "restore",
+            DatabaseBackupAction::Clone => "clone",
+        }
+    }
 
-                    return Err(service.engine_error(EngineErrorCause::Internal, message));
-                }
-            }
+    fn is_dump(&self) -> bool {
+        match self {
+            DatabaseBackupAction::Backup | DatabaseBackupAction::Clone => true,
+            DatabaseBackupAction::Restore => false,
         }
-        DeploymentTarget::SelfHosted(kubernetes, environment) => {
-            let helm_release_name = service.helm_release_name();
+    }
 
-            // clean the resource
-            let _ = do_stateless_service_cleanup(*kubernetes, *environment, helm_release_name.as_str())?;
+    /// the clone job runs in the namespace of the cloned environment, to reach its database and credentials
+    fn namespace(&self, environment: &Environment) -> Option<String> {
+        match self {
+            DatabaseBackupAction::Clone => environment.clone_from_namespace(),
+            DatabaseBackupAction::Backup | DatabaseBackupAction::Restore => Some(environment.namespace().to_string()),
         }
     }
+}
 
-    Ok(())
+fn database_backup_job_name<T>(service: &T, action: &DatabaseBackupAction) -> String
+where
+    T: Service + ?Sized,
+{
+    crate::string::cut(format!("{}-{}", action.name(), service.id()), 50)
+}
+
+// dumps and restores of large databases can take a while
+const DATABASE_BACKUP_JOB_MAX_DURATION_IN_SECONDS: u32 = 3600;
+// the dump is uploaded through a presigned URL, in a single request which object storages limit to 5 GiB
+const DATABASE_BACKUP_MAX_SIZE_IN_BYTES: u64 = 5 * 1024 * 1024 * 1024;
+
+/// run the q-database-backup chart as a Kubernetes Job, and wait for its completion.
+/// dumps larger than DATABASE_BACKUP_MAX_SIZE_IN_BYTES can't be uploaded, the job fails once the dump is taken.
+fn run_database_backup_job<T>(
+    target: &DeploymentTarget,
+    kubernetes: &dyn Kubernetes,
+    environment: &Environment,
+    service: &T,
+    action: DatabaseBackupAction,
+    object_key: &str,
+) -> Result<(), EngineError>
+where
+    T: StatefulService + Helm,
+{
+    // database kind matches the bitnami image name, host matches the service name created by the chart,
+    // admin login and password key match the secret created by the chart
+    let (database_kind, database_host, database_name, admin_login, admin_password_key) = match service.service_type() {
+        ServiceType::Database(DatabaseType::PostgreSQL(_)) => (
+            "postgresql",
+            service.sanitized_name(),
+            service.name().to_string(),
+            "postgres",
+            "postgresql-postgres-password",
+        ),
+        ServiceType::Database(DatabaseType::MySQL(_)) => (
+            "mysql",
+            service.sanitized_name(),
+            service.sanitized_name(),
+            "root",
+            "mysql-root-password",
+        ),
+        ServiceType::Database(DatabaseType::MongoDB(_)) => (
+            "mongodb",
+            service.sanitized_name(),
+            service.name().to_string(),
+            "root",
+            "mongodb-root-password",
+        ),
+        ServiceType::Database(DatabaseType::Redis(_)) => (
+            "redis",
+            format!("{}-master", service.sanitized_name()),
+            String::new(),
+            "",
+            "redis-password",
+        ),
+        _ => {
+            return Err(service.engine_error(
+                EngineErrorCause::Internal,
+                format!("{} is not a database", service.name_with_id()),
+            ))
+        }
+    };
+
+    let namespace = match action.namespace(environment) {
+        Some(namespace) => namespace,
+        None => {
+            return Err(service.engine_error(
+                EngineErrorCause::Internal,
+                format!(
+                    "can't {} {}, its environment is not a clone",
+                    action.name(),
+                    service.name_with_id()
+                ),
+            ))
+        }
+    };
+
+    let object_storage = kubernetes.config_file_store();
+    let job_name = database_backup_job_name(service, &action);
+    let workspace_dir = format!("{}/{}", service.workspace_directory(), job_name);
+    let kubernetes_config_file_path = kubernetes.config_file_path()?;
+
+    let mut context = service.tera_context(target)?;
+    context.insert("namespace", namespace.as_str());
+    context.insert("backup_action", if action.is_dump() { "backup" } else { "restore" });
+    if let DatabaseBackupAction::Clone = action {
+        // credentials of the cloned database are not known, the admin ones are taken from its chart secret
+        context.insert("backup_credentials_secret", service.sanitized_name().as_str());
+        context.insert("backup_credentials_login", admin_login);
+        context.insert("backup_credentials_password_key", admin_password_key);
+    }
+    context.insert("backup_job_name", job_name.as_str());
+    context.insert(
+        "backup_job_max_duration_in_seconds",
+        &DATABASE_BACKUP_JOB_MAX_DURATION_IN_SECONDS,
+    );
+    context.insert("backup_database_kind", database_kind);
+    context.insert("backup_database_host", database_host.as_str());
+    context.insert("backup_database_name", database_name.as_str());
+    context.insert("backup_max_size_in_bytes", &DATABASE_BACKUP_MAX_SIZE_IN_BYTES);
+    // the dump is taken with the version of the running database, an older client refuses to dump a newer server
+    // (e.g. before a downgrade). the restore is done with the version being deployed
+    if action.is_dump() {
+        if let Some(version) = get_stateful_service_deployed_version(kubernetes, namespace.as_str(), service)? {
+            context.insert("backup_database_version", version.as_str());
+        }
+    }
+    // the credentials of the object storage are not given to the namespace, only an URL to the backup which
+    // expires with the job
+    let backup_url = object_storage.presigned_url(
+        kubernetes.region(),
+        kubernetes.database_backups_bucket_name().as_str(),
+        object_key,
+        if action.is_dump() {
+            ObjectAccess::Write
+        } else {
+            ObjectAccess::Read
+        },
+        Duration::from_secs(DATABASE_BACKUP_JOB_MAX_DURATION_IN_SECONDS as u64),
+    )?;
+    context.insert("backup_url", backup_url.as_str());
+
+    let _ = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::template::generate_and_copy_all_files_into_dir(
+            format!("{}/common/services/q-database-backup", service.context().lib_root_dir()),
+            workspace_dir.as_str(),
+            &context,
+        ),
+    )?;
+
+    info!(
+        "{} of {} {} with the dump {} of the object storage bucket {}",
+        action.name(),
+        service.service_type().name(),
+        service.name_with_id(),
+        object_key,
+        kubernetes.database_backups_bucket_name()
+    );
+
+    let _ = cast_simple_error_to_engine_error(
+        service.engine_error_scope(),
+        service.context().execution_id(),
+        crate::cmd::helm::helm_exec_upgrade(
+            kubernetes_config_file_path.as_str(),
+            namespace.as_str(),
+            job_name.as_str(),
+            workspace_dir.as_str(),
+            Timeout::Default,
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+    )?;
+
+    let is_succeeded = matches!(
+        crate::cmd::kubectl::kubectl_exec_wait_for_job_completion(
+            kubernetes_config_file_path.as_str(),
+            namespace.as_str(),
+            job_name.as_str(),
+            DATABASE_BACKUP_JOB_MAX_DURATION_IN_SECONDS,
+            kubernetes.cloud_provider().credentials_environment_variables(),
+        ),
+        Ok(true)
+    );
+
+    // the release is no longer useful once the job is done, its secret holds the database credentials
+    let action_name = action.name().to_string();
+    let deletion_result = delete_database_backup_job(target, service, action);
+
+    if !is_succeeded {
+        return Err(service.engine_error(
+            EngineErrorCause::Internal,
+            format!(
+                "{} job of {} {} did not succeed",
+                action_name,
+                service.service_type().name(),
+                service.name_with_id()
+            ),
+        ));
+    }
+
+    deletion_result
 }
 
 pub fn check_service_version<T>(result: Result<String, StringError>, service: &T) -> Result<String, EngineError>
@@ -1,8 +1,9 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
 use crate::cloud_provider::models::CpuLimits;
 use crate::error::{EngineError, StringError};
-use crate::models::{Listeners, ListenersHelper, ProgressInfo, ProgressLevel, ProgressScope};
+use crate::models::{DatabaseKind, Listeners, ListenersHelper, ProgressInfo, ProgressLevel, ProgressScope};
 use chrono::Duration;
 use core::option::Option::{None, Some};
 use core::result::Result;
@@ -11,6 +12,7 @@ use retry::delay::Fixed;
 use retry::OperationResult;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::net::IpAddr;
 use std::num::ParseFloatError;
 use trust_dns_resolver::config::*;
 use trust_dns_resolver::proto::rr::{RData, RecordType};
@@ -87,6 +89,37 @@ pub fn get_self_hosted_redis_version(requested_version: &str) -> Result<String,
     get_supported_version_to_use("Redis", supported_redis_versions, requested_version)
 }
 
+/// compare two versions of a database on the part its engine considers as the major version:
+/// the first two numbers for MySQL, MongoDB and PostgreSQL before 10, the first one otherwise
+pub fn compare_database_major_versions(
+    database_kind: &DatabaseKind,
+    version: &str,
+    other_version: &str,
+) -> Result<Ordering, StringError> {
+    let major_version = |version: &str| -> Result<(u32, u32), StringError> {
+        let version_number = get_version_number(version)?;
+        let parse = |number: &str| {
+            number
+                .parse::<u32>()
+                .map_err(|_| format!("{} is not a valid database version", version))
+        };
+
+        let major = parse(version_number.major.as_str())?;
+        let minor = match version_number.minor {
+            Some(minor) => parse(minor.as_str())?,
+            None => 0,
+        };
+
+        Ok(match database_kind {
+            DatabaseKind::Postgresql if major < 10 => (major, minor),
+            DatabaseKind::Postgresql | DatabaseKind::Redis => (major, 0),
+            DatabaseKind::Mysql | DatabaseKind::Mongodb => (major, minor),
+        })
+    };
+
+    Ok(major_version(version)?.cmp(&major_version(other_version)?))
+}
+
 pub fn get_supported_version_to_use(
     database_name: &str,
     all_supported_versions: HashMap<String, String>,
@@ -445,6 +478,22 @@ pub fn sanitize_name(prefix: &str, name: &str) -> String {
     format!("{}-{}", prefix, name).replace("_", "-")
 }
 
+/// check a source range is an IPv4 or IPv6 CIDR (E.g: 10.0.0.0/8), as expected by nginx ingress allow-lists
+pub fn is_valid_cidr(cidr: &str) -> bool {
+    let (address, prefix_length) = match cidr.split_once('/') {
+        Some(parts) => parts,
+        None => return false,
+    };
+
+    let max_prefix_length = match address.parse::<IpAddr>() {
+        Ok(IpAddr::V4(_)) => 32,
+        Ok(IpAddr::V6(_)) => 128,
+        Err(_) => return false,
+    };
+
+    matches!(prefix_length.parse::<u8>(), Ok(length) if length <= max_prefix_length)
+}
+
 pub fn convert_k8s_cpu_value_to_f32(value: String) -> Result<f32, ParseFloatError> {
     if value.ends_with('m') {
         let mut value_number_string = value;
@@ -503,11 +552,13 @@ pub fn validate_k8s_required_cpu_and_burstable(
 #[cfg(test)]
 mod tests {
     use crate::cloud_provider::models::CpuLimits;
+    use std::cmp::Ordering;
+
     use crate::cloud_provider::utilities::{
-        cloudflare_dns_resolver, convert_k8s_cpu_value_to_f32, get_cname_record_value,
-        validate_k8s_required_cpu_and_burstable,
+        cloudflare_dns_resolver, compare_database_major_versions, convert_k8s_cpu_value_to_f32, get_cname_record_value,
+        is_valid_cidr, validate_k8s_required_cpu_and_burstable,
     };
-    use crate::models::ListenersHelper;
+    use crate::models::{DatabaseKind, ListenersHelper};
 
     #[test]
     pub fn test_k8s_milli_cpu_convert() {
@@ -548,6 +599,57 @@ mod tests {
         );
     }
 
+    #[test]
+    pub fn test_database_major_versions() {
+        let postgresql = DatabaseKind::Postgresql;
+        assert_eq!(
+            compare_database_major_versions(&postgresql, "12.6.0", "12.2.0").unwrap(),
+            Ordering::Equal
+        );
+        assert_eq!(
+            compare_database_major_versions(&postgresql, "11.11.0", "12").unwrap(),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_database_major_versions(&postgresql, "9.6", "9.5").unwrap(),
+            Ordering::Greater
+        );
+
+        let mysql = DatabaseKind::Mysql;
+        assert_eq!(
+            compare_database_major_versions(&mysql, "8.0.23", "5.7.33").unwrap(),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_database_major_versions(&mysql, "5.7.16", "5.7").unwrap(),
+            Ordering::Equal
+        );
+
+        let mongodb = DatabaseKind::Mongodb;
+        assert_eq!(
+            compare_database_major_versions(&mongodb, "4.2.12", "4.4.4").unwrap(),
+            Ordering::Less
+        );
+
+        let redis = DatabaseKind::Redis;
+        assert_eq!(
+            compare_database_major_versions(&redis, "6.0.9", "6").unwrap(),
+            Ordering::Equal
+        );
+        assert!(compare_database_major_versions(&redis, "latest", "6").is_err());
+    }
+
+    #[test]
+    pub fn test_is_valid_cidr() {
+        assert!(is_valid_cidr("10.0.0.0/8"));
+        assert!(is_valid_cidr("203.0.113.42/32"));
+        assert!(is_valid_cidr("2001:db8::/32"));
+        assert!(!is_valid_cidr("10.0.0.0"));
+        assert!(!is_valid_cidr("10.0.0.0/33"));
+        assert!(!is_valid_cidr("10.0.0/8"));
+        assert!(!is_valid_cidr("10.0.0.0/8,0.0.0.0/0"));
+    }
+
     #[test]
     pub fn test_cname_resolution() {
         let resolver = cloudflare_dns_resolver();
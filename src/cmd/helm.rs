@@ -502,6 +502,51 @@ where
     )
 }
 
+pub fn helm_exec_rollback<P>(
+    kubernetes_config: P,
+    namespace: &str,
+    release_name: &str,
+    revision: u16,
+    timeout: Timeout<u32>,
+    envs: Vec<(&str, &str)>,
+) -> Result<(), SimpleError>
+where
+    P: AsRef<Path>,
+{
+    let timeout_string = format!(
+        "{}s",
+        match timeout {
+            Timeout::Value(v) => v + HELM_DEFAULT_TIMEOUT_IN_SECONDS,
+            Timeout::Default => HELM_DEFAULT_TIMEOUT_IN_SECONDS,
+        }
+    );
+    let revision_string = revision.to_string();
+
+    helm_exec_with_output(
+        vec![
+            "rollback",
+            "--kubeconfig",
+            kubernetes_config.as_ref().to_str().unwrap(),
+            "--namespace",
+            namespace,
+            "--timeout",
+            timeout_string.as_str(),
+            "--wait",
+            release_name,
+            revision_string.as_str(),
+        ],
+        envs,
+        |out| match out {
+            Ok(line) => info!("{}", line.as_str()),
+            Err(err) => error!("{}", err),
+        },
+        |out| match out {
+            Ok(line) => error!("{}", line.as_str()),
+            Err(err) => error!("{}", err),
+        },
+    )
+}
+
 pub fn helm_exec_history<P>(
     kubernetes_config: P,
     namespace: &str,
@@ -747,6 +792,63 @@ where
     Ok(helms_charts)
 }
 
+/// diff between the release currently deployed and the chart it would be upgraded to (helm-diff plugin)
+pub fn helm_exec_diff<P>(
+    kubernetes_config: P,
+    namespace: &str,
+    release_name: &str,
+    chart_root_dir: P,
+    envs: Vec<(&str, &str)>,
+) -> Result<Vec<String>, SimpleError>
+where
+    P: AsRef<Path>,
+{
+    let mut output_lines: Vec<String> = Vec::new();
+    let mut error_lines: Vec<String> = Vec::new();
+
+    let result = helm_exec_with_output(
+        vec![
+            "diff",
+            "upgrade",
+            "--kubeconfig",
+            kubernetes_config.as_ref().to_str().unwrap(),
+            "--namespace",
+            namespace,
+            "--allow-unreleased",
+            "--suppress-secrets",
+            "--no-color",
+            release_name,
+            chart_root_dir.as_ref().to_str().unwrap(),
+        ],
+        envs,
+        |out| match out {
+            Ok(line) => output_lines.push(line),
+            Err(err) => error!("{}", err),
+        },
+        |out| match out {
+            Ok(line) => {
+                error!("{}", line.as_str());
+                error_lines.push(line)
+            }
+            Err(err) => error!("{}", err),
+        },
+    );
+
+    // helm_exec_with_output considers the exit code 1 as a success, but the diff is missing then
+    match result {
+        Ok(_) if error_lines.iter().any(|line| line.starts_with("Error:")) => Err(SimpleError::new(
+            SimpleErrorKind::Other,
+            Some(format!(
+                "can't diff helm release {}: {}",
+                release_name,
+                error_lines.join("\n")
+            )),
+        )),
+        Ok(_) => Ok(output_lines),
+        Err(err) => Err(err),
+    }
+}
+
 pub fn helm_upgrade_diff_with_chart_info<P>(
     kubernetes_config: P,
     envs: &Vec<(String, String)>,
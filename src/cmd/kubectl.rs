@@ -1,16 +1,19 @@
+use std::collections::BTreeMap;
 use std::io::Error;
 use std::path::Path;
 
 use chrono::Duration;
-use retry::delay::Fibonacci;
+use retry::delay::{Fibonacci, Fixed};
 use retry::OperationResult;
 use serde::de::DeserializeOwned;
 
 use crate::cloud_provider::digitalocean::models::svc::DOKubernetesList;
 use crate::cloud_provider::metrics::KubernetesApiMetrics;
 use crate::cmd::structs::{
-    Configmap, Daemonset, Item, KubernetesEvent, KubernetesJob, KubernetesKind, KubernetesList, KubernetesNode,
-    KubernetesPod, KubernetesPodStatusPhase, KubernetesService, KubernetesVersion, LabelsContent,
+    Configmap, Daemonset, Item, KubernetesEvent, KubernetesJob, KubernetesJobStatus, KubernetesKind, KubernetesList,
+    KubernetesNode, KubernetesPersistentVolumeClaim, KubernetesPod, KubernetesPodStatusPhase, KubernetesSecret,
+    KubernetesService, KubernetesServiceDefinition, KubernetesVersion, KubernetesVolumeSnapshot, KubernetesWorkload,
+    LabelsContent,
 };
 use crate::cmd::utilities::exec_with_envs_and_output;
 use crate::constants::KUBECONFIG;
@@ -229,6 +232,117 @@ where
     ))
 }
 
+/// selector of the service, or None if the service does not exist
+pub fn kubectl_exec_get_service_selector<P>(
+    kubernetes_config: P,
+    namespace: &str,
+    service_name: &str,
+    envs: Vec<(&str, &str)>,
+) -> Result<Option<BTreeMap<String, String>>, SimpleError>
+where
+    P: AsRef<Path>,
+{
+    let field_selector = format!("metadata.name={}", service_name);
+
+    let result = kubectl_exec::<P, KubernetesList<KubernetesServiceDefinition>>(
+        vec![
+            "get",
+            "svc",
+            "-o",
+            "json",
+            "-n",
+            namespace,
+            "--field-selector",
+            field_selector.as_str(),
+        ],
+        kubernetes_config,
+        envs,
+    )?;
+
+    Ok(result.items.first().map(|service| service.spec.selector.clone()))
+}
+
+/// annotations of the service, or None if the service does not exist
+pub fn kubectl_exec_get_service_annotations<P>(
+    kubernetes_config: P,
+    namespace: &str,
+    service_name: &str,
+    envs: Vec<(&str, &str)>,
+) -> Result<Option<BTreeMap<String, String>>, SimpleError>
+where
+    P: AsRef<Path>,
+{
+    let field_selector = format!("metadata.name={}", service_name);
+
+    let result = kubectl_exec::<P, KubernetesList<KubernetesServiceDefinition>>(
+        vec![
+            "get",
+            "svc",
+            "-o",
+            "json",
+            "-n",
+            namespace,
+            "--field-selector",
+            field_selector.as_str(),
+        ],
+        kubernetes_config,
+        envs,
+    )?;
+
+    Ok(result.items.first().map(|service| service.metadata.annotations.clone()))
+}
+
+/// decoded data of a secret, none if the secret doesn't exist
+pub fn kubectl_exec_get_secret_data<P>(
+    kubernetes_config: P,
+    namespace: &str,
+    secret_name: &str,
+    envs: Vec<(&str, &str)>,
+) -> Result<Option<BTreeMap<String, Vec<u8>>>, SimpleError>
+where
+    P: AsRef<Path>,
+{
+    let field_selector = format!("metadata.name={}", secret_name);
+
+    let result = kubectl_exec::<P, KubernetesList<KubernetesSecret>>(
+        vec![
+            "get",
+            "secrets",
+            "-o",
+            "json",
+            "-n",
+            namespace,
+            "--field-selector",
+            field_selector.as_str(),
+        ],
+        kubernetes_config,
+        envs,
+    )?;
+
+    let secret = match result.items.first() {
+        Some(secret) => secret,
+        None => return Ok(None),
+    };
+
+    let mut data = BTreeMap::new();
+    for (key, value) in &secret.data {
+        match base64::decode(value) {
+            Ok(decoded_value) => data.insert(key.clone(), decoded_value),
+            Err(err) => {
+                return Err(SimpleError::new(
+                    SimpleErrorKind::Other,
+                    Some(format!(
+                        "can't decode the key {} of the secret {}: {}",
+                        key, secret_name, err
+                    )),
+                ))
+            }
+        };
+    }
+
+    Ok(Some(data))
+}
+
 pub fn kubectl_exec_is_pod_ready_with_retry<P>(
     kubernetes_config: P,
     namespace: &str,
@@ -352,6 +466,47 @@ where
     }
 }
 
+/// wait for a job to be over, for at most its active deadline: the job fails on its own once the deadline is
+/// exceeded, waiting longer is pointless. Returns whether the job has succeeded.
+pub fn kubectl_exec_wait_for_job_completion<P>(
+    kubernetes_config: P,
+    namespace: &str,
+    job_name: &str,
+    active_deadline_in_seconds: u32,
+    envs: Vec<(&str, &str)>,
+) -> Result<bool, SimpleError>
+where
+    P: AsRef<Path>,
+{
+    // some room for the pod to be scheduled and for the status of the job to be updated
+    let polling_interval_in_seconds = 10;
+    let max_polls = (active_deadline_in_seconds + 120) / polling_interval_in_seconds;
+
+    let result = retry::retry(
+        Fixed::from_millis(polling_interval_in_seconds as u64 * 1000).take(max_polls as usize),
+        || match kubectl_exec_get_job_status(kubernetes_config.as_ref(), namespace, job_name, envs.clone()) {
+            Ok(status) => match status.is_succeeded() {
+                Some(is_succeeded) => OperationResult::Ok(is_succeeded),
+                None => {
+                    info!("job {} is not completed yet", job_name);
+                    OperationResult::Retry(format!("job {} is not completed yet", job_name))
+                }
+            },
+            // the job may not be created yet
+            Err(err) => OperationResult::Retry(format!("command error: {:?}", err)),
+        },
+    );
+
+    match result {
+        Ok(is_succeeded) => Ok(is_succeeded),
+        Err(retry::Error::Operation { error, .. }) => {
+            warn!("job {} has not completed in time: {}", job_name, error);
+            Ok(false)
+        }
+        Err(retry::Error::Internal(err)) => Err(SimpleError::new(SimpleErrorKind::Other, Some(err))),
+    }
+}
+
 pub fn kubectl_exec_is_job_ready<P>(
     kubernetes_config: P,
     namespace: &str,
@@ -374,6 +529,24 @@ where
     Ok(Some(false))
 }
 
+pub fn kubectl_exec_get_job_status<P>(
+    kubernetes_config: P,
+    namespace: &str,
+    job_name: &str,
+    envs: Vec<(&str, &str)>,
+) -> Result<KubernetesJobStatus, SimpleError>
+where
+    P: AsRef<Path>,
+{
+    let job_result = kubectl_exec::<P, KubernetesJob>(
+        vec!["get", "job", "-o", "json", "-n", namespace, job_name],
+        kubernetes_config,
+        envs,
+    )?;
+
+    Ok(job_result.status)
+}
+
 pub fn kubectl_exec_is_namespace_present<P>(kubernetes_config: P, namespace: &str, envs: Vec<(&str, &str)>) -> bool
 where
     P: AsRef<Path>,
@@ -639,6 +812,215 @@ where
     Ok(())
 }
 
+pub fn kubectl_exec_delete_pvc<P>(
+    kubernetes_config: P,
+    namespace: &str,
+    pvc: &str,
+    envs: Vec<(&str, &str)>,
+) -> Result<(), SimpleError>
+where
+    P: AsRef<Path>,
+{
+    let mut _envs = Vec::with_capacity(envs.len() + 1);
+    _envs.push((KUBECONFIG, kubernetes_config.as_ref().to_str().unwrap()));
+    _envs.extend(envs);
+
+    let _ = kubectl_exec_with_output(
+        vec!["-n", namespace, "delete", "pvc", pvc, "--ignore-not-found"],
+        _envs,
+        |out| match out {
+            Ok(line) => info!("{}", line),
+            Err(err) => error!("{:?}", err),
+        },
+        |out| match out {
+            Ok(line) => error!("{}", line),
+            Err(err) => error!("{:?}", err),
+        },
+    )?;
+
+    Ok(())
+}
+
+pub fn kubectl_exec_get_pvc<P>(
+    kubernetes_config: P,
+    namespace: &str,
+    selector: &str,
+    envs: Vec<(&str, &str)>,
+) -> Result<KubernetesList<KubernetesPersistentVolumeClaim>, SimpleError>
+where
+    P: AsRef<Path>,
+{
+    kubectl_exec::<P, KubernetesList<KubernetesPersistentVolumeClaim>>(
+        vec!["get", "pvc", "-o", "json", "-n", namespace, "-l", selector],
+        kubernetes_config,
+        envs,
+    )
+}
+
+pub fn kubectl_exec_delete_pvc_by_selector<P>(
+    kubernetes_config: P,
+    namespace: &str,
+    selector: &str,
+    envs: Vec<(&str, &str)>,
+) -> Result<(), SimpleError>
+where
+    P: AsRef<Path>,
+{
+    let mut _envs = Vec::with_capacity(envs.len() + 1);
+    _envs.push((KUBECONFIG, kubernetes_config.as_ref().to_str().unwrap()));
+    _envs.extend(envs);
+
+    let _ = kubectl_exec_with_output(
+        vec!["-n", namespace, "delete", "pvc", "-l", selector, "--wait=true"],
+        _envs,
+        |out| match out {
+            Ok(line) => info!("{}", line),
+            Err(err) => error!("{:?}", err),
+        },
+        |out| match out {
+            Ok(line) => error!("{}", line),
+            Err(err) => error!("{:?}", err),
+        },
+    )?;
+
+    Ok(())
+}
+
+pub fn kubectl_exec_delete_by_selector<P>(
+    kubernetes_config: P,
+    namespace: &str,
+    object_kinds: &str,
+    selector: &str,
+    envs: Vec<(&str, &str)>,
+) -> Result<(), SimpleError>
+where
+    P: AsRef<Path>,
+{
+    let mut _envs = Vec::with_capacity(envs.len() + 1);
+    _envs.push((KUBECONFIG, kubernetes_config.as_ref().to_str().unwrap()));
+    _envs.extend(envs);
+
+    let _ = kubectl_exec_with_output(
+        vec!["-n", namespace, "delete", object_kinds, "-l", selector],
+        _envs,
+        |out| match out {
+            Ok(line) => info!("{}", line),
+            Err(err) => error!("{:?}", err),
+        },
+        |out| match out {
+            Ok(line) => error!("{}", line),
+            Err(err) => error!("{:?}", err),
+        },
+    )?;
+
+    Ok(())
+}
+
+pub fn kubectl_exec_apply<P>(
+    kubernetes_config: P,
+    manifests_path: &str,
+    envs: Vec<(&str, &str)>,
+) -> Result<(), SimpleError>
+where
+    P: AsRef<Path>,
+{
+    let mut _envs = Vec::with_capacity(envs.len() + 1);
+    _envs.push((KUBECONFIG, kubernetes_config.as_ref().to_str().unwrap()));
+    _envs.extend(envs);
+
+    let _ = kubectl_exec_with_output(
+        vec!["apply", "-f", manifests_path],
+        _envs,
+        |out| match out {
+            Ok(line) => info!("{}", line),
+            Err(err) => error!("{:?}", err),
+        },
+        |out| match out {
+            Ok(line) => error!("{}", line),
+            Err(err) => error!("{:?}", err),
+        },
+    )?;
+
+    Ok(())
+}
+
+pub fn kubectl_exec_create_job_from_cronjob<P>(
+    kubernetes_config: P,
+    namespace: &str,
+    cronjob_name: &str,
+    job_name: &str,
+    envs: Vec<(&str, &str)>,
+) -> Result<(), SimpleError>
+where
+    P: AsRef<Path>,
+{
+    let mut _envs = Vec::with_capacity(envs.len() + 1);
+    _envs.push((KUBECONFIG, kubernetes_config.as_ref().to_str().unwrap()));
+    _envs.extend(envs);
+
+    let cronjob = format!("--from=cronjob/{}", cronjob_name);
+
+    let _ = kubectl_exec_with_output(
+        vec!["-n", namespace, "create", "job", job_name, cronjob.as_str()],
+        _envs,
+        |out| match out {
+            Ok(line) => info!("{}", line),
+            Err(err) => error!("{:?}", err),
+        },
+        |out| match out {
+            Ok(line) => error!("{}", line),
+            Err(err) => error!("{:?}", err),
+        },
+    )?;
+
+    Ok(())
+}
+
+pub fn kubectl_exec_get_volume_snapshot<P>(
+    kubernetes_config: P,
+    namespace: &str,
+    snapshot_name: &str,
+    envs: Vec<(&str, &str)>,
+) -> Result<Option<KubernetesVolumeSnapshot>, SimpleError>
+where
+    P: AsRef<Path>,
+{
+    let field_selector = format!("metadata.name={}", snapshot_name);
+
+    let result = kubectl_exec::<P, KubernetesList<KubernetesVolumeSnapshot>>(
+        vec![
+            "get",
+            "volumesnapshots",
+            "-o",
+            "json",
+            "-n",
+            namespace,
+            "--field-selector",
+            field_selector.as_str(),
+        ],
+        kubernetes_config,
+        envs,
+    )?;
+
+    Ok(result.items.into_iter().next())
+}
+
+pub fn kubectl_exec_get_volume_snapshots<P>(
+    kubernetes_config: P,
+    namespace: &str,
+    selector: &str,
+    envs: Vec<(&str, &str)>,
+) -> Result<KubernetesList<KubernetesVolumeSnapshot>, SimpleError>
+where
+    P: AsRef<Path>,
+{
+    kubectl_exec::<P, KubernetesList<KubernetesVolumeSnapshot>>(
+        vec!["get", "volumesnapshots", "-o", "json", "-n", namespace, "-l", selector],
+        kubernetes_config,
+        envs,
+    )
+}
+
 pub fn kubectl_exec_logs<P>(
     kubernetes_config: P,
     namespace: &str,
@@ -801,6 +1183,55 @@ where
     )
 }
 
+/// get the deployments and statefulsets matching the selector
+pub fn kubectl_exec_get_workloads<P>(
+    kubernetes_config: P,
+    namespace: &str,
+    selector: &str,
+    envs: Vec<(&str, &str)>,
+) -> Result<KubernetesList<KubernetesWorkload>, SimpleError>
+where
+    P: AsRef<Path>,
+{
+    kubectl_exec::<P, KubernetesList<KubernetesWorkload>>(
+        vec![
+            "get",
+            "deployments,statefulsets",
+            "-o",
+            "json",
+            "-n",
+            namespace,
+            "-l",
+            selector,
+        ],
+        kubernetes_config,
+        envs,
+    )
+}
+
+/// get the pods of all namespaces which are still holding resources on their node (neither succeeded nor failed)
+pub fn kubectl_exec_get_scheduled_pods<P>(
+    kubernetes_config: P,
+    envs: Vec<(&str, &str)>,
+) -> Result<KubernetesList<KubernetesPod>, SimpleError>
+where
+    P: AsRef<Path>,
+{
+    kubectl_exec::<P, KubernetesList<KubernetesPod>>(
+        vec![
+            "get",
+            "pod",
+            "-A",
+            "-o",
+            "json",
+            "--field-selector",
+            "status.phase!=Succeeded,status.phase!=Failed",
+        ],
+        kubernetes_config,
+        envs,
+    )
+}
+
 pub fn kubectl_exec_get_configmap<P>(
     kubernetes_config: P,
     namespace: &str,
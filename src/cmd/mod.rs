@@ -1,5 +1,6 @@
 pub mod helm;
 pub mod kubectl;
+pub mod openssl;
 pub mod structs;
 pub mod terraform;
 pub mod utilities;
@@ -0,0 +1,163 @@
+use std::path::Path;
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::cmd::utilities::exec_with_output;
+use crate::error::{SimpleError, SimpleErrorKind};
+
+pub struct X509CertificateInfo {
+    pub not_after: DateTime<Utc>,
+    // DNS names of the subject alternative name extension, wildcards included (E.g: *.qovery.com)
+    pub dns_names: Vec<String>,
+}
+
+impl X509CertificateInfo {
+    pub fn is_expired(&self) -> bool {
+        self.not_after <= Utc::now()
+    }
+
+    /// a wildcard name only covers a single label, as browsers do
+    pub fn covers_domain(&self, domain: &str) -> bool {
+        let domain = domain.trim_end_matches('.').to_lowercase();
+
+        self.dns_names.iter().any(|dns_name| {
+            let dns_name = dns_name.to_lowercase();
+            match dns_name.strip_prefix("*.") {
+                Some(wildcard_parent) => match domain.split_once('.') {
+                    Some((label, parent)) => !label.is_empty() && parent == wildcard_parent,
+                    None => false,
+                },
+                None => dns_name == domain,
+            }
+        })
+    }
+}
+
+pub fn openssl_exec_x509_info<P>(certificate_path: P) -> Result<X509CertificateInfo, SimpleError>
+where
+    P: AsRef<Path>,
+{
+    let output = openssl_exec(vec![
+        "x509",
+        "-in",
+        certificate_path.as_ref().to_str().unwrap(),
+        "-noout",
+        "-text",
+    ])?;
+
+    parse_x509_text(output.as_str())
+}
+
+/// PEM encoded public key of a certificate, to be compared with the one of its private key
+pub fn openssl_exec_x509_public_key<P>(certificate_path: P) -> Result<String, SimpleError>
+where
+    P: AsRef<Path>,
+{
+    openssl_exec(vec![
+        "x509",
+        "-in",
+        certificate_path.as_ref().to_str().unwrap(),
+        "-noout",
+        "-pubkey",
+    ])
+}
+
+pub fn openssl_exec_private_key_public_key<P>(private_key_path: P) -> Result<String, SimpleError>
+where
+    P: AsRef<Path>,
+{
+    openssl_exec(vec![
+        "pkey",
+        "-in",
+        private_key_path.as_ref().to_str().unwrap(),
+        "-pubout",
+    ])
+}
+
+fn openssl_exec(args: Vec<&str>) -> Result<String, SimpleError> {
+    let mut output_lines = vec![];
+
+    let _ = exec_with_output(
+        "openssl",
+        args,
+        |out| match out {
+            Ok(line) => output_lines.push(line),
+            Err(err) => error!("{:?}", err),
+        },
+        |out| match out {
+            Ok(line) => error!("{}", line),
+            Err(err) => error!("{:?}", err),
+        },
+    )?;
+
+    Ok(output_lines.join("\n"))
+}
+
+fn parse_x509_text(output: &str) -> Result<X509CertificateInfo, SimpleError> {
+    let mut lines = output.lines().map(|line| line.trim());
+    let mut not_after = None;
+    let mut dns_names = vec![];
+
+    while let Some(line) = lines.next() {
+        if let Some(date) = line.strip_prefix("Not After :") {
+            // E.g: Jun  1 12:00:00 2022 GMT
+            let date = date.split_whitespace().collect::<Vec<_>>().join(" ");
+            not_after = Utc.datetime_from_str(date.as_str(), "%b %d %H:%M:%S %Y GMT").ok();
+        } else if line.starts_with("X509v3 Subject Alternative Name") {
+            if let Some(names) = lines.next() {
+                dns_names = names
+                    .split(',')
+                    .filter_map(|name| name.trim().strip_prefix("DNS:"))
+                    .map(|name| name.to_string())
+                    .collect();
+            }
+        }
+    }
+
+    match not_after {
+        Some(not_after) => Ok(X509CertificateInfo { not_after, dns_names }),
+        None => Err(SimpleError::new(
+            SimpleErrorKind::Other,
+            Some("can't read the expiration date of the certificate"),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cmd::openssl::parse_x509_text;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_parse_x509_text() {
+        let output = r#"
+Certificate:
+    Data:
+        Version: 3 (0x2)
+        Serial Number:
+            03:5a:2f:84:3c:d1:0a:6b:2c:e1:41:27:16:7a:8d:5c:9e:81
+        Signature Algorithm: sha256WithRSAEncryption
+        Issuer: C = US, O = Let's Encrypt, CN = R3
+        Validity
+            Not Before: Mar  3 10:00:00 2021 GMT
+            Not After : Jun  1 10:00:00 2021 GMT
+        Subject: CN = app.qovery.com
+        X509v3 extensions:
+            X509v3 Subject Alternative Name:
+                DNS:app.qovery.com, DNS:*.preview.qovery.com
+    Signature Algorithm: sha256WithRSAEncryption
+"#;
+
+        let info = parse_x509_text(output).unwrap();
+        assert_eq!(info.not_after, Utc.ymd(2021, 6, 1).and_hms(10, 0, 0));
+        assert!(info.is_expired());
+        assert!(info.covers_domain("app.qovery.com"));
+        assert!(info.covers_domain("APP.qovery.com."));
+        assert!(info.covers_domain("pr-42.preview.qovery.com"));
+        assert!(!info.covers_domain("preview.qovery.com"));
+        assert!(!info.covers_domain("a.pr-42.preview.qovery.com"));
+        assert!(!info.covers_domain("api.qovery.com"));
+
+        assert!(parse_x509_text("not a certificate").is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
@@ -81,6 +83,29 @@ pub struct MatchLabels {
     pub k8s_app: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesServiceDefinition {
+    pub metadata: KubernetesObjectMetadata,
+    pub spec: KubernetesServiceSpec,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesServiceSpec {
+    #[serde(default)]
+    pub selector: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesSecret {
+    pub metadata: KubernetesObjectMetadata,
+    // values are base64 encoded
+    #[serde(default)]
+    pub data: BTreeMap<String, String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct KubernetesServiceStatus {
@@ -99,12 +124,97 @@ pub struct KubernetesServiceStatusLoadBalancerIngress {
     pub hostname: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesObjectMetadata {
+    pub name: String,
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    #[serde(default)]
+    pub annotations: BTreeMap<String, String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct KubernetesPod {
+    pub metadata: KubernetesObjectMetadata,
+    pub spec: Option<KubernetesPodSpec>,
     pub status: KubernetesPodStatus,
 }
 
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesPersistentVolumeClaim {
+    pub metadata: KubernetesObjectMetadata,
+    pub spec: KubernetesPersistentVolumeClaimSpec,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesPersistentVolumeClaimSpec {
+    pub storage_class_name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesWorkload {
+    pub metadata: KubernetesObjectMetadata,
+    pub spec: KubernetesWorkloadSpec,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesWorkloadSpec {
+    pub replicas: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesPodSpec {
+    pub node_name: Option<String>,
+    pub containers: Vec<KubernetesPodContainer>,
+    #[serde(default)]
+    pub init_containers: Vec<KubernetesPodContainer>,
+    #[serde(default)]
+    pub volumes: Vec<KubernetesPodVolume>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesPodContainer {
+    pub name: String,
+    pub image: String,
+    #[serde(default)]
+    pub resources: KubernetesPodContainerResources,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesPodContainerResources {
+    pub requests: Option<KubernetesPodContainerResourcesValues>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesPodContainerResourcesValues {
+    pub cpu: Option<String>,
+    pub memory: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesPodVolume {
+    pub name: String,
+    pub persistent_volume_claim: Option<KubernetesPodVolumeClaim>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesPodVolumeClaim {
+    pub claim_name: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct KubernetesPodStatus {
@@ -140,6 +250,8 @@ pub struct KubernetesPodContainerStatus {
     #[serde(rename = "last_state")]
     pub last_state: Option<KubernetesPodContainerStatusLastState>,
     pub ready: bool,
+    #[serde(default)]
+    pub restart_count: u32,
 }
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
@@ -175,15 +287,73 @@ pub struct KubernetesJob {
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct KubernetesJobStatus {
+    // not set until a pod of the job has completed
+    #[serde(default)]
     pub succeeded: u32,
+    #[serde(default)]
+    pub failed: u32,
+    // Complete or Failed once the job is over, failed pods are retried until then
+    #[serde(default)]
+    pub conditions: Vec<KubernetesJobCondition>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesJobCondition {
+    #[serde(rename = "type")]
+    pub condition_type: String,
+    pub status: String,
+}
+
+impl KubernetesJobStatus {
+    /// whether the job has succeeded, or None while it is still running
+    pub fn is_succeeded(&self) -> Option<bool> {
+        let has_condition = |condition_type: &str| {
+            self.conditions
+                .iter()
+                .any(|condition| condition.condition_type == condition_type && condition.status == "True")
+        };
+
+        if has_condition("Complete") {
+            Some(true)
+        } else if has_condition("Failed") {
+            Some(false)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesVolumeSnapshot {
+    pub metadata: KubernetesObjectMetadata,
+    // not set until the snapshot controller has processed the snapshot
+    pub status: Option<KubernetesVolumeSnapshotStatus>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesVolumeSnapshotStatus {
+    #[serde(default)]
+    pub ready_to_use: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct KubernetesNode {
+    pub metadata: KubernetesObjectMetadata,
+    pub spec: Option<KubernetesNodeSpec>,
     pub status: KubernetesNodeStatus,
 }
 
+#[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct KubernetesNodeSpec {
+    #[serde(default)]
+    pub unschedulable: bool,
+}
+
 #[derive(Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct KubernetesNodeStatus {
@@ -286,7 +456,27 @@ impl HelmHistoryRow {
 
 #[cfg(test)]
 mod tests {
-    use crate::cmd::structs::{KubernetesList, KubernetesPod};
+    use crate::cmd::structs::{KubernetesJob, KubernetesList, KubernetesPod};
+
+    #[test]
+    fn test_job_status_deserialize() {
+        let running = r#"{"status": {"active": 1, "startTime": "2021-06-01T10:00:00Z"}}"#;
+        let job = serde_json::from_str::<KubernetesJob>(running).unwrap();
+        assert_eq!(job.status.is_succeeded(), None);
+
+        // a failed pod is retried until the backoff limit is reached
+        let retried = r#"{"status": {"active": 1, "failed": 1, "startTime": "2021-06-01T10:00:00Z"}}"#;
+        let job = serde_json::from_str::<KubernetesJob>(retried).unwrap();
+        assert_eq!(job.status.is_succeeded(), None);
+
+        let succeeded = r#"{"status": {"conditions": [{"lastProbeTime": "2021-06-01T10:05:00Z", "lastTransitionTime": "2021-06-01T10:05:00Z", "status": "True", "type": "Complete"}], "completionTime": "2021-06-01T10:05:00Z", "startTime": "2021-06-01T10:00:00Z", "succeeded": 1}}"#;
+        let job = serde_json::from_str::<KubernetesJob>(succeeded).unwrap();
+        assert_eq!(job.status.is_succeeded(), Some(true));
+
+        let failed = r#"{"status": {"conditions": [{"lastProbeTime": "2021-06-01T11:00:00Z", "lastTransitionTime": "2021-06-01T11:00:00Z", "message": "Job was active longer than specified deadline", "reason": "DeadlineExceeded", "status": "True", "type": "Failed"}], "failed": 1, "startTime": "2021-06-01T10:00:00Z"}}"#;
+        let job = serde_json::from_str::<KubernetesJob>(failed).unwrap();
+        assert_eq!(job.status.is_succeeded(), Some(false));
+    }
 
     #[test]
     fn test_pod_status_deserialize() {
@@ -296,7 +486,18 @@ mod tests {
 
         let pod_status = serde_json::from_str::<KubernetesList<KubernetesPod>>(payload);
         assert_eq!(pod_status.is_ok(), true);
-        assert_eq!(pod_status.unwrap().items[0].status.conditions[0].status, "False");
+        let pod = pod_status.unwrap().items[0].clone();
+        assert_eq!(pod.status.conditions[0].status, "False");
+        assert_eq!(
+            pod.metadata.namespace,
+            Some("lbxmwiibzi9lbla-ah5bbhekjarxta5".to_string())
+        );
+        let spec = pod.spec.unwrap();
+        assert_eq!(spec.node_name, None);
+        assert_eq!(spec.init_containers.len(), 1);
+        let requests = spec.containers[0].resources.requests.clone().unwrap();
+        assert_eq!(requests.cpu, Some("100m".to_string()));
+        assert_eq!(requests.memory, Some("50Gi".to_string()));
 
         let payload = r#"
         {
@@ -67,6 +67,61 @@ pub fn terraform_init_validate_plan_apply(root_dir: &str, dry_run: bool) -> Resu
     }
 }
 
+/// plan the changes on the resources, without applying them, and return the plan output
+pub fn terraform_init_validate_plan(root_dir: &str, destroy: bool) -> Result<Vec<String>, SimpleError> {
+    match terraform_init_validate(root_dir) {
+        Err(e) => return Err(e),
+        Ok(_) => {}
+    }
+
+    let mut args = vec!["plan", "-no-color", "-input=false"];
+    if destroy {
+        args.push("-destroy");
+    }
+
+    let result = retry::retry(Fixed::from_millis(3000).take(3), || {
+        match terraform_exec(root_dir, args.clone()) {
+            Ok(out) => OperationResult::Ok(out),
+            Err(err) => {
+                error!("While trying to Terraform plan the rendered templates");
+                OperationResult::Retry(err)
+            }
+        }
+    });
+
+    match result {
+        Ok(output) => Ok(output),
+        Err(Operation { error, .. }) => Err(error),
+        Err(retry::Error::Internal(e)) => Err(SimpleError::new(SimpleErrorKind::Other, Some(e))),
+    }
+}
+
+/// force the re-creation of a resource on the next apply, the resource is left untouched on dry run
+pub fn terraform_init_validate_taint_plan_apply(
+    root_dir: &str,
+    resource_address: &str,
+    dry_run: bool,
+) -> Result<(), SimpleError> {
+    match terraform_init_validate(root_dir) {
+        Err(e) => return Err(e),
+        Ok(_) => {}
+    }
+
+    if dry_run {
+        return Ok(());
+    }
+
+    match terraform_exec(root_dir, vec!["taint", resource_address]) {
+        Err(e) => {
+            error!("error while trying to Terraform taint {}", resource_address);
+            return Err(e);
+        }
+        Ok(_) => {}
+    }
+
+    terraform_plan_apply(root_dir)
+}
+
 pub fn terraform_init_validate_destroy(root_dir: &str, run_apply_before_destroy: bool) -> Result<(), SimpleError> {
     // terraform init
     match terraform_init_validate(root_dir) {
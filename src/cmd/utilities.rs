@@ -133,17 +133,36 @@ where
 }
 
 pub fn exec_with_envs_and_output<P, F, X>(
+    binary: P,
+    args: Vec<&str>,
+    envs: Vec<(&str, &str)>,
+    stdout_output: F,
+    stderr_output: X,
+    timeout: Duration,
+) -> Result<Vec<String>, SimpleError>
+where
+    P: AsRef<Path>,
+    F: FnMut(Result<String, Error>),
+    X: FnMut(Result<String, Error>),
+{
+    exec_with_envs_and_output_and_cancel(binary, args, envs, stdout_output, stderr_output, timeout, || false)
+}
+
+/// same as exec_with_envs_and_output, the process is also killed as soon as the task is canceled
+pub fn exec_with_envs_and_output_and_cancel<P, F, X, C>(
     binary: P,
     args: Vec<&str>,
     envs: Vec<(&str, &str)>,
     mut stdout_output: F,
     mut stderr_output: X,
     timeout: Duration,
+    is_task_canceled: C,
 ) -> Result<Vec<String>, SimpleError>
 where
     P: AsRef<Path>,
     F: FnMut(Result<String, Error>),
     X: FnMut(Result<String, Error>),
+    C: Fn() -> bool,
 {
     assert!(timeout.num_seconds() > 0, "Timeout cannot be a 0 or negative duration");
 
@@ -189,7 +208,7 @@ where
             }
         }
 
-        if (process_start_time.elapsed().as_secs() as i64) >= timeout.num_seconds() {
+        if (process_start_time.elapsed().as_secs() as i64) >= timeout.num_seconds() || is_task_canceled() {
             break;
         }
     }
@@ -204,6 +223,16 @@ where
                 break;
             }
             Ok(None) => {
+                if is_task_canceled() {
+                    warn!("Killing process {} due to task cancellation", command_string);
+                    let _ = child_process
+                        .kill()
+                        .map(|_| child_process.wait())
+                        .map_err(|err| error!("Cannot kill process {:?} {}", child_process, err));
+
+                    return Err(SimpleError::new(Other, Some("Task canceled")));
+                }
+
                 if (process_start_time.elapsed().as_secs() as i64) < timeout.num_seconds() {
                     std::thread::sleep(std::time::Duration::from_secs(1));
                     continue;
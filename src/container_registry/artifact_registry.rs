@@ -0,0 +1,291 @@
+use crate::build_platform::Image;
+use crate::cloud_provider::gcp::write_credentials_file;
+use crate::cmd;
+use crate::container_registry::utilities::{docker_config_json, docker_tag_and_push_image};
+use crate::container_registry::{ContainerRegistry, EngineError, Kind, PushDestination, PushResult, BUILD_CACHE_TAG};
+use crate::error::{cast_simple_error_to_engine_error, EngineErrorCause};
+use crate::models::{
+    Context, Listen, Listener, Listeners, ListenersHelper, ProgressInfo, ProgressLevel, ProgressScope,
+};
+
+// gcloud uses this service account key instead of the configured account
+const CLOUDSDK_AUTH_CREDENTIAL_FILE_OVERRIDE: &str = "CLOUDSDK_AUTH_CREDENTIAL_FILE_OVERRIDE";
+
+/// GCP Artifact Registry, one docker repository holding the images of all the applications
+pub struct ArtifactRegistry {
+    context: Context,
+    id: String,
+    name: String,
+    project_id: String,
+    region: String,
+    credentials_json: String,
+    listeners: Listeners,
+}
+
+impl ArtifactRegistry {
+    pub fn new(context: Context, id: &str, name: &str, project_id: &str, region: &str, credentials_json: &str) -> Self {
+        ArtifactRegistry {
+            context,
+            id: id.to_string(),
+            // Artifact Registry does not support upper cases
+            name: name.to_lowercase(),
+            project_id: project_id.to_string(),
+            region: region.to_string(),
+            credentials_json: credentials_json.to_string(),
+            listeners: vec![],
+        }
+    }
+
+    fn registry_host(&self) -> String {
+        format!("{}-docker.pkg.dev", self.region)
+    }
+
+    fn image_url(&self, image: &Image) -> String {
+        format!(
+            "{}/{}/{}/{}",
+            self.registry_host(),
+            self.project_id,
+            self.name,
+            image.name_with_tag()
+        )
+    }
+
+    fn docker_envs(&self) -> Vec<(&str, &str)> {
+        match self.context.docker_tcp_socket() {
+            Some(tcp_socket) => vec![("DOCKER_HOST", tcp_socket.as_str())],
+            None => vec![],
+        }
+    }
+
+    fn gcloud_exec(&self, args: Vec<&str>) -> Result<(), EngineError> {
+        let credentials_file_path = cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            write_credentials_file(&self.context, self.id.as_str(), self.credentials_json.as_str()),
+        )?;
+
+        let mut gcloud_args = args;
+        gcloud_args.extend(vec!["--project", self.project_id.as_str(), "--quiet"]);
+
+        cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context.execution_id(),
+            cmd::utilities::exec(
+                "gcloud",
+                gcloud_args,
+                &[(CLOUDSDK_AUTH_CREDENTIAL_FILE_OVERRIDE, credentials_file_path.as_str())],
+            ),
+        )
+    }
+
+    fn docker_login(&self) -> Result<(), EngineError> {
+        let registry_host = self.registry_host();
+        if cmd::utilities::exec(
+            "docker",
+            vec![
+                "login",
+                "-u",
+                "_json_key",
+                "-p",
+                self.credentials_json.as_str(),
+                registry_host.as_str(),
+            ],
+            &self.docker_envs(),
+        )
+        .is_err()
+        {
+            return Err(self.engine_error(
+                EngineErrorCause::User(
+                    "Your GCP account seems to be no longer valid (bad Credentials). \
+                Please contact your Organization administrator to fix or change the Credentials.",
+                ),
+                format!("failed to login to Artifact Registry {}", self.name_with_id()),
+            ));
+        };
+
+        Ok(())
+    }
+
+    fn create_repository(&self) -> Result<(), EngineError> {
+        self.gcloud_exec(vec![
+            "artifacts",
+            "repositories",
+            "create",
+            self.name.as_str(),
+            "--repository-format=docker",
+            format!("--location={}", self.region).as_str(),
+        ])
+    }
+
+    fn push_image(&self, dest: String, image: &Image) -> Result<PushResult, EngineError> {
+        match docker_tag_and_push_image(
+            self.kind(),
+            self.docker_envs(),
+            image.name.clone(),
+            image.tag.clone(),
+            dest.clone(),
+        ) {
+            Ok(_) => {
+                let mut image = image.clone();
+                image.registry_url = Some(dest);
+                Ok(PushResult { image })
+            }
+            Err(e) => Err(self.engine_error(
+                EngineErrorCause::Internal,
+                e.message
+                    .unwrap_or_else(|| "unknown error occurring during docker push".to_string()),
+            )),
+        }
+    }
+}
+
+impl ContainerRegistry for ArtifactRegistry {
+    fn context(&self) -> &Context {
+        &self.context
+    }
+
+    fn kind(&self) -> Kind {
+        Kind::ArtifactRegistry
+    }
+
+    fn id(&self) -> &str {
+        self.id.as_str()
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn is_valid(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_create(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_create_error(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_delete(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn on_delete_error(&self) -> Result<(), EngineError> {
+        Ok(())
+    }
+
+    fn does_image_exists(&self, image: &Image) -> bool {
+        let image_url = self.image_url(image);
+
+        self.gcloud_exec(vec!["artifacts", "docker", "images", "describe", image_url.as_str()])
+            .is_ok()
+    }
+
+    // https://cloud.google.com/artifact-registry/docs/docker/authentication#json-key
+    fn push(&self, image: &Image, force_push: bool) -> Result<PushResult, EngineError> {
+        let _ = match self.create_repository() {
+            Ok(_) => info!("Artifact Registry repository {} has been created", self.name()),
+            Err(_) => warn!("Artifact Registry repository {} already exists", self.name()),
+        };
+
+        let _ = self.docker_login()?;
+
+        let dest = self.image_url(image);
+
+        let listeners_helper = ListenersHelper::new(&self.listeners);
+
+        if !force_push && self.does_image_exists(image) {
+            // check if image does exist - if yes, do not upload it again
+            let info_message = format!(
+                "image {:?} found on Artifact Registry {} repository, container build is not required",
+                image,
+                self.name()
+            );
+
+            info!("{}", info_message.as_str());
+
+            listeners_helper.deployment_in_progress(ProgressInfo::new(
+                ProgressScope::Application {
+                    id: image.application_id.clone(),
+                },
+                ProgressLevel::Info,
+                Some(info_message),
+                self.context.execution_id(),
+            ));
+
+            let mut image = image.clone();
+            image.registry_url = Some(dest);
+
+            return Ok(PushResult { image });
+        }
+
+        let info_message = format!(
+            "image {:?} does not exist on Artifact Registry {} repository, starting image upload",
+            image,
+            self.name()
+        );
+
+        info!("{}", info_message.as_str());
+
+        listeners_helper.deployment_in_progress(ProgressInfo::new(
+            ProgressScope::Application {
+                id: image.application_id.clone(),
+            },
+            ProgressLevel::Info,
+            Some(info_message),
+            self.context.execution_id(),
+        ));
+
+        self.push_image(dest, image)
+    }
+
+    fn push_error(&self, image: &Image) -> Result<PushResult, EngineError> {
+        Ok(PushResult { image: image.clone() })
+    }
+
+    fn build_cache_reference(&self, image: &Image) -> Result<String, EngineError> {
+        let _ = match self.create_repository() {
+            Ok(_) => info!("Artifact Registry repository {} has been created", self.name()),
+            Err(_) => warn!("Artifact Registry repository {} already exists", self.name()),
+        };
+
+        let _ = self.docker_login()?;
+
+        Ok(format!(
+            "{}/{}/{}/{}:{}",
+            self.registry_host(),
+            self.project_id,
+            self.name,
+            image.name,
+            BUILD_CACHE_TAG
+        ))
+    }
+
+    fn push_destination(&self, image: &Image) -> Result<PushDestination, EngineError> {
+        let _ = match self.create_repository() {
+            Ok(_) => info!("Artifact Registry repository {} has been created", self.name()),
+            Err(_) => warn!("Artifact Registry repository {} already exists", self.name()),
+        };
+
+        Ok(PushDestination {
+            image_url: self.image_url(image),
+            docker_config_json: docker_config_json(
+                self.registry_host().as_str(),
+                "_json_key",
+                self.credentials_json.as_str(),
+            ),
+        })
+    }
+}
+
+impl Listen for ArtifactRegistry {
+    fn listeners(&self) -> &Listeners {
+        &self.listeners
+    }
+
+    fn add_listener(&mut self, listener: Listener) {
+        self.listeners.push(listener);
+    }
+}
@@ -4,8 +4,8 @@ use reqwest::StatusCode;
 
 use crate::build_platform::Image;
 use crate::cmd;
-use crate::container_registry::utilities::docker_tag_and_push_image;
-use crate::container_registry::{ContainerRegistry, EngineError, Kind, PushResult};
+use crate::container_registry::utilities::{docker_config_json, docker_tag_and_push_image};
+use crate::container_registry::{ContainerRegistry, EngineError, Kind, PushDestination, PushResult, BUILD_CACHE_TAG};
 use crate::error::EngineErrorCause;
 use crate::models::{
     Context, Listen, Listener, Listeners, ListenersHelper, ProgressInfo, ProgressLevel, ProgressScope,
@@ -31,6 +31,29 @@ impl DockerHub {
             listeners: vec![],
         }
     }
+
+    fn docker_login(&self) -> Result<(), EngineError> {
+        let envs = match self.context.docker_tcp_socket() {
+            Some(tcp_socket) => vec![("DOCKER_HOST", tcp_socket.as_str())],
+            None => vec![],
+        };
+
+        if let Err(_) = cmd::utilities::exec(
+            "docker",
+            vec!["login", "-u", self.login.as_str(), "-p", self.password.as_str()],
+            &envs,
+        ) {
+            return Err(self.engine_error(
+                EngineErrorCause::User(
+                    "Your DockerHub account seems to be no longer valid (bad Credentials). \
+                Please contact your Organization administrator to fix or change the Credentials.",
+                ),
+                format!("failed to login to DockerHub {}", self.name_with_id()),
+            ));
+        };
+
+        Ok(())
+    }
 }
 
 impl ContainerRegistry for DockerHub {
@@ -108,24 +131,7 @@ impl ContainerRegistry for DockerHub {
     }
 
     fn push(&self, image: &Image, force_push: bool) -> Result<PushResult, EngineError> {
-        let envs = match self.context.docker_tcp_socket() {
-            Some(tcp_socket) => vec![("DOCKER_HOST", tcp_socket.as_str())],
-            None => vec![],
-        };
-
-        if let Err(_) = cmd::utilities::exec(
-            "docker",
-            vec!["login", "-u", self.login.as_str(), "-p", self.password.as_str()],
-            &envs,
-        ) {
-            return Err(self.engine_error(
-                EngineErrorCause::User(
-                    "Your DockerHub account seems to be no longer valid (bad Credentials). \
-                Please contact your Organization administrator to fix or change the Credentials.",
-                ),
-                format!("failed to login to DockerHub {}", self.name_with_id()),
-            ));
-        };
+        let _ = self.docker_login()?;
 
         let dest = format!("{}/{}", self.login.as_str(), image.name_with_tag().as_str());
         let listeners_helper = ListenersHelper::new(&self.listeners);
@@ -187,6 +193,29 @@ impl ContainerRegistry for DockerHub {
     fn push_error(&self, _image: &Image) -> Result<PushResult, EngineError> {
         unimplemented!()
     }
+
+    fn build_cache_reference(&self, image: &Image) -> Result<String, EngineError> {
+        // the repository is created by the first push
+        let _ = self.docker_login()?;
+
+        Ok(format!(
+            "{}/{}:{}",
+            self.login.as_str(),
+            image.name.as_str(),
+            BUILD_CACHE_TAG
+        ))
+    }
+
+    fn push_destination(&self, image: &Image) -> Result<PushDestination, EngineError> {
+        Ok(PushDestination {
+            image_url: format!("{}/{}", self.login.as_str(), image.name_with_tag().as_str()),
+            docker_config_json: docker_config_json(
+                "https://index.docker.io/v1/",
+                self.login.as_str(),
+                self.password.as_str(),
+            ),
+        })
+    }
 }
 
 impl Listen for DockerHub {
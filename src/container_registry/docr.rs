@@ -4,8 +4,8 @@ use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 
 use crate::build_platform::Image;
-use crate::container_registry::utilities::docker_tag_and_push_image;
-use crate::container_registry::{ContainerRegistry, EngineError, Kind, PushResult};
+use crate::container_registry::utilities::{docker_config_json, docker_tag_and_push_image};
+use crate::container_registry::{ContainerRegistry, EngineError, Kind, PushDestination, PushResult, BUILD_CACHE_TAG};
 use crate::error::{cast_simple_error_to_engine_error, EngineErrorCause, SimpleError, SimpleErrorKind};
 use crate::models::{
     Context, Listen, Listener, Listeners, ListenersHelper, ProgressInfo, ProgressLevel, ProgressScope,
@@ -152,6 +152,27 @@ impl DOCR {
         }
     }
 
+    fn docker_login(&self) -> Result<(), EngineError> {
+        match cmd::utilities::exec(
+            "doctl",
+            vec!["registry", "login", self.name.as_str(), "-t", self.api_key.as_str()],
+            &vec![],
+        ) {
+            Err(_) => {
+                return Err(self.engine_error(
+                    EngineErrorCause::User(
+                        "Your DOCR account seems to be no longer valid (bad Credentials). \
+                    Please contact your Organization administrator to fix or change the Credentials.",
+                    ),
+                    format!("failed to login to DOCR {}", self.name_with_id()),
+                ));
+            }
+            _ => {}
+        };
+
+        Ok(())
+    }
+
     pub fn delete_repository(&self) -> Result<(), EngineError> {
         let headers = utilities::get_header_with_bearer(&self.api_key);
         let res = reqwest::blocking::Client::new()
@@ -302,22 +323,7 @@ impl ContainerRegistry for DOCR {
             Err(_) => warn!("DOCR {} already exists", registry_name.as_str()),
         };
 
-        match cmd::utilities::exec(
-            "doctl",
-            vec!["registry", "login", self.name.as_str(), "-t", self.api_key.as_str()],
-            &vec![],
-        ) {
-            Err(_) => {
-                return Err(self.engine_error(
-                    EngineErrorCause::User(
-                        "Your DOCR account seems to be no longer valid (bad Credentials). \
-                    Please contact your Organization administrator to fix or change the Credentials.",
-                    ),
-                    format!("failed to login to DOCR {}", self.name_with_id()),
-                ));
-            }
-            _ => {}
-        };
+        let _ = self.docker_login()?;
 
         let dest = format!(
             "registry.digitalocean.com/{}/{}",
@@ -372,6 +378,47 @@ impl ContainerRegistry for DOCR {
         self.push_image(registry_name, dest, &image)
     }
 
+    fn build_cache_reference(&self, image: &Image) -> Result<String, EngineError> {
+        let registry_name = self.get_registry_name(image)?;
+
+        let _ = match self.create_repository(&image) {
+            Ok(_) => info!("DOCR {} has been created", registry_name.as_str()),
+            Err(_) => warn!("DOCR {} already exists", registry_name.as_str()),
+        };
+
+        let _ = self.docker_login()?;
+
+        Ok(format!(
+            "registry.digitalocean.com/{}/{}:{}",
+            registry_name.as_str(),
+            image.name.as_str(),
+            BUILD_CACHE_TAG
+        ))
+    }
+
+    fn push_destination(&self, image: &Image) -> Result<PushDestination, EngineError> {
+        let registry_name = self.get_registry_name(image)?;
+
+        let _ = match self.create_repository(&image) {
+            Ok(_) => info!("DOCR {} has been created", registry_name.as_str()),
+            Err(_) => warn!("DOCR {} already exists", registry_name.as_str()),
+        };
+
+        // the API token is accepted as both the user and the password
+        Ok(PushDestination {
+            image_url: format!(
+                "registry.digitalocean.com/{}/{}",
+                registry_name.as_str(),
+                image.name_with_tag()
+            ),
+            docker_config_json: docker_config_json(
+                "registry.digitalocean.com",
+                self.api_key.as_str(),
+                self.api_key.as_str(),
+            ),
+        })
+    }
+
     fn push_error(&self, image: &Image) -> Result<PushResult, EngineError> {
         Ok(PushResult { image: image.clone() })
     }
@@ -10,8 +10,8 @@ use rusoto_sts::{GetCallerIdentityRequest, Sts, StsClient};
 
 use crate::build_platform::Image;
 use crate::cmd;
-use crate::container_registry::utilities::docker_tag_and_push_image;
-use crate::container_registry::{ContainerRegistry, Kind, PushResult};
+use crate::container_registry::utilities::{docker_config_json, docker_tag_and_push_image};
+use crate::container_registry::{ContainerRegistry, Kind, PushDestination, PushResult, BUILD_CACHE_TAG};
 use crate::error::{EngineError, EngineErrorCause};
 use crate::models::{
     Context, Listen, Listener, Listeners, ListenersHelper, ProgressInfo, ProgressLevel, ProgressScope,
@@ -136,6 +136,75 @@ impl ECR {
         }
     }
 
+    /// base64 encoded `user:password` token and endpoint of the registry
+    fn get_authorization_token(&self) -> Result<(String, String), EngineError> {
+        let r = block_on(
+            self.ecr_client()
+                .get_authorization_token(GetAuthorizationTokenRequest::default()),
+        );
+
+        match r {
+            Ok(t) => match t.authorization_data {
+                Some(authorization_data) => {
+                    let ad = authorization_data.first().unwrap();
+
+                    Ok((
+                        ad.authorization_token.clone().unwrap(),
+                        ad.clone().proxy_endpoint.unwrap(),
+                    ))
+                }
+                None => Err(self.engine_error(
+                    EngineErrorCause::Internal,
+                    format!(
+                        "failed to retrieve credentials and endpoint URL from ECR {}",
+                        self.name_with_id(),
+                    ),
+                )),
+            },
+            _ => Err(self.engine_error(
+                EngineErrorCause::Internal,
+                format!(
+                    "failed to retrieve credentials and endpoint URL from ECR {}",
+                    self.name_with_id(),
+                ),
+            )),
+        }
+    }
+
+    fn docker_login(&self) -> Result<(), EngineError> {
+        let (b64_token, endpoint_url) = self.get_authorization_token()?;
+
+        let decoded_token = base64::decode(b64_token).unwrap();
+        let token = std::str::from_utf8(decoded_token.as_slice()).unwrap();
+
+        let s_token: Vec<&str> = token.split(":").collect::<Vec<_>>();
+        let access_token = s_token.first().unwrap().to_string();
+        let password = s_token.get(1).unwrap().to_string();
+
+        if let Err(_) = cmd::utilities::exec(
+            "docker",
+            vec![
+                "login",
+                "-u",
+                access_token.as_str(),
+                "-p",
+                password.as_str(),
+                endpoint_url.as_str(),
+            ],
+            &self.docker_envs(),
+        ) {
+            return Err(self.engine_error(
+                EngineErrorCause::User(
+                    "Your ECR account seems to be no longer valid (bad Credentials). \
+                Please contact your Organization administrator to fix or change the Credentials.",
+                ),
+                format!("failed to login to ECR {}", self.name_with_id()),
+            ));
+        };
+
+        Ok(())
+    }
+
     fn create_repository(&self, image: &Image) -> Result<Repository, EngineError> {
         let repository_name = image.name.as_str();
         info!("creating ECR repository {}", &repository_name);
@@ -320,49 +389,6 @@ impl ContainerRegistry for ECR {
     }
 
     fn push(&self, image: &Image, force_push: bool) -> Result<PushResult, EngineError> {
-        let r = block_on(
-            self.ecr_client()
-                .get_authorization_token(GetAuthorizationTokenRequest::default()),
-        );
-
-        let (access_token, password, endpoint_url) = match r {
-            Ok(t) => match t.authorization_data {
-                Some(authorization_data) => {
-                    let ad = authorization_data.first().unwrap();
-                    let b64_token = ad.authorization_token.as_ref().unwrap();
-
-                    let decoded_token = base64::decode(b64_token).unwrap();
-                    let token = std::str::from_utf8(decoded_token.as_slice()).unwrap();
-
-                    let s_token: Vec<&str> = token.split(":").collect::<Vec<_>>();
-
-                    (
-                        s_token.first().unwrap().to_string(),
-                        s_token.get(1).unwrap().to_string(),
-                        ad.clone().proxy_endpoint.unwrap(),
-                    )
-                }
-                None => {
-                    return Err(self.engine_error(
-                        EngineErrorCause::Internal,
-                        format!(
-                            "failed to retrieve credentials and endpoint URL from ECR {}",
-                            self.name_with_id(),
-                        ),
-                    ));
-                }
-            },
-            _ => {
-                return Err(self.engine_error(
-                    EngineErrorCause::Internal,
-                    format!(
-                        "failed to retrieve credentials and endpoint URL from ECR {}",
-                        self.name_with_id(),
-                    ),
-                ));
-            }
-        };
-
         let repository = match if force_push {
             self.create_repository(image)
         } else {
@@ -381,26 +407,7 @@ impl ContainerRegistry for ECR {
             }
         };
 
-        if let Err(_) = cmd::utilities::exec(
-            "docker",
-            vec![
-                "login",
-                "-u",
-                access_token.as_str(),
-                "-p",
-                password.as_str(),
-                endpoint_url.as_str(),
-            ],
-            &self.docker_envs(),
-        ) {
-            return Err(self.engine_error(
-                EngineErrorCause::User(
-                    "Your ECR account seems to be no longer valid (bad Credentials). \
-                Please contact your Organization administrator to fix or change the Credentials.",
-                ),
-                format!("failed to login to ECR {}", self.name_with_id()),
-            ));
-        };
+        let _ = self.docker_login()?;
 
         let dest = format!("{}:{}", repository.repository_uri.unwrap(), image.tag.as_str());
 
@@ -455,6 +462,27 @@ impl ContainerRegistry for ECR {
         // TODO change this
         Ok(PushResult { image: image.clone() })
     }
+
+    fn build_cache_reference(&self, image: &Image) -> Result<String, EngineError> {
+        let repository = self.get_or_create_repository(image)?;
+        let _ = self.docker_login()?;
+
+        Ok(format!("{}:{}", repository.repository_uri.unwrap(), BUILD_CACHE_TAG))
+    }
+
+    fn push_destination(&self, image: &Image) -> Result<PushDestination, EngineError> {
+        let repository = self.get_or_create_repository(image)?;
+        let (b64_token, endpoint_url) = self.get_authorization_token()?;
+
+        let decoded_token = base64::decode(b64_token).unwrap();
+        let token = std::str::from_utf8(decoded_token.as_slice()).unwrap();
+        let (access_token, password) = token.split_once(':').unwrap_or_default();
+
+        Ok(PushDestination {
+            image_url: format!("{}:{}", repository.repository_uri.unwrap(), image.tag.as_str()),
+            docker_config_json: docker_config_json(endpoint_url.trim_start_matches("https://"), access_token, password),
+        })
+    }
 }
 
 impl Listen for ECR {
@@ -4,12 +4,16 @@ use crate::build_platform::Image;
 use crate::error::{EngineError, EngineErrorCause, EngineErrorScope};
 use crate::models::{Context, Listen};
 
+pub mod artifact_registry;
 pub mod docker_hub;
 pub mod docr;
 pub mod ecr;
 pub mod utilities;
 
-pub trait ContainerRegistry: Listen {
+/// tag of the BuildKit cache, pushed in the repository of the image it has been built for
+pub const BUILD_CACHE_TAG: &str = "build-cache";
+
+pub trait ContainerRegistry: Listen + Send + Sync {
     fn context(&self) -> &Context;
     fn kind(&self) -> Kind;
     fn id(&self) -> &str;
@@ -25,6 +29,10 @@ pub trait ContainerRegistry: Listen {
     fn does_image_exists(&self, image: &Image) -> bool;
     fn push(&self, image: &Image, force_push: bool) -> Result<PushResult, EngineError>;
     fn push_error(&self, image: &Image) -> Result<PushResult, EngineError>;
+    /// log in and make sure the repository of the image exists, so BuildKit can import and export its cache
+    fn build_cache_reference(&self, image: &Image) -> Result<String, EngineError>;
+    /// where and how a build running outside of the engine host pushes the image by itself
+    fn push_destination(&self, image: &Image) -> Result<PushDestination, EngineError>;
     fn engine_error_scope(&self) -> EngineErrorScope {
         EngineErrorScope::ContainerRegistry(self.id().to_string(), self.name().to_string())
     }
@@ -42,10 +50,18 @@ pub struct PushResult {
     pub image: Image,
 }
 
+pub struct PushDestination {
+    // complete registry URL of the image, tag included
+    pub image_url: String,
+    // docker `config.json` holding the push credentials of the registry
+    pub docker_config_json: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Kind {
     DockerHub,
     Ecr,
     Docr,
+    ArtifactRegistry,
 }
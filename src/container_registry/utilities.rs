@@ -5,6 +5,7 @@ use chrono::Duration;
 use retry::delay::Fibonacci;
 use retry::Error::Operation;
 use retry::OperationResult;
+use serde_json::json;
 
 pub fn docker_tag_and_push_image(
     container_registry_kind: Kind,
@@ -18,6 +19,7 @@ pub fn docker_tag_and_push_image(
         Kind::DockerHub => "DockerHub",
         Kind::Ecr => "AWS ECR",
         Kind::Docr => "DigitalOcean Registry",
+        Kind::ArtifactRegistry => "GCP Artifact Registry",
     };
 
     match retry::retry(Fibonacci::from_millis(3000).take(5), || {
@@ -78,3 +80,215 @@ pub fn docker_tag_and_push_image(
         }
     }
 }
+
+/// content of a docker `config.json`, for the builds pushing the image from outside of the engine host
+pub fn docker_config_json(registry_host: &str, username: &str, password: &str) -> String {
+    let auth = base64::encode(format!("{}:{}", username, password));
+
+    json!({
+        "auths": {
+            registry_host: {
+                "auth": auth
+            }
+        }
+    })
+    .to_string()
+}
+
+const DOCKER_HUB_REGISTRY_HOST: &str = "registry-1.docker.io";
+
+const MANIFEST_MEDIA_TYPES: [&str; 4] = [
+    "application/vnd.docker.distribution.manifest.v2+json",
+    "application/vnd.docker.distribution.manifest.list.v2+json",
+    "application/vnd.oci.image.manifest.v1+json",
+    "application/vnd.oci.image.index.v1+json",
+];
+
+/// split an image URL into its registry host, repository and tag, E.g: `user/app:tag` is on Docker Hub
+fn parse_image_url(image_url: &str) -> Option<(String, String, String)> {
+    let (repository_url, tag) = match image_url.rsplit_once(':') {
+        Some((repository_url, tag)) if !tag.contains('/') => (repository_url, tag),
+        _ => return None,
+    };
+
+    let (host, repository) = match repository_url.split_once('/') {
+        Some((host, repository)) if host.contains('.') || host.contains(':') || host == "localhost" => {
+            (host.to_string(), repository.to_string())
+        }
+        Some(_) => (DOCKER_HUB_REGISTRY_HOST.to_string(), repository_url.to_string()),
+        None => (
+            DOCKER_HUB_REGISTRY_HOST.to_string(),
+            format!("library/{}", repository_url),
+        ),
+    };
+
+    Some((host, repository, tag.to_string()))
+}
+
+/// parameters of a `WWW-Authenticate: Bearer realm="...",service="...",scope="..."` challenge
+fn parse_bearer_challenge(header: &str) -> Vec<(String, String)> {
+    let mut params = vec![];
+    let mut rest = match header.strip_prefix("Bearer ") {
+        Some(rest) => rest.trim(),
+        None => return params,
+    };
+
+    // a scope holds commas, values can't be split on them
+    while let Some((key, value_and_rest)) = rest.split_once("=\"") {
+        let (value, next) = match value_and_rest.split_once('"') {
+            Some(value_and_next) => value_and_next,
+            None => break,
+        };
+
+        params.push((key.trim().to_string(), value.to_string()));
+        rest = next.trim_start_matches(|c| c == ',' || c == ' ');
+    }
+
+    params
+}
+
+/// whether an image is in its registry, asked to the registry API with the credentials of a docker `config.json`
+pub fn does_image_exist_in_registry(image_url: &str, docker_config_json: &str) -> Result<bool, SimpleError> {
+    let (host, repository, tag) = match parse_image_url(image_url) {
+        Some(image) => image,
+        None => {
+            return Err(SimpleError::new(
+                SimpleErrorKind::Other,
+                Some(format!("invalid image URL {}", image_url)),
+            ))
+        }
+    };
+
+    // the config holds the credentials of a single registry
+    let basic_auth = serde_json::from_str::<serde_json::Value>(docker_config_json)
+        .ok()
+        .and_then(|config| {
+            config["auths"]
+                .as_object()
+                .and_then(|auths| auths.values().next())
+                .and_then(|auth| auth["auth"].as_str())
+                .map(|auth| format!("Basic {}", auth))
+        });
+
+    let client = reqwest::blocking::Client::new();
+    let manifest_url = format!("https://{}/v2/{}/manifests/{}", host, repository, tag);
+    let get_manifest = |authorization: Option<&str>| {
+        let mut request = client
+            .head(manifest_url.as_str())
+            .header(reqwest::header::ACCEPT, MANIFEST_MEDIA_TYPES.join(","));
+        if let Some(authorization) = authorization {
+            request = request.header(reqwest::header::AUTHORIZATION, authorization);
+        }
+
+        request.send()
+    };
+
+    let to_simple_error = |err: reqwest::Error| {
+        SimpleError::new(
+            SimpleErrorKind::Other,
+            Some(format!(
+                "error while getting the manifest of image {}: {:?}",
+                image_url, err
+            )),
+        )
+    };
+
+    let mut response = get_manifest(basic_auth.as_deref()).map_err(to_simple_error)?;
+
+    // most registries only accept a token, given by their authentication service against the credentials
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        let challenge = response
+            .headers()
+            .get(reqwest::header::WWW_AUTHENTICATE)
+            .and_then(|header| header.to_str().ok())
+            .map(parse_bearer_challenge)
+            .unwrap_or_default();
+
+        if let Some((_, realm)) = challenge.iter().find(|(key, _)| key == "realm") {
+            let query = challenge.iter().filter(|(key, _)| key != "realm").collect::<Vec<_>>();
+            let mut request = client.get(realm.as_str()).query(&query);
+            if let Some(basic_auth) = basic_auth.as_ref() {
+                request = request.header(reqwest::header::AUTHORIZATION, basic_auth.as_str());
+            }
+
+            let token_response = request
+                .send()
+                .and_then(|response| response.error_for_status())
+                .and_then(|response| response.json::<serde_json::Value>())
+                .map_err(to_simple_error)?;
+
+            if let Some(token) = token_response["token"]
+                .as_str()
+                .or_else(|| token_response["access_token"].as_str())
+            {
+                response = get_manifest(Some(format!("Bearer {}", token).as_str())).map_err(to_simple_error)?;
+            }
+        }
+    }
+
+    match response.status() {
+        status if status.is_success() => Ok(true),
+        reqwest::StatusCode::NOT_FOUND => Ok(false),
+        status => Err(SimpleError::new(
+            SimpleErrorKind::Other,
+            Some(format!(
+                "unexpected status {} while getting the manifest of image {}",
+                status, image_url
+            )),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::container_registry::utilities::{docker_config_json, parse_bearer_challenge, parse_image_url};
+
+    #[test]
+    fn test_docker_config_json() {
+        let config = docker_config_json("registry.digitalocean.com", "token", "token");
+
+        assert_eq!(
+            config,
+            r#"{"auths":{"registry.digitalocean.com":{"auth":"dG9rZW46dG9rZW4="}}}"#
+        );
+    }
+
+    #[test]
+    fn test_parse_image_url() {
+        let image =
+            |host: &str, repository: &str, tag: &str| Some((host.to_string(), repository.to_string(), tag.to_string()));
+
+        assert_eq!(
+            parse_image_url("123456789.dkr.ecr.us-east-2.amazonaws.com/app-123:tag"),
+            image("123456789.dkr.ecr.us-east-2.amazonaws.com", "app-123", "tag")
+        );
+        assert_eq!(
+            parse_image_url("localhost:5000/qovery/app:tag"),
+            image("localhost:5000", "qovery/app", "tag")
+        );
+        assert_eq!(
+            parse_image_url("qovery/app:tag"),
+            image("registry-1.docker.io", "qovery/app", "tag")
+        );
+        assert_eq!(
+            parse_image_url("app:tag"),
+            image("registry-1.docker.io", "library/app", "tag")
+        );
+        assert_eq!(parse_image_url("localhost:5000/app"), None);
+    }
+
+    #[test]
+    fn test_parse_bearer_challenge() {
+        assert_eq!(
+            parse_bearer_challenge(
+                r#"Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="repository:qovery/app:pull,push""#
+            ),
+            vec![
+                ("realm".to_string(), "https://auth.docker.io/token".to_string()),
+                ("service".to_string(), "registry.docker.io".to_string()),
+                ("scope".to_string(), "repository:qovery/app:pull,push".to_string()),
+            ]
+        );
+        assert!(parse_bearer_challenge(r#"Basic realm="registry""#).is_empty());
+    }
+}
@@ -1,6 +1,10 @@
 use crypto::digest::Digest;
+use crypto::md5::Md5;
 use crypto::sha1::Sha1;
 
+// alphabet of the crypt(3) base64 encoding
+const CRYPT_BASE64: &[u8] = b"./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
 pub fn to_sha1(input: &str) -> String {
     let mut hasher = Sha1::new();
     hasher.input_str(input);
@@ -13,6 +17,94 @@ pub fn to_sha1_truncate_16(input: &str) -> String {
     hash_str
 }
 
+/// htpasswd entry of a user, with the salted APR1 (MD5) password scheme supported by nginx basic authentication.
+/// The salt is derived from `salt_seed` and the credentials, so that the same user always gets the same entry.
+pub fn to_htpasswd_apr1(salt_seed: &str, username: &str, password: &str) -> String {
+    let mut hasher = Sha1::new();
+    hasher.input_str(format!("{}:{}:{}", salt_seed, username, password).as_str());
+    let mut digest = [0u8; 20];
+    hasher.result(&mut digest);
+
+    let salt = digest
+        .iter()
+        .take(8)
+        .map(|b| CRYPT_BASE64[(*b & 0x3f) as usize] as char)
+        .collect::<String>();
+
+    format!("{}:{}", username, apr1_crypt(password, salt.as_str()))
+}
+
+/// Apache variant of the MD5 based crypt(3) algorithm
+fn apr1_crypt(password: &str, salt: &str) -> String {
+    let magic = "$apr1$";
+    let password = password.as_bytes();
+    let salt = &salt.as_bytes()[..salt.len().min(8)];
+
+    let mut alternate = [0u8; 16];
+    let mut hasher = Md5::new();
+    hasher.input(password);
+    hasher.input(salt);
+    hasher.input(password);
+    hasher.result(&mut alternate);
+
+    let mut hasher = Md5::new();
+    hasher.input(password);
+    hasher.input(magic.as_bytes());
+    hasher.input(salt);
+    for chunk in password.chunks(16) {
+        hasher.input(&alternate[..chunk.len()]);
+    }
+    let mut i = password.len();
+    while i > 0 {
+        if i & 1 == 1 {
+            hasher.input(&[0u8]);
+        } else {
+            hasher.input(&password[..1]);
+        }
+        i >>= 1;
+    }
+
+    let mut hash = [0u8; 16];
+    hasher.result(&mut hash);
+
+    // key stretching
+    for round in 0..1000 {
+        let mut hasher = Md5::new();
+        if round & 1 == 1 {
+            hasher.input(password);
+        } else {
+            hasher.input(&hash);
+        }
+        if round % 3 != 0 {
+            hasher.input(salt);
+        }
+        if round % 7 != 0 {
+            hasher.input(password);
+        }
+        if round & 1 == 1 {
+            hasher.input(&hash);
+        } else {
+            hasher.input(password);
+        }
+        hasher.result(&mut hash);
+    }
+
+    let mut encoded = String::with_capacity(22);
+    let mut encode = |value: u32, length: usize| {
+        let mut value = value;
+        for _ in 0..length {
+            encoded.push(CRYPT_BASE64[(value & 0x3f) as usize] as char);
+            value >>= 6;
+        }
+    };
+    for (a, b, c) in &[(0, 6, 12), (1, 7, 13), (2, 8, 14), (3, 9, 15), (4, 10, 5)] {
+        encode((hash[*a] as u32) << 16 | (hash[*b] as u32) << 8 | hash[*c] as u32, 4);
+    }
+    encode(hash[11] as u32, 2);
+
+    format!("{}{}${}", magic, String::from_utf8_lossy(salt), encoded)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,6 +145,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_apr1_crypt() {
+        // same as `openssl passwd -apr1 -salt <salt> <password>`
+        assert_eq!(
+            apr1_crypt("myPassword", "r31....."),
+            "$apr1$r31.....$HqJZimcKQFAMYayBlzkrA/"
+        );
+        assert_eq!(
+            apr1_crypt("password", "qovery12"),
+            "$apr1$qovery12$mcegIgWo7IgcLq0pa56ZS1"
+        );
+    }
+
+    #[test]
+    fn test_to_htpasswd_apr1() {
+        let entry = to_htpasswd_apr1("router-id", "qovery", "password");
+        let salt = &entry["qovery:$apr1$".len().."qovery:$apr1$".len() + 8];
+
+        assert_eq!(entry, format!("qovery:{}", apr1_crypt("password", salt)));
+        // stable across renders, so that the secret of the router doesn't change on each deployment
+        assert_eq!(entry, to_htpasswd_apr1("router-id", "qovery", "password"));
+        // salted, the same password of another router or user differs
+        assert_ne!(entry, to_htpasswd_apr1("other-router-id", "qovery", "password"));
+        assert_ne!(
+            &entry["qovery:".len()..],
+            &to_htpasswd_apr1("router-id", "other", "password")["other:".len()..]
+        );
+    }
+
     #[test]
     fn test_to_sha1_truncate_16() {
         // setup:
@@ -44,6 +44,7 @@ pub enum EngineErrorScope {
     Application(Id, Name),
     Router(Id, Name),
     ExternalService(Id, Name),
+    Job(Id, Name),
 }
 
 #[derive(Debug)]
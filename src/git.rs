@@ -1,25 +1,328 @@
-use std::path::Path;
+use std::fs::{self, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
 
-use git2::build::RepoBuilder;
-use git2::{Error, Oid, Repository};
+use git2::build::{CheckoutBuilder, RepoBuilder};
+use git2::{Cred, CredentialType, Error, FetchOptions, Oid, RemoteCallbacks, Repository, SubmoduleUpdateOptions};
+
+// the SSH username may be asked before the key itself
+const MAX_AUTHENTICATION_ATTEMPTS: u8 = 3;
+
+// GIT_FETCH_DEPTH_UNSHALLOW of libgit2
+const FETCH_DEPTH_UNSHALLOW: i32 = i32::MAX;
+
+const MIRROR_LAST_USE_FILE: &str = "qovery-last-use";
+const MIRROR_LOCK_RETRY_DELAY: Duration = Duration::from_secs(1);
+// the holder of a lock refreshes its modification time, a lock which is not refreshed anymore has been left by a killed engine
+const MIRROR_LOCK_REFRESH_DELAY: Duration = Duration::from_secs(15);
+const MIRROR_LOCK_STALE_AFTER: Duration = Duration::from_secs(2 * 60);
 
-/// TODO support SSH repository_url - we assume that the repository URL starts with HTTPS
-/// TODO support git submodules
 pub fn clone<P>(repository_url: &str, into_dir: P, credentials: &Option<Credentials>) -> Result<Repository, Error>
 where
     P: AsRef<Path>,
 {
-    let final_repository_url = match credentials {
-        Some(c) => format!(
-            "https://{}:{}@{}",
-            c.login,
-            c.password,
-            repository_url.replace("https://", "")
-        ),
-        None => repository_url.to_string(),
+    RepoBuilder::new()
+        .fetch_options(fetch_options(credentials))
+        .clone(repository_url, into_dir.as_ref())
+}
+
+/// libgit2 calls back for credentials until the authentication succeeds, it has to be stopped once they are refused
+fn fetch_options(credentials: &Option<Credentials>) -> FetchOptions<'_> {
+    let mut callbacks = RemoteCallbacks::new();
+
+    if let Some(credentials) = credentials {
+        let mut attempts = 0;
+
+        callbacks.credentials(move |url, username_from_url, allowed_types| {
+            attempts += 1;
+            if attempts > MAX_AUTHENTICATION_ATTEMPTS {
+                return Err(Error::from_str(
+                    format!(
+                        "authentication on repository {} has failed with the given credentials",
+                        url
+                    )
+                    .as_str(),
+                ));
+            }
+
+            credentials.to_cred(username_from_url, allowed_types)
+        });
+    }
+
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
+    fetch_options
+}
+
+/// shallow bare repository keeping the objects of a remote repository between builds.
+/// Only the commit is fetched, without its history, when the server allows it. Otherwise the history of the branch
+/// holding the commit is fetched. The caller must hold the lock of the mirror, see `lock_mirror`.
+pub fn fetch_into_mirror<P>(
+    repository_url: &str,
+    commit_id: &str,
+    branch: &str,
+    mirror_dir: P,
+    credentials: &Option<Credentials>,
+) -> Result<Repository, Error>
+where
+    P: AsRef<Path>,
+{
+    let oid = Oid::from_str(commit_id)?;
+
+    let mirror = match Repository::open_bare(mirror_dir.as_ref()) {
+        Ok(mirror) => mirror,
+        Err(_) => Repository::init_bare(mirror_dir.as_ref())?,
     };
 
-    RepoBuilder::new().clone(final_repository_url.as_str(), into_dir.as_ref())
+    // the last use is kept to evict the mirrors which are not used anymore
+    if let Err(err) = fs::write(mirror.path().join(MIRROR_LAST_USE_FILE), "") {
+        return Err(Error::from_str(
+            format!("can't mark the mirror of {} as used: {}", repository_url, err).as_str(),
+        ));
+    }
+
+    // nothing to fetch when the commit is rebuilt
+    if mirror.find_commit(oid).is_ok() {
+        return Ok(mirror);
+    }
+
+    // GitHub and GitLab send a commit which is not advertised by a reference, some servers don't
+    let commit_refspec = format!("+{0}:refs/commits/{0}", commit_id);
+    let commit_fetched = mirror
+        .remote_anonymous(repository_url)
+        .and_then(|mut remote| {
+            let mut options = fetch_options(credentials);
+            options.depth(1);
+            remote.fetch(&[commit_refspec.as_str()], Some(&mut options), None)
+        })
+        .is_ok();
+
+    if !commit_fetched || mirror.find_commit(oid).is_err() {
+        // the commit may be older than the commits the previous fetches stopped at
+        let branch_refspec = format!("+refs/heads/{0}:refs/heads/{0}", branch);
+        let mut options = fetch_options(credentials);
+        options.depth(FETCH_DEPTH_UNSHALLOW);
+
+        let mut remote = mirror.remote_anonymous(repository_url)?;
+        remote.fetch(&[branch_refspec.as_str()], Some(&mut options), None)?;
+    }
+
+    if let Err(e) = mirror.find_commit(oid) {
+        let mut x = git2::Error::from_str(
+            format!(
+                "Commit ID {} on branch {} of repository {} was not found",
+                commit_id, branch, repository_url
+            )
+            .as_ref(),
+        );
+        x.set_code(e.code());
+        x.set_class(e.class());
+        return Err(x);
+    }
+
+    Ok(mirror)
+}
+
+/// exclusive lock on a mirror, shared by the builds running on the same host. It is released when dropped.
+pub struct MirrorLock {
+    path: PathBuf,
+    // the refresh of the lock stops once the sender is dropped
+    refresh: Option<(Sender<()>, JoinHandle<()>)>,
+}
+
+impl MirrorLock {
+    fn new(path: PathBuf) -> Self {
+        let (sender, receiver) = mpsc::channel::<()>();
+        let refreshed_path = path.clone();
+
+        let handle = thread::spawn(move || {
+            while let Err(RecvTimeoutError::Timeout) = receiver.recv_timeout(MIRROR_LOCK_REFRESH_DELAY) {
+                if let Err(err) = OpenOptions::new()
+                    .write(true)
+                    .open(&refreshed_path)
+                    .and_then(|file| file.set_modified(SystemTime::now()))
+                {
+                    warn!("can't refresh the lock {:?}: {}", refreshed_path, err);
+                }
+            }
+        });
+
+        MirrorLock {
+            path,
+            refresh: Some((sender, handle)),
+        }
+    }
+}
+
+impl Drop for MirrorLock {
+    fn drop(&mut self) {
+        if let Some((sender, handle)) = self.refresh.take() {
+            drop(sender);
+            let _ = handle.join();
+        }
+
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn is_mirror_lock_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .map(|modified| modified.elapsed().unwrap_or_default() > MIRROR_LOCK_STALE_AFTER)
+        .unwrap_or(false)
+}
+
+/// the lock is renamed before being removed, so that only one waiter removes it. Another waiter may have removed it
+/// and locked the mirror since its check: such a lock is not stale, it's put back.
+fn remove_stale_mirror_lock(path: &Path) {
+    let mut removed_path = path.as_os_str().to_owned();
+    removed_path.push(format!(".stale-{}", rand::random::<u32>()));
+    let removed_path = PathBuf::from(removed_path);
+
+    if fs::rename(path, &removed_path).is_err() {
+        return;
+    }
+
+    if is_mirror_lock_stale(&removed_path) {
+        warn!("removing the stale lock {:?}", path);
+    } else if let Err(err) = fs::hard_link(&removed_path, path) {
+        warn!("can't put back the lock {:?}: {}", path, err);
+    }
+
+    let _ = fs::remove_file(&removed_path);
+}
+
+fn mirror_lock_path(mirror_dir: &Path) -> PathBuf {
+    let mut lock_path = mirror_dir.as_os_str().to_owned();
+    lock_path.push(".lock");
+    PathBuf::from(lock_path)
+}
+
+fn try_lock_mirror(mirror_dir: &Path) -> std::io::Result<Option<MirrorLock>> {
+    let path = mirror_lock_path(mirror_dir);
+
+    match OpenOptions::new().write(true).create_new(true).open(&path) {
+        Ok(_) => Ok(Some(MirrorLock::new(path))),
+        Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+            // the lock of an engine which has been killed is never released
+            if is_mirror_lock_stale(&path) {
+                remove_stale_mirror_lock(&path);
+            }
+
+            Ok(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// wait until the mirror is not used by another build
+pub fn lock_mirror<P>(mirror_dir: P, timeout: Duration) -> Result<MirrorLock, Error>
+where
+    P: AsRef<Path>,
+{
+    let mirror_dir = mirror_dir.as_ref();
+    if let Some(parent) = mirror_dir.parent() {
+        if let Err(err) = fs::create_dir_all(parent) {
+            return Err(Error::from_str(
+                format!("can't create the directory of the mirror {:?}: {}", mirror_dir, err).as_str(),
+            ));
+        }
+    }
+
+    let started_at = Instant::now();
+    loop {
+        match try_lock_mirror(mirror_dir) {
+            Ok(Some(lock)) => return Ok(lock),
+            Ok(None) if started_at.elapsed() < timeout => thread::sleep(MIRROR_LOCK_RETRY_DELAY),
+            Ok(None) => {
+                return Err(Error::from_str(
+                    format!("mirror {:?} is still locked after {:?}", mirror_dir, timeout).as_str(),
+                ))
+            }
+            Err(err) => {
+                return Err(Error::from_str(
+                    format!("can't lock the mirror {:?}: {}", mirror_dir, err).as_str(),
+                ))
+            }
+        }
+    }
+}
+
+/// remove the mirrors of `mirrors_dir` which are not locked and have not been used for `max_unused`
+pub fn evict_unused_mirrors<P>(mirrors_dir: P, max_unused: Duration) -> Result<(), std::io::Error>
+where
+    P: AsRef<Path>,
+{
+    let entries = match fs::read_dir(mirrors_dir.as_ref()) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+
+    for entry in entries {
+        let mirror_dir = entry?.path();
+        if !mirror_dir.is_dir() {
+            continue;
+        }
+
+        let last_use = fs::metadata(mirror_dir.join(MIRROR_LAST_USE_FILE))
+            .or_else(|_| fs::metadata(&mirror_dir))
+            .and_then(|metadata| metadata.modified())?;
+        if last_use.elapsed().unwrap_or_default() < max_unused {
+            continue;
+        }
+
+        // a mirror in use is evicted by a later build
+        if let Some(_lock) = try_lock_mirror(&mirror_dir)? {
+            info!("evicting the git mirror {:?}, unused for {:?}", mirror_dir, max_unused);
+            fs::remove_dir_all(&mirror_dir)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// checkout a commit of a mirror into a repository sharing its objects, like `git clone --shared` does.
+/// Only `root_path` and the submodules declaration are checked out, unless `root_path` is the repository root.
+pub fn checkout_from_mirror<P>(
+    mirror: &Repository,
+    repository_url: &str,
+    commit_id: &str,
+    into_dir: P,
+    root_path: &str,
+) -> Result<Repository, Error>
+where
+    P: AsRef<Path>,
+{
+    let oid = Oid::from_str(commit_id)?;
+    let repo = Repository::init(into_dir.as_ref())?;
+
+    // objects are read from the mirror instead of being copied
+    let alternates = format!("{}\n", mirror.path().join("objects").to_str().unwrap_or_default());
+    if let Err(err) = std::fs::write(repo.path().join("objects/info/alternates"), alternates) {
+        return Err(Error::from_str(
+            format!("can't share the objects of the mirror of {}: {}", repository_url, err).as_str(),
+        ));
+    }
+
+    // relative submodule URLs are resolved against it
+    let _ = repo.remote("origin", repository_url)?;
+    repo.set_head_detached(oid)?;
+
+    let mut checkout = CheckoutBuilder::new();
+    checkout.force();
+
+    let root_path = root_path.trim_matches('/');
+    if !root_path.is_empty() && root_path != "." {
+        checkout.path(root_path).path(".gitmodules");
+    }
+
+    repo.checkout_head(Some(&mut checkout))?;
+
+    Ok(repo)
 }
 
 pub fn checkout(repo: &Repository, commit_id: &str, repo_url: &str) -> Result<(), Error> {
@@ -68,13 +371,17 @@ pub fn checkout(repo: &Repository, commit_id: &str, repo_url: &str) -> Result<()
     repo.set_head(&("refs/heads/".to_owned() + &commit_id))
 }
 
-pub fn checkout_submodules(repo: &Repository) -> Result<(), Error> {
+/// submodules are fetched with the credentials of their parent repository
+pub fn checkout_submodules(repo: &Repository, credentials: &Option<Credentials>) -> Result<(), Error> {
     match repo.submodules() {
         Ok(submodules) => {
             for mut submodule in submodules {
                 info!("getting submodule {:?} from {:?}", submodule.name(), submodule.url());
 
-                match submodule.update(true, None) {
+                let mut update_options = SubmoduleUpdateOptions::new();
+                update_options.fetch(fetch_options(credentials));
+
+                match submodule.update(true, Some(&mut update_options)) {
                     Err(e) => return Err(e),
                     _ => (),
                 }
@@ -86,7 +393,290 @@ pub fn checkout_submodules(repo: &Repository) -> Result<(), Error> {
     Ok(())
 }
 
-pub struct Credentials {
-    pub login: String,
-    pub password: String,
+pub enum Credentials {
+    // E.g: an access token as password
+    Https {
+        login: String,
+        password: String,
+    },
+    // PEM or OpenSSH encoded private key, E.g: a deploy key
+    SshKey {
+        username: String,
+        private_key: String,
+        passphrase: Option<String>,
+    },
+    // keys loaded into the ssh-agent of the engine host
+    SshAgent {
+        username: String,
+    },
+}
+
+impl Credentials {
+    fn to_cred(&self, username_from_url: Option<&str>, allowed_types: CredentialType) -> Result<Cred, Error> {
+        match self {
+            Credentials::Https { login, password } => {
+                if !allowed_types.contains(CredentialType::USER_PASS_PLAINTEXT) {
+                    return Err(Error::from_str(
+                        "HTTPS credentials are given but the repository is not cloned through HTTPS",
+                    ));
+                }
+
+                Cred::userpass_plaintext(login, password)
+            }
+            Credentials::SshKey { username, .. } | Credentials::SshAgent { username } => {
+                // the username of an URL like git@github.com:Qovery/engine.git takes precedence
+                let username = username_from_url.unwrap_or(username);
+
+                if allowed_types.contains(CredentialType::USERNAME) {
+                    return Cred::username(username);
+                }
+
+                if !allowed_types.contains(CredentialType::SSH_KEY) {
+                    return Err(Error::from_str(
+                        "SSH credentials are given but the repository is not cloned through SSH",
+                    ));
+                }
+
+                match self {
+                    Credentials::SshKey {
+                        private_key,
+                        passphrase,
+                        ..
+                    } => Cred::ssh_key_from_memory(username, None, private_key, passphrase.as_deref()),
+                    _ => Cred::ssh_key_from_agent(username),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate tempdir;
+
+    use super::*;
+    use std::fs;
+    use tempdir::TempDir;
+
+    fn commit_files(repo: &Repository, files: &[(&str, &str)]) -> Oid {
+        let workdir = repo.workdir().unwrap();
+        let mut index = repo.index().unwrap();
+
+        for (path, content) in files {
+            let file_path = workdir.join(path);
+            fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+            fs::write(&file_path, content).unwrap();
+            index.add_path(Path::new(path)).unwrap();
+        }
+
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = git2::Signature::now("qovery", "test@qovery.com").unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents = parent.iter().collect::<Vec<_>>();
+        repo.commit(Some("HEAD"), &signature, &signature, "commit", &tree, &parents)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_checkout_from_mirror() {
+        let tmp_dir = TempDir::new("git_mirror").expect("error creating temporary dir");
+        let origin = Repository::init(tmp_dir.path().join("origin")).unwrap();
+        let commit_id = commit_files(
+            &origin,
+            &[("backend/Dockerfile", "FROM alpine"), ("frontend/index.html", "")],
+        )
+        .to_string();
+        let branch = origin.head().unwrap().shorthand().unwrap().to_string();
+        let mirror_dir = tmp_dir.path().join("mirror");
+
+        let (_daemon, port) = GitDaemon::start(tmp_dir.path());
+        let origin_url = format!("git://127.0.0.1:{}/origin", port);
+
+        let mirror = fetch_into_mirror(
+            origin_url.as_str(),
+            commit_id.as_str(),
+            branch.as_str(),
+            &mirror_dir,
+            &None,
+        )
+        .unwrap();
+        assert!(mirror.is_bare());
+
+        // only root path is checked out
+        let build_dir = tmp_dir.path().join("build");
+        let repo =
+            checkout_from_mirror(&mirror, origin_url.as_str(), commit_id.as_str(), &build_dir, "/backend").unwrap();
+        assert_eq!(repo.head().unwrap().target().unwrap().to_string(), commit_id);
+        assert!(build_dir.join("backend/Dockerfile").exists());
+        assert!(!build_dir.join("frontend/index.html").exists());
+
+        // the commit is already in the mirror
+        let mirror = fetch_into_mirror(
+            "file:///does-not-exist",
+            commit_id.as_str(),
+            branch.as_str(),
+            &mirror_dir,
+            &None,
+        )
+        .unwrap();
+        let full_build_dir = tmp_dir.path().join("full-build");
+        let _ = checkout_from_mirror(&mirror, origin_url.as_str(), commit_id.as_str(), &full_build_dir, "/").unwrap();
+        assert!(full_build_dir.join("frontend/index.html").exists());
+
+        assert!(fetch_into_mirror(
+            origin_url.as_str(),
+            "0000000000000000000000000000000000000001",
+            branch.as_str(),
+            &mirror_dir,
+            &None
+        )
+        .is_err());
+    }
+
+    /// the local transport of libgit2 ignores the fetch depth, the repositories are served by the git protocol
+    struct GitDaemon(std::process::Child);
+
+    impl GitDaemon {
+        fn start(base_path: &Path) -> (GitDaemon, u16) {
+            let port = std::net::TcpListener::bind("127.0.0.1:0")
+                .unwrap()
+                .local_addr()
+                .unwrap()
+                .port();
+            // `git daemon` runs git-daemon as a child process, which would outlive the wrapper once it is killed
+            let exec_path = std::process::Command::new("git")
+                .arg("--exec-path")
+                .output()
+                .expect("git exec path can't be found");
+            let daemon = std::process::Command::new(
+                Path::new(String::from_utf8_lossy(&exec_path.stdout).trim()).join("git-daemon"),
+            )
+            .args(["--export-all", "--reuseaddr", "--listen=127.0.0.1"])
+            .arg(format!("--port={}", port))
+            .arg(format!("--base-path={}", base_path.to_str().unwrap()))
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .expect("git daemon can't be started");
+
+            for _ in 0..50 {
+                if std::net::TcpStream::connect(("127.0.0.1", port)).is_ok() {
+                    break;
+                }
+                thread::sleep(Duration::from_millis(100));
+            }
+
+            (GitDaemon(daemon), port)
+        }
+    }
+
+    impl Drop for GitDaemon {
+        fn drop(&mut self) {
+            let _ = self.0.kill();
+            let _ = self.0.wait();
+        }
+    }
+
+    #[test]
+    fn test_fetch_into_mirror_is_shallow() {
+        let tmp_dir = TempDir::new("git_mirror").expect("error creating temporary dir");
+        let origin = Repository::init(tmp_dir.path().join("origin")).unwrap();
+        let first_commit_id = commit_files(&origin, &[("Dockerfile", "FROM alpine")]).to_string();
+        let commit_id = commit_files(&origin, &[("Dockerfile", "FROM debian")]).to_string();
+        let branch = origin.head().unwrap().shorthand().unwrap().to_string();
+        let mirror_dir = tmp_dir.path().join("mirror");
+        let mut origin_config = origin.config().unwrap();
+        origin_config
+            .set_bool("uploadpack.allowReachableSHA1InWant", true)
+            .unwrap();
+
+        let (_daemon, port) = GitDaemon::start(tmp_dir.path());
+        let origin_url = format!("git://127.0.0.1:{}/origin", port);
+
+        // the history of the commit is not fetched
+        let mirror = fetch_into_mirror(
+            origin_url.as_str(),
+            commit_id.as_str(),
+            branch.as_str(),
+            &mirror_dir,
+            &None,
+        )
+        .unwrap();
+        assert!(mirror.is_shallow());
+        assert!(mirror
+            .find_commit(Oid::from_str(first_commit_id.as_str()).unwrap())
+            .is_err());
+
+        // the server refuses a commit which is not advertised, the history of the branch is fetched
+        origin_config
+            .set_bool("uploadpack.allowReachableSHA1InWant", false)
+            .unwrap();
+        let mirror = fetch_into_mirror(
+            origin_url.as_str(),
+            first_commit_id.as_str(),
+            branch.as_str(),
+            &mirror_dir,
+            &None,
+        )
+        .unwrap();
+        assert!(!mirror.is_shallow());
+    }
+
+    #[test]
+    fn test_mirror_lock_and_eviction() {
+        let tmp_dir = TempDir::new("git_mirrors").expect("error creating temporary dir");
+        let mirror_dir = tmp_dir.path().join("mirror");
+        let _ = Repository::init_bare(&mirror_dir).unwrap();
+
+        let lock = lock_mirror(&mirror_dir, Duration::from_secs(0)).unwrap();
+        assert!(lock_mirror(&mirror_dir, Duration::from_secs(0)).is_err());
+
+        // a locked mirror is kept
+        evict_unused_mirrors(tmp_dir.path(), Duration::from_secs(0)).unwrap();
+        assert!(mirror_dir.exists());
+
+        drop(lock);
+        evict_unused_mirrors(tmp_dir.path(), Duration::from_secs(3600)).unwrap();
+        assert!(mirror_dir.exists());
+
+        evict_unused_mirrors(tmp_dir.path(), Duration::from_secs(0)).unwrap();
+        assert!(!mirror_dir.exists());
+        assert!(!mirror_lock_path(&mirror_dir).exists());
+    }
+
+    #[test]
+    fn test_stale_mirror_lock() {
+        let tmp_dir = TempDir::new("git_mirrors").expect("error creating temporary dir");
+        let mirror_dir = tmp_dir.path().join("mirror");
+        let lock_path = mirror_lock_path(&mirror_dir);
+        let set_lock_age = |age: Duration| {
+            OpenOptions::new()
+                .write(true)
+                .create(true)
+                .open(&lock_path)
+                .and_then(|file| file.set_modified(SystemTime::now() - age))
+                .unwrap();
+        };
+
+        // the lock of a running engine is kept
+        set_lock_age(Duration::from_secs(30));
+        assert!(lock_mirror(&mirror_dir, Duration::from_secs(0)).is_err());
+        assert!(lock_path.exists());
+
+        // the one of a killed engine is removed, the next waiter gets the mirror
+        set_lock_age(MIRROR_LOCK_STALE_AFTER * 2);
+        assert!(lock_mirror(&mirror_dir, Duration::from_secs(0)).is_err());
+        assert!(!lock_path.exists());
+        let lock = lock_mirror(&mirror_dir, Duration::from_secs(0)).unwrap();
+        assert!(!is_mirror_lock_stale(&lock_path));
+
+        // only stale locks are removed
+        remove_stale_mirror_lock(&lock_path);
+        assert!(lock_path.exists());
+        assert_eq!(fs::read_dir(tmp_dir.path()).unwrap().count(), 1);
+
+        drop(lock);
+        assert!(!lock_path.exists());
+    }
 }
@@ -1,9 +1,16 @@
+use rusoto_core::Region;
+use rusoto_credential::AwsCredentials;
+use rusoto_s3::util::{PreSignedRequest, PreSignedRequestOption};
+use rusoto_s3::{GetObjectRequest, PutObjectRequest};
 use serde::{Deserialize, Serialize};
 
+use crate::constants::{AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY};
 use crate::error::{EngineError, EngineErrorCause, EngineErrorScope};
 use crate::models::{Context, StringPath};
 use std::fs::File;
+use std::time::Duration;
 
+pub mod gcs;
 pub mod s3;
 pub mod spaces;
 
@@ -16,10 +23,71 @@ pub trait ObjectStorage {
         format!("{} ({})", self.name(), self.id())
     }
     fn is_valid(&self) -> Result<(), EngineError>;
+    /// S3 compatible endpoint to use instead of the AWS one (None means AWS S3)
+    fn endpoint_url(&self) -> Option<String>;
+    /// environment variables containing credentials, usable by any S3 compatible client
+    fn credentials_environment_variables(&self) -> Vec<(&str, &str)>;
     fn create_bucket(&self, bucket_name: &str) -> Result<(), EngineError>;
     fn delete_bucket(&self, bucket_name: &str) -> Result<(), EngineError>;
     fn get(&self, bucket_name: &str, object_key: &str, use_cache: bool) -> Result<(StringPath, File), EngineError>;
     fn put(&self, bucket_name: &str, object_key: &str, file_path: &str) -> Result<(), EngineError>;
+    /// URL reading or writing a single object until it expires, to give access to it without the credentials.
+    /// A single PUT is limited to 5GB by S3 and Spaces.
+    fn presigned_url(
+        &self,
+        region: &str,
+        bucket_name: &str,
+        object_key: &str,
+        access: ObjectAccess,
+        expires_in: Duration,
+    ) -> Result<String, EngineError> {
+        let region = match self.endpoint_url() {
+            Some(endpoint) => Region::Custom {
+                name: region.to_string(),
+                endpoint,
+            },
+            None => match region.parse::<Region>() {
+                Ok(region) => region,
+                Err(err) => {
+                    return Err(self.engine_error(
+                        EngineErrorCause::Internal,
+                        format!("can't presign object {}, invalid region: {:?}", object_key, err),
+                    ))
+                }
+            },
+        };
+
+        let credentials = self.credentials_environment_variables();
+        let credential = |key: &str| {
+            credentials
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, value)| value.to_string())
+                .unwrap_or_default()
+        };
+        let credentials = AwsCredentials::new(
+            credential(AWS_ACCESS_KEY_ID),
+            credential(AWS_SECRET_ACCESS_KEY),
+            None,
+            None,
+        );
+        let option = PreSignedRequestOption { expires_in };
+
+        Ok(match access {
+            ObjectAccess::Read => GetObjectRequest {
+                bucket: bucket_name.to_string(),
+                key: object_key.to_string(),
+                ..Default::default()
+            }
+            .get_presigned_url(&region, &credentials, &option),
+            ObjectAccess::Write => PutObjectRequest {
+                bucket: bucket_name.to_string(),
+                key: object_key.to_string(),
+                ..Default::default()
+            }
+            .get_presigned_url(&region, &credentials, &option),
+        })
+    }
     fn engine_error_scope(&self) -> EngineErrorScope {
         EngineErrorScope::ObjectStorage(self.id().to_string(), self.name().to_string())
     }
@@ -33,9 +101,15 @@ pub trait ObjectStorage {
     }
 }
 
+pub enum ObjectAccess {
+    Read,
+    Write,
+}
+
 #[derive(Serialize, Deserialize, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Kind {
     S3,
     Spaces,
+    Gcs,
 }
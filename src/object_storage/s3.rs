@@ -26,13 +26,6 @@ impl S3 {
             secret_access_key,
         }
     }
-
-    fn credentials_environment_variables(&self) -> Vec<(&str, &str)> {
-        vec![
-            (AWS_ACCESS_KEY_ID, self.access_key_id.as_str()),
-            (AWS_SECRET_ACCESS_KEY, self.secret_access_key.as_str()),
-        ]
-    }
 }
 
 impl ObjectStorage for S3 {
@@ -57,6 +50,17 @@ impl ObjectStorage for S3 {
         Ok(())
     }
 
+    fn endpoint_url(&self) -> Option<String> {
+        None
+    }
+
+    fn credentials_environment_variables(&self) -> Vec<(&str, &str)> {
+        vec![
+            (AWS_ACCESS_KEY_ID, self.access_key_id.as_str()),
+            (AWS_SECRET_ACCESS_KEY, self.secret_access_key.as_str()),
+        ]
+    }
+
     fn create_bucket(&self, bucket_name: &str) -> Result<(), EngineError> {
         cast_simple_error_to_engine_error(
             self.engine_error_scope(),
@@ -8,8 +8,9 @@ use rusoto_credential::StaticProvider;
 use rusoto_s3::{GetObjectRequest, S3Client, S3};
 use tokio::io;
 
+use crate::constants::{AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY};
 use crate::error::EngineErrorCause::Internal;
-use crate::error::{EngineError, EngineErrorCause};
+use crate::error::{cast_simple_error_to_engine_error, EngineError, EngineErrorCause};
 use crate::models::{Context, StringPath};
 use crate::object_storage::{Kind, ObjectStorage};
 use crate::runtime;
@@ -118,12 +119,38 @@ impl ObjectStorage for Spaces {
         Ok(())
     }
 
+    fn endpoint_url(&self) -> Option<String> {
+        Some(format!("https://{}.digitaloceanspaces.com", self.region))
+    }
+
+    fn credentials_environment_variables(&self) -> Vec<(&str, &str)> {
+        vec![
+            (AWS_ACCESS_KEY_ID, self.access_key_id.as_str()),
+            (AWS_SECRET_ACCESS_KEY, self.secret_access_key.as_str()),
+        ]
+    }
+
     fn create_bucket(&self, _bucket_name: &str) -> Result<(), EngineError> {
         unimplemented!()
     }
 
-    fn delete_bucket(&self, _bucket_name: &str) -> Result<(), EngineError> {
-        unimplemented!()
+    fn delete_bucket(&self, bucket_name: &str) -> Result<(), EngineError> {
+        cast_simple_error_to_engine_error(
+            self.engine_error_scope(),
+            self.context().execution_id(),
+            crate::cmd::utilities::exec(
+                "aws",
+                vec![
+                    "s3",
+                    "rb",
+                    "--force",
+                    "--endpoint-url",
+                    self.endpoint_url().unwrap().as_str(),
+                    format!("s3://{}", bucket_name).as_str(),
+                ],
+                &self.credentials_environment_variables(),
+            ),
+        )
     }
 
     fn get(&self, bucket_name: &str, object_key: &str, use_cache: bool) -> Result<(StringPath, File), EngineError> {
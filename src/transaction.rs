@@ -1,12 +1,17 @@
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::Mutex;
 use std::thread;
 
-use crate::build_platform::BuildResult;
+use rayon::prelude::*;
+
+use crate::build_platform::{Build, BuildResult, Image};
 use crate::cloud_provider::kubernetes::Kubernetes;
-use crate::cloud_provider::service::{Application, Service};
+use crate::cloud_provider::plan::EnvironmentPlan;
+use crate::cloud_provider::service::{get_service_deployed_image, Application, Service};
 use crate::container_registry::PushResult;
 use crate::engine::Engine;
-use crate::error::EngineError;
+use crate::error::{EngineError, EngineErrorCause, EngineErrorScope};
 use crate::models::{
     Action, Environment, EnvironmentAction, EnvironmentError, ListenersHelper, ProgressInfo, ProgressLevel,
     ProgressScope,
@@ -68,6 +73,7 @@ impl<'a> Transaction<'a> {
             DeploymentOption {
                 force_build: false,
                 force_push: false,
+                max_parallel_builds: DEFAULT_MAX_PARALLEL_BUILDS,
             },
         )
     }
@@ -83,6 +89,16 @@ impl<'a> Transaction<'a> {
         // add build step
         self.steps.push(Step::BuildEnvironment(environment_action, option));
 
+        // create the databases from the ones of the cloned environment before deploying anything else
+        let target_environment = match environment_action {
+            EnvironmentAction::Environment(te) => te,
+            EnvironmentAction::EnvironmentWithFailover(te, _) => te,
+        };
+
+        if target_environment.clone_from_environment_id.is_some() {
+            self.steps.push(Step::CloneEnvironment(kubernetes, environment_action));
+        }
+
         // add deployment step
         self.steps.push(Step::DeployEnvironment(kubernetes, environment_action));
 
@@ -111,6 +127,29 @@ impl<'a> Transaction<'a> {
         Ok(())
     }
 
+    pub fn backup_environment(
+        &mut self,
+        kubernetes: &'a dyn Kubernetes,
+        environment_action: &'a EnvironmentAction,
+    ) -> Result<(), EnvironmentError> {
+        let _ = self.check_environment_action(environment_action)?;
+
+        self.steps.push(Step::BackupEnvironment(kubernetes, environment_action));
+        Ok(())
+    }
+
+    pub fn restore_environment(
+        &mut self,
+        kubernetes: &'a dyn Kubernetes,
+        environment_action: &'a EnvironmentAction,
+    ) -> Result<(), EnvironmentError> {
+        let _ = self.check_environment_action(environment_action)?;
+
+        self.steps
+            .push(Step::RestoreEnvironment(kubernetes, environment_action));
+        Ok(())
+    }
+
     fn check_environment_action(&self, environment_action: &EnvironmentAction) -> Result<(), EnvironmentError> {
         match environment_action {
             EnvironmentAction::Environment(te) => match te.is_valid() {
@@ -138,67 +177,97 @@ impl<'a> Transaction<'a> {
         environment: &Environment,
         option: &DeploymentOption,
     ) -> Result<Vec<Box<dyn Application>>, EngineError> {
+        let build_platform = self.engine.build_platform();
+        let container_registry = self.engine.container_registry();
+
+        let build = |mut build: Build, image: Image, is_task_canceled: &dyn Fn() -> bool| {
+            if option.force_build || !container_registry.does_image_exists(&image) {
+                // the build cache is stored next to the image, the build goes on without it if the registry can't hold it
+                build.options.cache_reference = match container_registry.build_cache_reference(&image) {
+                    Ok(cache_reference) => Some(cache_reference),
+                    Err(err) => {
+                        warn!("build cache of image {:?} is not available: {:?}", image, err);
+                        None
+                    }
+                };
+
+                if build_platform.is_image_pushed_by_build() {
+                    build.options.push_destination = Some(container_registry.push_destination(&image)?);
+                }
+
+                // only if the build is forced OR if the image does not exist in the registry
+                build_platform.build(build, option.force_build, is_task_canceled)
+            } else {
+                // use the cache
+                Ok(BuildResult::new(build))
+            }
+        };
+
+        // cleanups of the build platform run once here, not while the parallel builds below use it
+        build_platform.prepare_builds();
+
+        // build only the services that are set with Action: Create
         let external_services_to_build = environment
             .external_services
             .iter()
-            // build only applications that are set with Action: Create
-            .filter(|es| es.action == Action::Create);
-
-        let external_service_and_result_tuples = external_services_to_build
-            .map(|es| {
-                let image = es.to_image();
-                let build_result = if option.force_build || !self.engine.container_registry().does_image_exists(&image)
-                {
-                    // only if the build is forced OR if the image does not exist in the registry
-                    self.engine.build_platform().build(es.to_build(), option.force_build)
-                } else {
-                    // use the cache
-                    Ok(BuildResult::new(es.to_build()))
-                };
-
-                (es, build_result)
-            })
+            .filter(|es| es.action == Action::Create)
             .collect::<Vec<_>>();
 
-        // do the same for applications
         let apps_to_build = environment
             .applications
             .iter()
-            // build only applications that are set with Action: Create
-            .filter(|app| app.action == Action::Create);
+            .filter(|app| app.action == Action::Create)
+            .collect::<Vec<_>>();
 
-        let application_and_result_tuples = apps_to_build
-            .map(|app| {
-                let image = app.to_image();
-                let build_result = if option.force_build || !self.engine.container_registry().does_image_exists(&image)
-                {
-                    // only if the build is forced OR if the image does not exist in the registry
-                    self.engine.build_platform().build(app.to_build(), option.force_build)
-                } else {
-                    // use the cache
-                    Ok(BuildResult::new(app.to_build()))
-                };
+        let jobs_to_build = environment
+            .jobs
+            .iter()
+            .filter(|job| job.action == Action::Create)
+            .collect::<Vec<_>>();
 
-                (app, build_result)
-            })
+        // every build shares the same parallel builds, a long one does not hold up the others
+        let builds = external_services_to_build
+            .iter()
+            .map(|es| ("external_service", es.id.as_str(), es.to_build(), es.to_image()))
+            .chain(
+                apps_to_build
+                    .iter()
+                    .map(|app| ("application", app.id.as_str(), app.to_build(), app.to_image())),
+            )
+            .chain(
+                jobs_to_build
+                    .iter()
+                    .map(|job| ("job", job.id.as_str(), job.to_build(), job.to_image())),
+            )
             .collect::<Vec<_>>();
 
-        let mut applications: Vec<Box<dyn Application>> = Vec::with_capacity(application_and_result_tuples.len());
+        let build_results = run_in_parallel(
+            self.engine,
+            builds,
+            option.max_parallel_builds,
+            |(kind, id, to_build, image), is_task_canceled| {
+                build(to_build, image, is_task_canceled).map_err(|err| {
+                    error!("build error for {} {}: {:?}", kind, id, err);
+                    err
+                })
+            },
+        )?;
 
-        for (external_service, result) in external_service_and_result_tuples {
-            // catch build error, can't do it in Fn
-            let build_result = match result {
-                Err(err) => {
-                    error!(
-                        "build error for external_service {}: {:?}",
-                        external_service.id.as_str(),
-                        err
-                    );
-                    return Err(err);
-                }
-                Ok(build_result) => build_result,
-            };
+        // the results come in the order of the builds
+        let mut build_results = build_results.into_iter();
+        let external_service_build_results = build_results
+            .by_ref()
+            .take(external_services_to_build.len())
+            .collect::<Vec<_>>();
+        let application_build_results = build_results.by_ref().take(apps_to_build.len()).collect::<Vec<_>>();
+        let job_build_results = build_results.collect::<Vec<_>>();
+
+        let mut applications: Vec<Box<dyn Application>> = Vec::with_capacity(application_build_results.len());
 
+        for (external_service, build_result) in external_services_to_build
+            .into_iter()
+            .zip(external_service_build_results)
+        {
             match external_service.to_application(
                 self.engine.context(),
                 &build_result.build.image,
@@ -209,16 +278,7 @@ impl<'a> Transaction<'a> {
             }
         }
 
-        for (application, result) in application_and_result_tuples {
-            // catch build error, can't do it in Fn
-            let build_result = match result {
-                Err(err) => {
-                    error!("build error for application {}: {:?}", application.id.as_str(), err);
-                    return Err(err);
-                }
-                Ok(build_result) => build_result,
-            };
-
+        for (application, build_result) in apps_to_build.into_iter().zip(application_build_results) {
             if let Some(app) = application.to_application(
                 self.engine.context(),
                 &build_result.build.image,
@@ -228,6 +288,16 @@ impl<'a> Transaction<'a> {
             }
         }
 
+        for (job, build_result) in jobs_to_build.into_iter().zip(job_build_results) {
+            if let Some(app) = job.to_application(
+                self.engine.context(),
+                &build_result.build.image,
+                self.engine.cloud_provider(),
+            ) {
+                applications.push(app)
+            }
+        }
+
         Ok(applications)
     }
 
@@ -236,32 +306,34 @@ impl<'a> Transaction<'a> {
         applications: Vec<Box<dyn Application>>,
         option: &DeploymentOption,
     ) -> Result<Vec<(Box<dyn Application>, PushResult)>, EngineError> {
-        let application_and_push_results: Vec<_> = applications
-            .into_iter()
-            .map(|mut app| {
-                match self.engine.container_registry().push(app.image(), option.force_push) {
-                    Ok(push_result) => {
-                        // I am not a big fan of doing that but it's the most effective way
-                        app.set_image(push_result.image.clone());
-                        Ok((app, push_result))
-                    }
-                    Err(err) => Err(err),
-                }
-            })
-            .collect();
-
-        let mut results: Vec<(Box<dyn Application>, PushResult)> = vec![];
-        for result in application_and_push_results.into_iter() {
-            match result {
-                Ok(tuple) => results.push(tuple),
-                Err(err) => {
+        let build_platform = self.engine.build_platform();
+        let container_registry = self.engine.container_registry();
+        let images = applications.iter().map(|app| app.image().clone()).collect::<Vec<_>>();
+
+        // a push can't be interrupted, the remaining ones are not started after a failure
+        let push_results = run_in_parallel(
+            self.engine,
+            images,
+            option.max_parallel_builds,
+            |image, _is_task_canceled| {
+                // an image pushed by its build is already in the registry, only its location is resolved
+                let force_push = option.force_push && !build_platform.is_image_pushed_by_build();
+                container_registry.push(&image, force_push).map_err(|err| {
                     error!("error pushing docker image {:?}", err);
-                    return Err(err);
-                }
-            }
-        }
+                    err
+                })
+            },
+        )?;
 
-        Ok(results)
+        Ok(applications
+            .into_iter()
+            .zip(push_results)
+            .map(|(mut app, push_result)| {
+                // I am not a big fan of doing that but it's the most effective way
+                app.set_image(push_result.image.clone());
+                (app, push_result)
+            })
+            .collect())
     }
 
     fn check_environment(&self, environment: &crate::cloud_provider::environment::Environment) -> TransactionResult {
@@ -279,6 +351,91 @@ impl<'a> Transaction<'a> {
         TransactionResult::Ok
     }
 
+    /// diff of every environment step against what is currently deployed, service by service,
+    /// so the changes can be reviewed before `commit()`. Nothing is built nor deployed,
+    /// and the infrastructure steps are not planned.
+    pub fn plan(&self) -> Result<Vec<EnvironmentPlan>, EngineError> {
+        let mut plans = vec![];
+
+        for step in self.steps.iter() {
+            let (kubernetes, environment_action) = match step {
+                Step::DeployEnvironment(kubernetes, environment_action) => (*kubernetes, *environment_action),
+                Step::PauseEnvironment(kubernetes, environment_action) => (*kubernetes, *environment_action),
+                Step::DeleteEnvironment(kubernetes, environment_action) => (*kubernetes, *environment_action),
+                _ => continue,
+            };
+
+            let target_environment = match environment_action {
+                EnvironmentAction::Environment(te) => te,
+                EnvironmentAction::EnvironmentWithFailover(te, _) => te,
+            };
+
+            let planned_applications = self._planned_applications(kubernetes, target_environment);
+
+            let qe_environment = target_environment.to_qe_environment(
+                self.engine.context(),
+                &planned_applications,
+                kubernetes.cloud_provider(),
+            );
+
+            plans.push(kubernetes.plan_environment(&qe_environment)?);
+        }
+
+        Ok(plans)
+    }
+
+    /// applications are not built while planning: when the tag to deploy is the one already running,
+    /// the deployed image is reused so that the registry url does not show up as a change
+    fn _planned_applications(
+        &self,
+        kubernetes: &dyn Kubernetes,
+        environment: &Environment,
+    ) -> Vec<Box<dyn Application>> {
+        let context = self.engine.context();
+        let cloud_provider = kubernetes.cloud_provider();
+        let qe_environment = environment.to_qe_environment(context, &Vec::with_capacity(0), cloud_provider);
+
+        let external_services = environment
+            .external_services
+            .iter()
+            .filter(|es| es.action == Action::Create)
+            .filter_map(|es| es.to_application(context, &es.to_image(), cloud_provider));
+
+        let applications = environment
+            .applications
+            .iter()
+            .filter(|app| app.action == Action::Create)
+            .filter_map(|app| app.to_application(context, &app.to_image(), cloud_provider));
+
+        let jobs = environment
+            .jobs
+            .iter()
+            .filter(|job| job.action == Action::Create)
+            .filter_map(|job| job.to_application(context, &job.to_image(), cloud_provider));
+
+        external_services
+            .chain(applications)
+            .chain(jobs)
+            .map(|mut application| {
+                match get_service_deployed_image(kubernetes, &qe_environment, application.as_ref()) {
+                    Ok(Some(deployed_image)) if deployed_image.ends_with(&format!(":{}", application.image().tag)) => {
+                        let mut image = application.image().clone();
+                        image.registry_url = Some(deployed_image);
+                        application.set_image(image);
+                    }
+                    Ok(_) => {}
+                    Err(err) => warn!(
+                        "can't get the image deployed for application {}: {:?}",
+                        application.name_with_id(),
+                        err
+                    ),
+                }
+
+                application
+            })
+            .collect()
+    }
+
     pub fn rollback(&self) -> Result<(), RollbackError> {
         for step in self.executed_steps.iter() {
             match step {
@@ -313,6 +470,15 @@ impl<'a> Transaction<'a> {
                 Step::DeleteEnvironment(kubernetes, environment_action) => {
                     self.rollback_environment(*kubernetes, *environment_action)?;
                 }
+                Step::BackupEnvironment(_kubernetes, _environment_action) => {
+                    // nothing to revert, a failed backup job is already cleaned up
+                }
+                Step::RestoreEnvironment(_kubernetes, _environment_action) => {
+                    // nothing to revert, a failed restore job is already cleaned up
+                }
+                Step::CloneEnvironment(_kubernetes, _environment_action) => {
+                    // nothing to revert, the cloned databases are deleted with the environment
+                }
             }
         }
 
@@ -328,8 +494,8 @@ impl<'a> Transaction<'a> {
     ) -> Result<(), RollbackError> {
         let qe_environment = |environment: &Environment| {
             let mut _applications = Vec::with_capacity(
-                // ExternalService impl Application (which is a StatelessService)
-                environment.applications.len() + environment.external_services.len(),
+                // ExternalService and Job impl Application (which is a StatelessService)
+                environment.applications.len() + environment.external_services.len() + environment.jobs.len(),
             );
 
             for application in environment.applications.iter() {
@@ -352,6 +518,14 @@ impl<'a> Transaction<'a> {
                 }
             }
 
+            for job in environment.jobs.iter() {
+                let build = job.to_build();
+
+                if let Some(x) = job.to_application(self.engine.context(), &build.image, self.engine.cloud_provider()) {
+                    _applications.push(x)
+                }
+            }
+
             let qe_environment =
                 environment.to_qe_environment(self.engine.context(), &_applications, self.engine.cloud_provider());
 
@@ -501,6 +675,31 @@ impl<'a> Transaction<'a> {
                         }
                     };
                 }
+                Step::CloneEnvironment(kubernetes, environment_action) => {
+                    // clone the databases, applications are deployed on the next step
+                    let target_environment = match environment_action {
+                        EnvironmentAction::Environment(te) => te,
+                        EnvironmentAction::EnvironmentWithFailover(te, _) => te,
+                    };
+
+                    let qe_environment = target_environment.to_qe_environment(
+                        self.engine.context(),
+                        &Vec::with_capacity(0),
+                        kubernetes.cloud_provider(),
+                    );
+
+                    if let Err(commit_error) = kubernetes.clone_environment(&qe_environment) {
+                        warn!("ROLLBACK STARTED! an error occurred {:?}", commit_error);
+
+                        return match self.rollback() {
+                            Ok(_) => TransactionResult::Rollback(commit_error),
+                            Err(err) => {
+                                error!("ROLLBACK FAILED! fatal error: {:?}", err);
+                                TransactionResult::UnrecoverableError(commit_error, err)
+                            }
+                        };
+                    }
+                }
                 Step::DeployEnvironment(kubernetes, environment_action) => {
                     // deploy complete environment
                     match self.commit_environment(
@@ -546,6 +745,36 @@ impl<'a> Transaction<'a> {
                         }
                     };
                 }
+                Step::BackupEnvironment(kubernetes, environment_action) => {
+                    // backup all the databases and the application storage of the environment
+                    match self.commit_environment(
+                        *kubernetes,
+                        *environment_action,
+                        &applications_by_environment,
+                        |qe_env| kubernetes.backup_environment(qe_env),
+                    ) {
+                        TransactionResult::Ok => {}
+                        err => {
+                            error!("Error while backing up environment: {:?}", err);
+                            return err;
+                        }
+                    };
+                }
+                Step::RestoreEnvironment(kubernetes, environment_action) => {
+                    // restore all the databases and the application storage of the environment having a restore source
+                    match self.commit_environment(
+                        *kubernetes,
+                        *environment_action,
+                        &applications_by_environment,
+                        |qe_env| kubernetes.restore_environment(qe_env),
+                    ) {
+                        TransactionResult::Ok => {}
+                        err => {
+                            error!("Error while restoring environment: {:?}", err);
+                            return err;
+                        }
+                    };
+                }
             };
         }
 
@@ -733,10 +962,77 @@ impl<'a> Transaction<'a> {
     }
 }
 
+/// builds share the resources of the build platform, there is no gain in running too many at the same time
+pub const DEFAULT_MAX_PARALLEL_BUILDS: usize = 4;
+
 #[derive(Clone)]
 pub struct DeploymentOption {
     pub force_build: bool,
     pub force_push: bool,
+    // number of applications built (then pushed) at the same time
+    pub max_parallel_builds: usize,
+}
+
+/// run a task for every item, at most `max_parallel_tasks` at the same time, and return the results in the items order.
+/// the first error cancels the tasks not started yet, and the running ones through the function they are given.
+fn run_in_parallel<T, R, F>(
+    engine: &Engine,
+    items: Vec<T>,
+    max_parallel_tasks: usize,
+    task: F,
+) -> Result<Vec<R>, EngineError>
+where
+    T: Send,
+    R: Send,
+    F: Fn(T, &dyn Fn() -> bool) -> Result<R, EngineError> + Sync,
+{
+    let thread_pool = match rayon::ThreadPoolBuilder::new()
+        .num_threads(max_parallel_tasks.max(1))
+        .build()
+    {
+        Ok(thread_pool) => thread_pool,
+        Err(err) => {
+            return Err(EngineError::new(
+                EngineErrorCause::Internal,
+                EngineErrorScope::Engine,
+                engine.context().execution_id(),
+                Some(format!(
+                    "can't create the thread pool to run tasks in parallel: {:?}",
+                    err
+                )),
+            ))
+        }
+    };
+
+    let is_canceled = AtomicBool::new(false);
+    let first_error = Mutex::new(None);
+
+    let results = thread_pool.install(|| {
+        items
+            .into_par_iter()
+            .map(|item| {
+                if is_canceled.load(AtomicOrdering::SeqCst) {
+                    return None;
+                }
+
+                match task(item, &|| is_canceled.load(AtomicOrdering::SeqCst)) {
+                    Ok(result) => Some(result),
+                    Err(err) => {
+                        // only the error which caused the cancellation is returned
+                        if !is_canceled.swap(true, AtomicOrdering::SeqCst) {
+                            *first_error.lock().unwrap() = Some(err);
+                        }
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+    });
+
+    match first_error.into_inner().unwrap() {
+        Some(err) => Err(err),
+        None => Ok(results.into_iter().flatten().collect()),
+    }
 }
 
 enum Step<'a> {
@@ -748,6 +1044,9 @@ enum Step<'a> {
     DeployEnvironment(&'a dyn Kubernetes, &'a EnvironmentAction),
     PauseEnvironment(&'a dyn Kubernetes, &'a EnvironmentAction),
     DeleteEnvironment(&'a dyn Kubernetes, &'a EnvironmentAction),
+    BackupEnvironment(&'a dyn Kubernetes, &'a EnvironmentAction),
+    RestoreEnvironment(&'a dyn Kubernetes, &'a EnvironmentAction),
+    CloneEnvironment(&'a dyn Kubernetes, &'a EnvironmentAction),
 }
 
 impl<'a> Clone for Step<'a> {
@@ -760,6 +1059,9 @@ impl<'a> Clone for Step<'a> {
             Step::DeployEnvironment(k, e) => Step::DeployEnvironment(*k, *e),
             Step::PauseEnvironment(k, e) => Step::PauseEnvironment(*k, *e),
             Step::DeleteEnvironment(k, e) => Step::DeleteEnvironment(*k, *e),
+            Step::BackupEnvironment(k, e) => Step::BackupEnvironment(*k, *e),
+            Step::RestoreEnvironment(k, e) => Step::RestoreEnvironment(*k, *e),
+            Step::CloneEnvironment(k, e) => Step::CloneEnvironment(*k, *e),
         }
     }
 }
@@ -14,8 +14,8 @@ use qovery_engine::container_registry::ecr::ECR;
 use qovery_engine::dns_provider::DnsProvider;
 use qovery_engine::engine::Engine;
 use qovery_engine::models::{
-    Action, Application, Context, Database, DatabaseKind, Environment, EnvironmentVariable, GitCredentials, Kind,
-    Route, Router, Storage, StorageType,
+    Action, Application, Context, Database, DatabaseKind, Environment, EnvironmentVariable, EnvironmentVariableScope,
+    GitCredentials, Kind, RolloutStrategy, Route, RouteOptions, Router, Storage, StorageType,
 };
 
 use crate::cloudflare::dns_provider_cloudflare;
@@ -335,7 +335,7 @@ pub fn environment_3_apps_3_routers_3_databases(context: &Context, secrets: Func
                 dockerfile_path: Some("Dockerfile".to_string()),
                 root_path: "/".to_string(),
                 action: Action::Create,
-                git_credentials: Some(GitCredentials {
+                git_credentials: Some(GitCredentials::Https {
                     login: "x-access-token".to_string(),
                     access_token: "xxx".to_string(),
                     expired_at: Utc::now(),
@@ -347,27 +347,38 @@ pub fn environment_3_apps_3_routers_3_databases(context: &Context, secrets: Func
                     size_in_gib: 10,
                     mount_point: "/mnt/photos".to_string(),
                     snapshot_retention_in_days: 0,
+                    restore_from_snapshot: None,
                 }],
                 environment_variables: vec![
                     EnvironmentVariable {
                         key: "PG_DBNAME".to_string(),
                         value: database_name.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_HOST".to_string(),
                         value: fqdn.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_PORT".to_string(),
                         value: database_port.clone().to_string(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_USERNAME".to_string(),
                         value: database_username.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_PASSWORD".to_string(),
                         value: database_password.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                 ],
                 branch: "master".to_string(),
@@ -377,6 +388,12 @@ pub fn environment_3_apps_3_routers_3_databases(context: &Context, secrets: Func
                 total_instances: 2,
                 cpu_burst: "100m".to_string(),
                 start_timeout_in_seconds: 60,
+                readiness_probe: None,
+                liveness_probe: None,
+                startup_probe: None,
+                autoscaling: None,
+                rollout_strategy: RolloutStrategy::RollingUpdate,
+                public_access: None,
             },
             Application {
                 id: generate_id(),
@@ -386,7 +403,7 @@ pub fn environment_3_apps_3_routers_3_databases(context: &Context, secrets: Func
                 dockerfile_path: Some("Dockerfile".to_string()),
                 root_path: String::from("/"),
                 action: Action::Create,
-                git_credentials: Some(GitCredentials {
+                git_credentials: Some(GitCredentials::Https {
                     login: "x-access-token".to_string(),
                     access_token: "xxx".to_string(),
                     expired_at: Utc::now(),
@@ -398,27 +415,38 @@ pub fn environment_3_apps_3_routers_3_databases(context: &Context, secrets: Func
                     size_in_gib: 10,
                     mount_point: "/mnt/photos".to_string(),
                     snapshot_retention_in_days: 0,
+                    restore_from_snapshot: None,
                 }],
                 environment_variables: vec![
                     EnvironmentVariable {
                         key: "PG_DBNAME".to_string(),
                         value: database_name_2.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_HOST".to_string(),
                         value: fqdn_2.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_PORT".to_string(),
                         value: database_port.clone().to_string(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_USERNAME".to_string(),
                         value: database_username_2.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_PASSWORD".to_string(),
                         value: database_password.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                 ],
                 branch: "master".to_string(),
@@ -428,6 +456,12 @@ pub fn environment_3_apps_3_routers_3_databases(context: &Context, secrets: Func
                 total_instances: 2,
                 cpu_burst: "100m".to_string(),
                 start_timeout_in_seconds: 60,
+                readiness_probe: None,
+                liveness_probe: None,
+                startup_probe: None,
+                autoscaling: None,
+                rollout_strategy: RolloutStrategy::RollingUpdate,
+                public_access: None,
             },
             Application {
                 id: generate_id(),
@@ -437,7 +471,7 @@ pub fn environment_3_apps_3_routers_3_databases(context: &Context, secrets: Func
                 dockerfile_path: Some(format!("Dockerfile-{}", version_mongo)),
                 action: Action::Create,
                 root_path: String::from("/"),
-                git_credentials: Some(GitCredentials {
+                git_credentials: Some(GitCredentials::Https {
                     login: "x-access-token".to_string(),
                     access_token: "xxx".to_string(),
                     expired_at: Utc::now(),
@@ -449,35 +483,50 @@ pub fn environment_3_apps_3_routers_3_databases(context: &Context, secrets: Func
                     size_in_gib: 10,
                     mount_point: "/mnt/photos".to_string(),
                     snapshot_retention_in_days: 0,
+                    restore_from_snapshot: None,
                 }],
                 environment_variables: vec![
                     EnvironmentVariable {
                         key: "IS_DOCUMENTDB".to_string(),
                         value: "false".to_string(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "QOVERY_DATABASE_TESTING_DATABASE_FQDN".to_string(),
                         value: database_host_mongo.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "QOVERY_DATABASE_MY_DDB_CONNECTION_URI".to_string(),
                         value: database_uri_mongo.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "QOVERY_DATABASE_TESTING_DATABASE_PORT".to_string(),
                         value: database_port_mongo.clone().to_string(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "MONGODB_DBNAME".to_string(),
                         value: database_db_name_mongo.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "QOVERY_DATABASE_TESTING_DATABASE_USERNAME".to_string(),
                         value: database_username_mongo.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "QOVERY_DATABASE_TESTING_DATABASE_PASSWORD".to_string(),
                         value: database_password_mongo.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                 ],
                 branch: "master".to_string(),
@@ -487,6 +536,12 @@ pub fn environment_3_apps_3_routers_3_databases(context: &Context, secrets: Func
                 total_instances: 2,
                 cpu_burst: "100m".to_string(),
                 start_timeout_in_seconds: 60,
+                readiness_probe: None,
+                liveness_probe: None,
+                startup_probe: None,
+                autoscaling: None,
+                rollout_strategy: RolloutStrategy::RollingUpdate,
+                public_access: None,
             },
         ],
         routers: vec![
@@ -500,7 +555,10 @@ pub fn environment_3_apps_3_routers_3_databases(context: &Context, secrets: Func
                 routes: vec![Route {
                     path: "/app1".to_string(),
                     application_name: app_name_1.clone(),
+                    options: RouteOptions::default(),
                 }],
+                whitelist_source_ranges: vec![],
+                basic_auth_users: vec![],
             },
             Router {
                 id: generate_id(),
@@ -512,7 +570,10 @@ pub fn environment_3_apps_3_routers_3_databases(context: &Context, secrets: Func
                 routes: vec![Route {
                     path: "/app2".to_string(),
                     application_name: app_name_2.clone(),
+                    options: RouteOptions::default(),
                 }],
+                whitelist_source_ranges: vec![],
+                basic_auth_users: vec![],
             },
             Router {
                 id: generate_id(),
@@ -524,7 +585,10 @@ pub fn environment_3_apps_3_routers_3_databases(context: &Context, secrets: Func
                 routes: vec![Route {
                     path: "/app3".to_string(),
                     application_name: app_name_3.clone(),
+                    options: RouteOptions::default(),
                 }],
+                whitelist_source_ranges: vec![],
+                basic_auth_users: vec![],
             },
         ],
         databases: vec![
@@ -544,6 +608,8 @@ pub fn environment_3_apps_3_routers_3_databases(context: &Context, secrets: Func
                 disk_size_in_gib: 10,
                 database_instance_type: "db.t2.micro".to_string(),
                 database_disk_type: "gp2".to_string(),
+                restore_from: None,
+                public_access: None,
             },
             Database {
                 kind: DatabaseKind::Postgresql,
@@ -561,6 +627,8 @@ pub fn environment_3_apps_3_routers_3_databases(context: &Context, secrets: Func
                 disk_size_in_gib: 10,
                 database_instance_type: "db.t2.micro".to_string(),
                 database_disk_type: "gp2".to_string(),
+                restore_from: None,
+                public_access: None,
             },
             Database {
                 kind: DatabaseKind::Mongodb,
@@ -578,9 +646,12 @@ pub fn environment_3_apps_3_routers_3_databases(context: &Context, secrets: Func
                 disk_size_in_gib: 10,
                 database_instance_type: "db.t3.medium".to_string(),
                 database_disk_type: "gp2".to_string(),
+                restore_from: None,
+                public_access: None,
             },
         ],
         external_services: vec![],
+        jobs: vec![],
         clone_from_environment_id: None,
     }
 }
@@ -604,7 +675,7 @@ pub fn working_minimal_environment(context: &Context, secrets: FuncTestsSecrets)
             dockerfile_path: Some("Dockerfile".to_string()),
             root_path: String::from("/"),
             action: Action::Create,
-            git_credentials: Some(GitCredentials {
+            git_credentials: Some(GitCredentials::Https {
                 login: "x-access-token".to_string(),
                 access_token: "xxx".to_string(),
                 expired_at: Utc::now(),
@@ -618,6 +689,12 @@ pub fn working_minimal_environment(context: &Context, secrets: FuncTestsSecrets)
             total_instances: 2,
             cpu_burst: "100m".to_string(),
             start_timeout_in_seconds: 60,
+            readiness_probe: None,
+            liveness_probe: None,
+            startup_probe: None,
+            autoscaling: None,
+            rollout_strategy: RolloutStrategy::RollingUpdate,
+            public_access: None,
         }],
         routers: vec![Router {
             id: generate_id(),
@@ -629,10 +706,14 @@ pub fn working_minimal_environment(context: &Context, secrets: FuncTestsSecrets)
             routes: vec![Route {
                 path: "/".to_string(),
                 application_name: format!("{}-{}", "simple-app".to_string(), &suffix),
+                options: RouteOptions::default(),
             }],
+            whitelist_source_ranges: vec![],
+            basic_auth_users: vec![],
         }],
         databases: vec![],
         external_services: vec![],
+        jobs: vec![],
         clone_from_environment_id: None,
     }
 }
@@ -675,6 +756,8 @@ pub fn environnement_2_app_2_routers_1_psql(context: &Context, secrets: FuncTest
             disk_size_in_gib: 10,
             database_instance_type: "db.t2.micro".to_string(),
             database_disk_type: "gp2".to_string(),
+            restore_from: None,
+            public_access: None,
         }],
         applications: vec![
             Application {
@@ -685,7 +768,7 @@ pub fn environnement_2_app_2_routers_1_psql(context: &Context, secrets: FuncTest
                 dockerfile_path: Some("Dockerfile".to_string()),
                 root_path: String::from("/"),
                 action: Action::Create,
-                git_credentials: Some(GitCredentials {
+                git_credentials: Some(GitCredentials::Https {
                     login: "x-access-token".to_string(),
                     access_token: "xxx".to_string(),
                     expired_at: Utc::now(),
@@ -697,27 +780,38 @@ pub fn environnement_2_app_2_routers_1_psql(context: &Context, secrets: FuncTest
                     size_in_gib: 10,
                     mount_point: "/mnt/photos".to_string(),
                     snapshot_retention_in_days: 0,
+                    restore_from_snapshot: None,
                 }],
                 environment_variables: vec![
                     EnvironmentVariable {
                         key: "PG_DBNAME".to_string(),
                         value: database_name.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_HOST".to_string(),
                         value: fqdn.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_PORT".to_string(),
                         value: database_port.clone().to_string(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_USERNAME".to_string(),
                         value: database_username.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_PASSWORD".to_string(),
                         value: database_password.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                 ],
                 branch: "master".to_string(),
@@ -727,6 +821,12 @@ pub fn environnement_2_app_2_routers_1_psql(context: &Context, secrets: FuncTest
                 total_instances: 2,
                 cpu_burst: "100m".to_string(),
                 start_timeout_in_seconds: 60,
+                readiness_probe: None,
+                liveness_probe: None,
+                startup_probe: None,
+                autoscaling: None,
+                rollout_strategy: RolloutStrategy::RollingUpdate,
+                public_access: None,
             },
             Application {
                 id: generate_id(),
@@ -736,7 +836,7 @@ pub fn environnement_2_app_2_routers_1_psql(context: &Context, secrets: FuncTest
                 dockerfile_path: Some("Dockerfile".to_string()),
                 root_path: String::from("/"),
                 action: Action::Create,
-                git_credentials: Some(GitCredentials {
+                git_credentials: Some(GitCredentials::Https {
                     login: "x-access-token".to_string(),
                     access_token: "xxx".to_string(),
                     expired_at: Utc::now(),
@@ -748,27 +848,38 @@ pub fn environnement_2_app_2_routers_1_psql(context: &Context, secrets: FuncTest
                     size_in_gib: 10,
                     mount_point: "/mnt/photos".to_string(),
                     snapshot_retention_in_days: 0,
+                    restore_from_snapshot: None,
                 }],
                 environment_variables: vec![
                     EnvironmentVariable {
                         key: "PG_DBNAME".to_string(),
                         value: database_name.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_HOST".to_string(),
                         value: fqdn.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_PORT".to_string(),
                         value: database_port.clone().to_string(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_USERNAME".to_string(),
                         value: database_username.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_PASSWORD".to_string(),
                         value: database_password.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                 ],
                 branch: "master".to_string(),
@@ -778,6 +889,12 @@ pub fn environnement_2_app_2_routers_1_psql(context: &Context, secrets: FuncTest
                 total_instances: 2,
                 cpu_burst: "100m".to_string(),
                 start_timeout_in_seconds: 60,
+                readiness_probe: None,
+                liveness_probe: None,
+                startup_probe: None,
+                autoscaling: None,
+                rollout_strategy: RolloutStrategy::RollingUpdate,
+                public_access: None,
             },
         ],
         routers: vec![
@@ -791,7 +908,10 @@ pub fn environnement_2_app_2_routers_1_psql(context: &Context, secrets: FuncTest
                 routes: vec![Route {
                     path: "/".to_string(),
                     application_name: application_name1.to_string(),
+                    options: RouteOptions::default(),
                 }],
+                whitelist_source_ranges: vec![],
+                basic_auth_users: vec![],
             },
             Router {
                 id: generate_id(),
@@ -803,11 +923,15 @@ pub fn environnement_2_app_2_routers_1_psql(context: &Context, secrets: FuncTest
                 routes: vec![Route {
                     path: "/coco".to_string(),
                     application_name: application_name2.to_string(),
+                    options: RouteOptions::default(),
                 }],
+                whitelist_source_ranges: vec![],
+                basic_auth_users: vec![],
             },
         ],
 
         external_services: vec![],
+        jobs: vec![],
         clone_from_environment_id: None,
     }
 }
@@ -850,7 +974,7 @@ pub fn echo_app_environment(context: &Context, secrets: FuncTestsSecrets) -> Env
             dockerfile_path: Some("Dockerfile".to_string()),
             root_path: String::from("/"),
             action: Action::Create,
-            git_credentials: Some(GitCredentials {
+            git_credentials: Some(GitCredentials::Https {
                 login: "x-access-token".to_string(),
                 access_token: "xxx".to_string(),
                 expired_at: Utc::now(),
@@ -859,6 +983,8 @@ pub fn echo_app_environment(context: &Context, secrets: FuncTestsSecrets) -> Env
             environment_variables: vec![EnvironmentVariable {
                 key: "ECHO_TEXT".to_string(),
                 value: "42".to_string(),
+                scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                is_secret: false,
             }],
             branch: "echo-app".to_string(),
             private_port: Some(5678),
@@ -867,6 +993,12 @@ pub fn echo_app_environment(context: &Context, secrets: FuncTestsSecrets) -> Env
             total_instances: 2,
             cpu_burst: "100m".to_string(),
             start_timeout_in_seconds: 60,
+            readiness_probe: None,
+            liveness_probe: None,
+            startup_probe: None,
+            autoscaling: None,
+            rollout_strategy: RolloutStrategy::RollingUpdate,
+            public_access: None,
         }],
         routers: vec![Router {
             id: generate_id(),
@@ -878,10 +1010,14 @@ pub fn echo_app_environment(context: &Context, secrets: FuncTestsSecrets) -> Env
             routes: vec![Route {
                 path: "/".to_string(),
                 application_name: format!("{}-{}", "echo-app".to_string(), &suffix),
+                options: RouteOptions::default(),
             }],
+            whitelist_source_ranges: vec![],
+            basic_auth_users: vec![],
         }],
         databases: vec![],
         external_services: vec![],
+        jobs: vec![],
         clone_from_environment_id: None,
     }
 }
@@ -905,7 +1041,7 @@ pub fn environment_only_http_server(context: &Context) -> Environment {
             dockerfile_path: Some("Dockerfile".to_string()),
             root_path: String::from("/"),
             action: Action::Create,
-            git_credentials: Some(GitCredentials {
+            git_credentials: Some(GitCredentials::Https {
                 login: "x-access-token".to_string(),
                 access_token: "xxx".to_string(),
                 expired_at: Utc::now(),
@@ -919,10 +1055,17 @@ pub fn environment_only_http_server(context: &Context) -> Environment {
             total_instances: 2,
             cpu_burst: "100m".to_string(),
             start_timeout_in_seconds: 60,
+            readiness_probe: None,
+            liveness_probe: None,
+            startup_probe: None,
+            autoscaling: None,
+            rollout_strategy: RolloutStrategy::RollingUpdate,
+            public_access: None,
         }],
         routers: vec![],
         databases: vec![],
         external_services: vec![],
+        jobs: vec![],
         clone_from_environment_id: None,
     }
 }
@@ -946,7 +1089,7 @@ pub fn environment_only_http_server_router(context: &Context, secrets: FuncTests
             dockerfile_path: Some("Dockerfile".to_string()),
             root_path: String::from("/"),
             action: Action::Create,
-            git_credentials: Some(GitCredentials {
+            git_credentials: Some(GitCredentials::Https {
                 login: "x-access-token".to_string(),
                 access_token: "xxx".to_string(),
                 expired_at: Utc::now(),
@@ -960,6 +1103,12 @@ pub fn environment_only_http_server_router(context: &Context, secrets: FuncTests
             total_instances: 2,
             cpu_burst: "100m".to_string(),
             start_timeout_in_seconds: 60,
+            readiness_probe: None,
+            liveness_probe: None,
+            startup_probe: None,
+            autoscaling: None,
+            rollout_strategy: RolloutStrategy::RollingUpdate,
+            public_access: None,
         }],
         routers: vec![Router {
             id: generate_id(),
@@ -971,10 +1120,14 @@ pub fn environment_only_http_server_router(context: &Context, secrets: FuncTests
             routes: vec![Route {
                 path: "/".to_string(),
                 application_name: format!("{}-{}", "mini-http".to_string(), &suffix),
+                options: RouteOptions::default(),
             }],
+            whitelist_source_ranges: vec![],
+            basic_auth_users: vec![],
         }],
         databases: vec![],
         external_services: vec![],
+        jobs: vec![],
         clone_from_environment_id: None,
     }
 }
@@ -4,7 +4,8 @@ use test_utilities::utilities::{init, FuncTestsSecrets};
 use tracing::{span, Level};
 
 use qovery_engine::models::{
-    Action, Clone2, Context, Database, DatabaseKind, Environment, EnvironmentAction, EnvironmentVariable, Kind,
+    Action, Clone2, Context, Database, DatabaseKind, Environment, EnvironmentAction, EnvironmentVariable,
+    EnvironmentVariableScope, Kind,
 };
 use qovery_engine::transaction::TransactionResult;
 
@@ -266,6 +267,8 @@ fn postgresql_deploy_a_working_environment_and_redeploy() {
             disk_size_in_gib: 10,
             database_instance_type: "db.t2.micro".to_string(),
             database_disk_type: "gp2".to_string(),
+            restore_from: None,
+            public_access: None,
         }];
         environment.applications = environment
             .applications
@@ -278,22 +281,32 @@ fn postgresql_deploy_a_working_environment_and_redeploy() {
                     EnvironmentVariable {
                         key: "PG_HOST".to_string(),
                         value: database_host.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_PORT".to_string(),
                         value: database_port.clone().to_string(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_DBNAME".to_string(),
                         value: database_db_name.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_USERNAME".to_string(),
                         value: database_username.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_PASSWORD".to_string(),
                         value: database_password.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                 ];
                 app
@@ -382,6 +395,8 @@ fn test_postgresql_configuration(
             disk_size_in_gib: 10,
             database_instance_type: "db.t2.micro".to_string(),
             database_disk_type: "gp2".to_string(),
+            restore_from: None,
+            public_access: None,
         }];
         environment.applications = environment
             .applications
@@ -395,22 +410,32 @@ fn test_postgresql_configuration(
                     EnvironmentVariable {
                         key: "PG_HOST".to_string(),
                         value: database_host.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_PORT".to_string(),
                         value: database_port.clone().to_string(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_DBNAME".to_string(),
                         value: database_db_name.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_USERNAME".to_string(),
                         value: database_username.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "PG_PASSWORD".to_string(),
                         value: database_password.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                 ];
                 app
@@ -586,6 +611,8 @@ fn test_mongodb_configuration(
         disk_size_in_gib: 10,
         database_instance_type: "db.t3.medium".to_string(),
         database_disk_type: "gp2".to_string(),
+        restore_from: None,
+        public_access: None,
     }];
     environment.applications = environment
         .applications
@@ -607,30 +634,44 @@ fn test_mongodb_configuration(
                 EnvironmentVariable {
                     key: "IS_DOCUMENTDB".to_string(),
                     value: is_documentdb.to_string(),
+                    scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                    is_secret: false,
                 },
                 EnvironmentVariable {
                     key: "QOVERY_DATABASE_TESTING_DATABASE_FQDN".to_string(),
                     value: database_host.clone(),
+                    scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                    is_secret: false,
                 },
                 EnvironmentVariable {
                     key: "QOVERY_DATABASE_MY_DDB_CONNECTION_URI".to_string(),
                     value: database_uri.clone(),
+                    scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                    is_secret: false,
                 },
                 EnvironmentVariable {
                     key: "QOVERY_DATABASE_TESTING_DATABASE_PORT".to_string(),
                     value: database_port.clone().to_string(),
+                    scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                    is_secret: false,
                 },
                 EnvironmentVariable {
                     key: "MONGODB_DBNAME".to_string(),
                     value: database_db_name.clone(),
+                    scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                    is_secret: false,
                 },
                 EnvironmentVariable {
                     key: "QOVERY_DATABASE_TESTING_DATABASE_USERNAME".to_string(),
                     value: database_username.clone(),
+                    scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                    is_secret: false,
                 },
                 EnvironmentVariable {
                     key: "QOVERY_DATABASE_TESTING_DATABASE_PASSWORD".to_string(),
                     value: database_password.clone(),
+                    scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                    is_secret: false,
                 },
             ];
             app
@@ -800,6 +841,8 @@ fn test_mysql_configuration(
             disk_size_in_gib: 10,
             database_instance_type: "db.t2.micro".to_string(),
             database_disk_type: "gp2".to_string(),
+            restore_from: None,
+            public_access: None,
         }];
         environment.applications = environment
             .applications
@@ -821,22 +864,32 @@ fn test_mysql_configuration(
                     EnvironmentVariable {
                         key: "MYSQL_HOST".to_string(),
                         value: database_host.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "MYSQL_PORT".to_string(),
                         value: database_port.clone().to_string(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "MYSQL_DBNAME".to_string(),
                         value: database_db_name.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "MYSQL_USERNAME".to_string(),
                         value: database_username.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "MYSQL_PASSWORD".to_string(),
                         value: database_password.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                 ];
                 app
@@ -978,6 +1031,8 @@ fn test_redis_configuration(
             disk_size_in_gib: 10,
             database_instance_type: "cache.t3.micro".to_string(),
             database_disk_type: "gp2".to_string(),
+            restore_from: None,
+            public_access: None,
         }];
         environment.applications = environment
             .applications
@@ -1000,22 +1055,32 @@ fn test_redis_configuration(
                     EnvironmentVariable {
                         key: "IS_ELASTICCACHE".to_string(),
                         value: is_elasticache.to_string(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "REDIS_HOST".to_string(),
                         value: database_host.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "REDIS_PORT".to_string(),
                         value: database_port.clone().to_string(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "REDIS_USERNAME".to_string(),
                         value: database_username.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                     EnvironmentVariable {
                         key: "REDIS_PASSWORD".to_string(),
                         value: database_password.clone(),
+                        scope: Some(EnvironmentVariableScope::BuildAndRuntime),
+                        is_secret: false,
                     },
                 ];
                 app
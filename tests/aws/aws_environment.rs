@@ -2,10 +2,10 @@ extern crate test_utilities;
 
 use self::test_utilities::cloudflare::dns_provider_cloudflare;
 use self::test_utilities::utilities::{
-    engine_run_test, generate_id, get_pods_aws, is_pod_restarted_aws_env, FuncTestsSecrets,
+    engine_run_test, generate_id, get_pods_aws, get_volume_snapshots_aws, is_pod_restarted_aws_env, FuncTestsSecrets,
 };
 use qovery_engine::models::{Action, Clone2, Context, EnvironmentAction, Storage, StorageType};
-use qovery_engine::transaction::{DeploymentOption, TransactionResult};
+use qovery_engine::transaction::{DeploymentOption, TransactionResult, DEFAULT_MAX_PARALLEL_BUILDS};
 use test_utilities::utilities::context;
 use test_utilities::utilities::init;
 use tracing::{span, Level};
@@ -30,6 +30,7 @@ pub fn deploy_environment(context: &Context, environment_action: &EnvironmentAct
         DeploymentOption {
             force_build: true,
             force_push: true,
+            max_parallel_builds: DEFAULT_MAX_PARALLEL_BUILDS,
         },
     );
 
@@ -66,6 +67,36 @@ pub fn delete_environment(context: &Context, environment_action: &EnvironmentAct
     tx.commit()
 }
 
+pub fn backup_environment(context: &Context, environment_action: &EnvironmentAction) -> TransactionResult {
+    let engine = test_utilities::aws::docker_ecr_aws_engine(&context);
+    let session = engine.session().unwrap();
+    let mut tx = session.transaction();
+
+    let cp = test_utilities::aws::cloud_provider_aws(&context);
+    let nodes = test_utilities::aws::aws_kubernetes_nodes();
+    let dns_provider = dns_provider_cloudflare(context);
+    let k = test_utilities::aws::aws_kubernetes_eks(&context, &cp, &dns_provider, nodes);
+
+    let _ = tx.backup_environment(&k, &environment_action);
+
+    tx.commit()
+}
+
+pub fn restore_environment(context: &Context, environment_action: &EnvironmentAction) -> TransactionResult {
+    let engine = test_utilities::aws::docker_ecr_aws_engine(&context);
+    let session = engine.session().unwrap();
+    let mut tx = session.transaction();
+
+    let cp = test_utilities::aws::cloud_provider_aws(&context);
+    let nodes = test_utilities::aws::aws_kubernetes_nodes();
+    let dns_provider = dns_provider_cloudflare(context);
+    let k = test_utilities::aws::aws_kubernetes_eks(&context, &cp, &dns_provider, nodes);
+
+    let _ = tx.restore_environment(&k, &environment_action);
+
+    tx.commit()
+}
+
 #[cfg(feature = "test-aws-self-hosted")]
 #[test]
 fn deploy_a_working_environment_with_no_router_on_aws_eks() {
@@ -359,6 +390,7 @@ fn deploy_a_working_environment_with_storage_on_aws_eks() {
                     size_in_gib: 10,
                     mount_point: "/mnt/photos".to_string(),
                     snapshot_retention_in_days: 0,
+                    restore_from_snapshot: None,
                 }];
                 app
             })
@@ -387,6 +419,95 @@ fn deploy_a_working_environment_with_storage_on_aws_eks() {
     })
 }
 
+#[cfg(feature = "test-aws-self-hosted")]
+#[test]
+fn backup_and_restore_application_storage_on_aws_eks() {
+    engine_run_test(|| {
+        let span = span!(
+            Level::INFO,
+            "test",
+            name = "backup_and_restore_application_storage_on_aws_eks"
+        );
+        let _enter = span.enter();
+
+        let context = context();
+        let context_for_backup = context.clone_not_same_execution_id();
+        let context_for_restore = context.clone_not_same_execution_id();
+        let context_for_deletion = context.clone_not_same_execution_id();
+        let secrets = FuncTestsSecrets::new();
+
+        let mut environment = test_utilities::aws::working_minimal_environment(&context, secrets.clone());
+        let storage_id = generate_id();
+
+        environment.applications = environment
+            .applications
+            .into_iter()
+            .map(|mut app| {
+                app.storage = vec![Storage {
+                    id: storage_id.clone(),
+                    name: "photos".to_string(),
+                    storage_type: StorageType::Ssd,
+                    size_in_gib: 10,
+                    mount_point: "/mnt/photos".to_string(),
+                    snapshot_retention_in_days: 0,
+                    restore_from_snapshot: None,
+                }];
+                app
+            })
+            .collect::<Vec<qovery_engine::models::Application>>();
+
+        let environment_check = environment.clone();
+        let mut environment_delete = environment.clone();
+        environment_delete.action = Action::Delete;
+
+        let ea = EnvironmentAction::Environment(environment.clone());
+        let ea_delete = EnvironmentAction::Environment(environment_delete);
+
+        match deploy_environment(&context, &ea) {
+            TransactionResult::Ok => assert!(true),
+            TransactionResult::Rollback(_) => assert!(false),
+            TransactionResult::UnrecoverableError(_, _) => assert!(false),
+        };
+
+        match backup_environment(&context_for_backup, &ea) {
+            TransactionResult::Ok => assert!(true),
+            TransactionResult::Rollback(_) => assert!(false),
+            TransactionResult::UnrecoverableError(_, _) => assert!(false),
+        };
+
+        // the storage of the application has been snapshotted along with the databases
+        let snapshots = get_volume_snapshots_aws(
+            environment_check,
+            format!("diskId={}", storage_id).as_str(),
+            secrets.clone(),
+        )
+        .unwrap();
+        assert!(!snapshots.items.is_empty());
+
+        let snapshot_name = snapshots.items[0].metadata.name.clone();
+        let mut environment_restore = environment;
+        for app in environment_restore.applications.iter_mut() {
+            app.storage[0].restore_from_snapshot = Some(snapshot_name.clone());
+        }
+
+        match restore_environment(
+            &context_for_restore,
+            &EnvironmentAction::Environment(environment_restore),
+        ) {
+            TransactionResult::Ok => assert!(true),
+            TransactionResult::Rollback(_) => assert!(false),
+            TransactionResult::UnrecoverableError(_, _) => assert!(false),
+        };
+
+        match delete_environment(&context_for_deletion, &ea_delete) {
+            TransactionResult::Ok => assert!(true),
+            TransactionResult::Rollback(_) => assert!(false),
+            TransactionResult::UnrecoverableError(_, _) => assert!(false),
+        };
+        return "backup_and_restore_application_storage_on_aws_eks".to_string();
+    })
+}
+
 // to check if app redeploy or not, it shouldn't
 #[cfg(feature = "test-aws-self-hosted")]
 #[test]
@@ -414,6 +535,7 @@ fn redeploy_same_app_with_ebs() {
                     size_in_gib: 10,
                     mount_point: "/mnt/photos".to_string(),
                     snapshot_retention_in_days: 0,
+                    restore_from_snapshot: None,
                 }];
                 app
             })
@@ -22,6 +22,7 @@
 //         DeploymentOption {
 //             force_build: true,
 //             force_push: true,
+//             max_parallel_builds: DEFAULT_MAX_PARALLEL_BUILDS,
 //         },
 //     );
 //